@@ -0,0 +1,91 @@
+//! Cipher block chaining mode (NIST SP 800-38A 6.2) with PKCS#7 padding (RFC 5652 6.3).
+
+use crypto_bigint::subtle::{ConstantTimeEq, ConstantTimeGreater};
+
+use crate::error::{Error, Result};
+
+use super::{check_block_len, BlockCipher, BLOCK_LEN};
+
+
+/// Pads the message in the first `message_len` bytes of `buffer` and encrypts it in place,
+/// returns the ciphertext.
+///
+/// `buffer` needs room for the padding, which is 1 to 16 bytes so that the ciphertext
+/// is the message length rounded up to the next full block.
+pub fn encrypt<'a, C: BlockCipher>(
+    cipher: &mut C,
+    iv: &[u8; BLOCK_LEN],
+    buffer: &'a mut [u8],
+    message_len: usize
+) -> Result<&'a [u8]> {
+    let padding_len = BLOCK_LEN - message_len % BLOCK_LEN;
+    let ciphertext_len = message_len + padding_len;
+    if buffer.len() < ciphertext_len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    buffer[message_len..ciphertext_len].fill(padding_len as u8);
+    encrypt_blocks(cipher, iv, &mut buffer[..ciphertext_len])?;
+
+    Ok(&buffer[..ciphertext_len])
+}
+
+/// Decrypts `buffer` in place and removes the padding, returns the message.
+///
+/// Invalid padding, which is also what a wrong key looks like, fails with `Error::Decryption`.
+pub fn decrypt<'a, C: BlockCipher>(cipher: &mut C, iv: &[u8; BLOCK_LEN], buffer: &'a mut [u8]) -> Result<&'a [u8]> {
+    if buffer.is_empty() {
+        return Err(Error::InvalidBlockSize);
+    }
+    decrypt_blocks(cipher, iv, buffer)?;
+
+    // The padding is checked in constant time over the whole last block
+    let last_block = &buffer[buffer.len() - BLOCK_LEN..];
+    let padding_len = last_block[BLOCK_LEN - 1];
+    let mut valid = padding_len.ct_gt(&0) & !padding_len.ct_gt(&(BLOCK_LEN as u8));
+    for (i, b) in last_block.iter().enumerate() {
+        let in_padding = !((BLOCK_LEN - i) as u8).ct_gt(&padding_len);
+        valid &= !in_padding | b.ct_eq(&padding_len);
+    }
+
+    if !bool::from(valid) {
+        return Err(Error::Decryption);
+    }
+
+    Ok(&buffer[..buffer.len() - padding_len as usize])
+}
+
+/// Encrypts `data` in place without padding, `data` has to be a multiple of the block length.
+pub fn encrypt_blocks<C: BlockCipher>(cipher: &mut C, iv: &[u8; BLOCK_LEN], data: &mut [u8]) -> Result<()> {
+    check_block_len(data.len())?;
+
+    let mut previous = *iv;
+    for block in data.chunks_exact_mut(BLOCK_LEN) {
+        let block: &mut [u8; BLOCK_LEN] = block.try_into().unwrap();
+        for (b, p) in block.iter_mut().zip(previous.iter()) {
+            *b ^= p;
+        }
+        cipher.encrypt_block(block);
+        previous = *block;
+    }
+
+    Ok(())
+}
+
+/// Decrypts `data` in place without removing padding, `data` has to be a multiple of the block length.
+pub fn decrypt_blocks<C: BlockCipher>(cipher: &mut C, iv: &[u8; BLOCK_LEN], data: &mut [u8]) -> Result<()> {
+    check_block_len(data.len())?;
+
+    let mut previous = *iv;
+    for block in data.chunks_exact_mut(BLOCK_LEN) {
+        let block: &mut [u8; BLOCK_LEN] = block.try_into().unwrap();
+        let ciphertext = *block;
+        cipher.decrypt_block(block);
+        for (b, p) in block.iter_mut().zip(previous.iter()) {
+            *b ^= p;
+        }
+        previous = ciphertext;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Counter with CBC-MAC mode (NIST SP 800-38C, RFC 3610), also covers CCM* of IEEE 802.15.4
+//! with 4, 8 or 16 byte tags.
+
+use crypto_bigint::subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+
+use super::{BlockCipher, BLOCK_LEN};
+
+
+/// Length of the biggest tag.
+pub const MAX_TAG_LEN: usize = 16;
+
+
+/// AES-CCM authenticated encryption with associated data.
+///
+/// The tag length `t` is one of 4, 6, 8, 10, 12, 14 or 16 bytes. The length field `L` holds the message
+/// length and is 2 to 8 bytes, the nonce is `15 - L` bytes: 13 byte nonces with `L = 2` (RFC 3610, 802.15.4)
+/// allow messages up to 64 KiB. A nonce must never be used twice with the same key.
+pub struct AesCcm<C: BlockCipher> {
+    cipher: C,
+    tag_len: usize,
+    length_len: usize,
+}
+
+impl<C: BlockCipher> AesCcm<C> {
+    /// Fails with `Error::InvalidLength` for unsupported tag or length field sizes.
+    pub fn new(cipher: C, tag_len: usize, length_len: usize) -> Result<Self> {
+        if !(4..=MAX_TAG_LEN).contains(&tag_len) || tag_len % 2 != 0 || !(2..=8).contains(&length_len) {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(Self { cipher, tag_len, length_len })
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Length of the nonce, `15 - L`.
+    pub fn nonce_len(&self) -> usize {
+        BLOCK_LEN - 1 - self.length_len
+    }
+
+    /// Encrypts `plaintext` to `ciphertext_buffer`, returns the ciphertext followed by the tag.
+    pub fn encrypt<'a>(
+        &mut self,
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+        ciphertext_buffer: &'a mut [u8]
+    ) -> Result<&'a [u8]> {
+        self.check_lengths(nonce, plaintext.len())?;
+
+        let len = plaintext.len() + self.tag_len;
+        if ciphertext_buffer.len() < len {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let mut tag = self.mac(nonce, aad, plaintext);
+
+        let (ciphertext, tag_out) = ciphertext_buffer[..len].split_at_mut(plaintext.len());
+        ciphertext.copy_from_slice(plaintext);
+        self.apply_keystream(nonce, &mut tag, ciphertext);
+        tag_out.copy_from_slice(&tag[..self.tag_len]);
+        tag.zeroize();
+
+        Ok(&ciphertext_buffer[..len])
+    }
+
+    /// Decrypts the ciphertext followed by the tag to `plaintext_buffer` and checks the tag,
+    /// returns the plaintext.
+    ///
+    /// A wrong tag, key, nonce or associated data fails with `Error::Decryption`, `plaintext_buffer`
+    /// is cleared in that case.
+    pub fn decrypt<'a>(
+        &mut self,
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+        plaintext_buffer: &'a mut [u8]
+    ) -> Result<&'a [u8]> {
+        if ciphertext.len() < self.tag_len {
+            return Err(Error::Decryption);
+        }
+        let plaintext_len = ciphertext.len() - self.tag_len;
+        self.check_lengths(nonce, plaintext_len)?;
+
+        if plaintext_buffer.len() < plaintext_len {
+            return Err(Error::BufferTooSmall);
+        }
+
+        // The tag is encrypted with the first block of the keystream
+        let (ciphertext, received_tag) = ciphertext.split_at(plaintext_len);
+        let mut s0 = [0u8; BLOCK_LEN];
+        let plaintext = &mut plaintext_buffer[..plaintext_len];
+        plaintext.copy_from_slice(ciphertext);
+        self.apply_keystream(nonce, &mut s0, plaintext);
+
+        let mut tag = self.mac(nonce, aad, plaintext);
+        for (t, s) in tag.iter_mut().zip(s0.iter()) {
+            *t ^= s;
+        }
+        let valid = tag[..self.tag_len].ct_eq(received_tag);
+        tag.zeroize();
+        s0.zeroize();
+
+        if !bool::from(valid) {
+            plaintext.zeroize();
+            return Err(Error::Decryption);
+        }
+
+        Ok(&plaintext_buffer[..plaintext_len])
+    }
+
+    fn check_lengths(&self, nonce: &[u8], message_len: usize) -> Result<()> {
+        if nonce.len() != self.nonce_len() {
+            return Err(Error::InvalidLength);
+        }
+        if self.length_len < 8 && message_len as u64 >= 1 << (8 * self.length_len) {
+            return Err(Error::MessageTooLong);
+        }
+        Ok(())
+    }
+
+    /// Counter block `Ctr_i = flags || N || [i]_L` (SP 800-38C A.3).
+    fn counter_block(&self, nonce: &[u8], i: u64) -> [u8; BLOCK_LEN] {
+        let mut block = [0u8; BLOCK_LEN];
+        block[0] = (self.length_len - 1) as u8;
+        block[1..1 + nonce.len()].copy_from_slice(nonce);
+        block[1 + nonce.len()..].copy_from_slice(&i.to_be_bytes()[8 - self.length_len..]);
+        block
+    }
+
+    /// XORs `tag` with the keystream block of counter 0 and `data` with the blocks from counter 1 on.
+    fn apply_keystream(&mut self, nonce: &[u8], tag: &mut [u8; BLOCK_LEN], data: &mut [u8]) {
+        let mut keystream = self.counter_block(nonce, 0);
+        self.cipher.encrypt_block(&mut keystream);
+        for (t, k) in tag.iter_mut().zip(keystream.iter()) {
+            *t ^= k;
+        }
+
+        for (i, chunk) in data.chunks_mut(BLOCK_LEN).enumerate() {
+            keystream = self.counter_block(nonce, i as u64 + 1);
+            self.cipher.encrypt_block(&mut keystream);
+            for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+                *b ^= k;
+            }
+        }
+        keystream.zeroize();
+    }
+
+    /// CBC-MAC over B0, the encoded associated data and the plaintext (SP 800-38C A.2), the tag before encryption.
+    fn mac(&mut self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> [u8; BLOCK_LEN] {
+        let mut b0 = self.counter_block(nonce, plaintext.len() as u64);
+        b0[0] = (((self.tag_len - 2) / 2) << 3) as u8 | (self.length_len - 1) as u8;
+        if !aad.is_empty() {
+            b0[0] |= 0x40;
+        }
+
+        let mut mac = CbcMac::new();
+        mac.update(&mut self.cipher, &b0);
+
+        if !aad.is_empty() {
+            // The length of the associated data is encoded in 2, 6 or 10 bytes
+            let aad_len = aad.len() as u64;
+            if aad_len < (1 << 16) - (1 << 8) {
+                mac.update(&mut self.cipher, &(aad_len as u16).to_be_bytes());
+            } else if aad_len < 1 << 32 {
+                mac.update(&mut self.cipher, &[0xff, 0xfe]);
+                mac.update(&mut self.cipher, &(aad_len as u32).to_be_bytes());
+            } else {
+                mac.update(&mut self.cipher, &[0xff, 0xff]);
+                mac.update(&mut self.cipher, &aad_len.to_be_bytes());
+            }
+            mac.update(&mut self.cipher, aad);
+            mac.pad(&mut self.cipher);
+        }
+
+        mac.update(&mut self.cipher, plaintext);
+        mac.pad(&mut self.cipher);
+
+        mac.state
+    }
+}
+
+
+/// CBC-MAC with a zero IV over data passed in pieces.
+struct CbcMac {
+    state: [u8; BLOCK_LEN],
+    pos: usize,
+}
+
+impl CbcMac {
+    fn new() -> Self {
+        Self { state: [0u8; BLOCK_LEN], pos: 0 }
+    }
+
+    fn update<C: BlockCipher>(&mut self, cipher: &mut C, data: &[u8]) {
+        for b in data {
+            self.state[self.pos] ^= b;
+            self.pos += 1;
+            if self.pos == BLOCK_LEN {
+                cipher.encrypt_block(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Fills the current block with zeros.
+    fn pad<C: BlockCipher>(&mut self, cipher: &mut C) {
+        if self.pos != 0 {
+            cipher.encrypt_block(&mut self.state);
+            self.pos = 0;
+        }
+    }
+}
+
+impl Drop for CbcMac {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
@@ -0,0 +1,33 @@
+//! Counter mode (NIST SP 800-38A 6.5).
+//!
+//! Encryption and decryption are the same operation, the data has no length restriction.
+//! A counter block must never be used twice with the same key.
+
+use zeroize::Zeroize;
+
+use super::{BlockCipher, BLOCK_LEN};
+
+
+/// XORs `data` in place with the keystream starting at `counter`.
+///
+/// The whole counter block is incremented as a big endian integer (SP 800-38A B.1), after the call
+/// it holds the counter of the next block, so a stream can be continued with the next call as long as
+/// all previous calls were a multiple of the block length.
+pub fn apply_keystream<C: BlockCipher>(cipher: &mut C, counter: &mut [u8; BLOCK_LEN], data: &mut [u8]) {
+    let mut keystream = [0u8; BLOCK_LEN];
+    for chunk in data.chunks_mut(BLOCK_LEN) {
+        keystream.copy_from_slice(counter);
+        cipher.encrypt_block(&mut keystream);
+        for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+            *b ^= k;
+        }
+        increment(counter);
+    }
+    keystream.zeroize();
+}
+
+/// Adds one to `counter` as a big endian integer, wrapping around at the top.
+fn increment(counter: &mut [u8; BLOCK_LEN]) {
+    let value = u128::from_be_bytes(*counter).wrapping_add(1);
+    *counter = value.to_be_bytes();
+}
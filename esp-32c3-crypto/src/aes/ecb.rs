@@ -0,0 +1,27 @@
+//! Electronic codebook mode (NIST SP 800-38A 6.1).
+//!
+//! Equal plaintext blocks give equal ciphertext blocks, only use it for single blocks of random data
+//! or to build other modes.
+
+use crate::error::Result;
+
+use super::{check_block_len, BlockCipher, BLOCK_LEN};
+
+
+/// Encrypts `data` in place, `data` has to be a multiple of the block length.
+pub fn encrypt<C: BlockCipher>(cipher: &mut C, data: &mut [u8]) -> Result<()> {
+    check_block_len(data.len())?;
+    for block in data.chunks_exact_mut(BLOCK_LEN) {
+        cipher.encrypt_block(block.try_into().unwrap());
+    }
+    Ok(())
+}
+
+/// Decrypts `data` in place, `data` has to be a multiple of the block length.
+pub fn decrypt<C: BlockCipher>(cipher: &mut C, data: &mut [u8]) -> Result<()> {
+    check_block_len(data.len())?;
+    for block in data.chunks_exact_mut(BLOCK_LEN) {
+        cipher.decrypt_block(block.try_into().unwrap());
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use esp_hal::aes::{Aes, Mode};
+use esp_hal::peripherals::AES;
+
+use crate::error::Result;
+
+use super::{AesKey, BlockCipher, BLOCK_LEN};
+
+
+/// AES on the AES peripheral of the ESP32-C3. The key is written to the peripheral for every block
+/// straight from `AesKey`, and the key registers are cleared on drop.
+pub struct Esp32C3Aes<'a, 'd> {
+    aes: &'a mut Aes<'d>,
+    key: AesKey,
+}
+
+impl<'a, 'd> Esp32C3Aes<'a, 'd> {
+    /// `key` has to be 16 bytes for AES-128 or 32 bytes for AES-256.
+    pub fn new(aes: &'a mut Aes<'d>, key: &[u8]) -> Result<Self> {
+        Ok(Self { aes, key: AesKey::new(key)? })
+    }
+
+    pub fn from_key(aes: &'a mut Aes<'d>, key: AesKey) -> Self {
+        Self { aes, key }
+    }
+
+    /// Runs one block in `mode` (encryption or decryption), the key size is added from the key.
+    fn process(&mut self, block: &mut [u8; BLOCK_LEN], mode: u32) {
+        let (key, key_mode) = match &self.key {
+            AesKey::Aes128(key) => (&key[..], Mode::Encryption128 as u32),
+            AesKey::Aes256(key) => (&key[..], Mode::Encryption256 as u32),
+        };
+
+        let registers = registers(self.aes);
+        // `Aes::process` takes the key by value and leaves copies of it behind, so the words
+        // are moved into the registers from the borrowed key instead.
+        for (i, word) in key.chunks_exact(4).enumerate() {
+            registers.key(i).write(|w| w.key().set(u32::from_ne_bytes([word[0], word[1], word[2], word[3]])));
+        }
+        // SAFETY: the modes 0, 2, 4 and 6 for 128 and 256 bit keys are valid MODE values.
+        registers.mode().write(|w| unsafe { w.mode().bits((key_mode | mode) as u8) });
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            registers.text_in(i).write(|w| w.text_in().set(u32::from_ne_bytes([word[0], word[1], word[2], word[3]])));
+        }
+
+        // SAFETY: writing 1 to TRIGGER starts the operation with the key, mode and text written above.
+        registers.trigger().write(|w| unsafe { w.bits(1) });
+        while registers.state().read().state().bits() != 0 {}
+
+        for (i, word) in block.chunks_exact_mut(4).enumerate() {
+            word.copy_from_slice(&registers.text_out(i).read().bits().to_ne_bytes());
+        }
+    }
+}
+
+/// The registers of the AES peripheral behind `aes`.
+fn registers<'r>(_aes: &'r mut Aes<'_>) -> &'r <AES as core::ops::Deref>::Target {
+    // SAFETY: `Aes::new` took the AES peripheral singleton and enabled its clock with DMA disabled,
+    // so no other driver can use the peripheral without `Peripherals::steal`. The exclusive borrow of
+    // the driver keeps it from running `Aes::process` while the registers are used.
+    unsafe { &*AES::ptr() }
+}
+
+impl BlockCipher for Esp32C3Aes<'_, '_> {
+    fn encrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]) {
+        self.process(block, Mode::Encryption128 as u32);
+    }
+
+    fn decrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]) {
+        self.process(block, Mode::Decryption128 as u32);
+    }
+}
+
+impl Drop for Esp32C3Aes<'_, '_> {
+    fn drop(&mut self) {
+        // The peripheral keeps the key of the last block until it is overwritten.
+        let registers = registers(self.aes);
+        for i in 0..8 {
+            registers.key(i).write(|w| w.key().set(0));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Galois/Counter mode (NIST SP 800-38D) with 96 bit nonces and 128 bit tags.
+
+use crypto_bigint::subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+
+use super::{BlockCipher, BLOCK_LEN};
+
+
+/// Length of the nonce, other lengths are not supported.
+pub const NONCE_LEN: usize = 12;
+
+/// Length of the authentication tag.
+pub const TAG_LEN: usize = 16;
+
+/// Upper bound of the plaintext length of a single message, 2^32 - 2 blocks (SP 800-38D 5.2.1.1).
+const MAX_MESSAGE_LEN: u64 = ((1 << 32) - 2) * BLOCK_LEN as u64;
+
+/// Reduction polynomial x^128 + x^7 + x^2 + x + 1 in the bit reflected representation of GCM.
+const R: u128 = 0xe1 << 120;
+
+
+/// AES-GCM authenticated encryption with associated data.
+///
+/// The block cipher is usually `Esp32C3Aes`, GHASH runs in software in constant time.
+/// A nonce must never be used twice with the same key.
+pub struct AesGcm<C: BlockCipher> {
+    cipher: C,
+    /// Hash subkey H = E(K, 0^128)
+    h: u128,
+}
+
+impl<C: BlockCipher> AesGcm<C> {
+    pub fn new(mut cipher: C) -> Self {
+        let mut block = [0u8; BLOCK_LEN];
+        cipher.encrypt_block(&mut block);
+        let h = u128::from_be_bytes(block);
+        block.zeroize();
+
+        Self { cipher, h }
+    }
+
+    /// Encrypts the message in the first `message_len` bytes of `buffer` in place and appends the tag,
+    /// returns the ciphertext followed by the tag.
+    pub fn encrypt<'a>(
+        &mut self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &'a mut [u8],
+        message_len: usize
+    ) -> Result<&'a [u8]> {
+        let len = message_len + TAG_LEN;
+        if buffer.len() < len {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let (message, tag) = buffer[..len].split_at_mut(message_len);
+        tag.copy_from_slice(&self.encrypt_detached(nonce, aad, message)?);
+
+        Ok(&buffer[..len])
+    }
+
+    /// Checks the tag at the end of `buffer` and decrypts the ciphertext before it in place,
+    /// returns the message.
+    pub fn decrypt<'a>(&mut self, nonce: &[u8; NONCE_LEN], aad: &[u8], buffer: &'a mut [u8]) -> Result<&'a [u8]> {
+        if buffer.len() < TAG_LEN {
+            return Err(Error::Decryption);
+        }
+
+        let message_len = buffer.len() - TAG_LEN;
+        let (ciphertext, tag) = buffer.split_at_mut(message_len);
+        self.decrypt_detached(nonce, aad, ciphertext, (&*tag).try_into().unwrap())?;
+
+        Ok(&buffer[..message_len])
+    }
+
+    /// Encrypts `buffer` in place, returns the tag.
+    pub fn encrypt_detached(&mut self, nonce: &[u8; NONCE_LEN], aad: &[u8], buffer: &mut [u8]) -> Result<[u8; TAG_LEN]> {
+        check_length(buffer)?;
+
+        let mut counter = initial_counter(nonce);
+        increment(&mut counter);
+        self.apply_keystream(&mut counter, buffer);
+
+        Ok(self.tag(nonce, aad, buffer))
+    }
+
+    /// Checks `tag` and decrypts `buffer` in place.
+    ///
+    /// A wrong tag, key, nonce or associated data fails with `Error::Decryption` and leaves `buffer` unchanged.
+    pub fn decrypt_detached(
+        &mut self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; TAG_LEN]
+    ) -> Result<()> {
+        check_length(buffer)?;
+
+        let mut expected_tag = self.tag(nonce, aad, buffer);
+        let valid = expected_tag.ct_eq(tag);
+        expected_tag.zeroize();
+        if !bool::from(valid) {
+            return Err(Error::Decryption);
+        }
+
+        let mut counter = initial_counter(nonce);
+        increment(&mut counter);
+        self.apply_keystream(&mut counter, buffer);
+
+        Ok(())
+    }
+
+    /// GCTR (SP 800-38D 6.5), CTR mode with a 32 bit counter in the last word of the counter block.
+    fn apply_keystream(&mut self, counter: &mut [u8; BLOCK_LEN], data: &mut [u8]) {
+        let mut keystream = [0u8; BLOCK_LEN];
+        for chunk in data.chunks_mut(BLOCK_LEN) {
+            keystream.copy_from_slice(counter);
+            self.cipher.encrypt_block(&mut keystream);
+            for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+                *b ^= k;
+            }
+            increment(counter);
+        }
+        keystream.zeroize();
+    }
+
+    /// T = E(K, J0) xor GHASH(H, A || C || len(A) || len(C)).
+    fn tag(&mut self, nonce: &[u8; NONCE_LEN], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+        let mut y = 0u128;
+        self.ghash(&mut y, aad);
+        self.ghash(&mut y, ciphertext);
+        let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+        y = gf_mul(y ^ lengths, self.h);
+
+        let mut tag = initial_counter(nonce);
+        self.cipher.encrypt_block(&mut tag);
+        for (t, s) in tag.iter_mut().zip(y.to_be_bytes().iter()) {
+            *t ^= s;
+        }
+        tag
+    }
+
+    /// Absorbs `data` into `y`, the last block is padded with zeros.
+    fn ghash(&self, y: &mut u128, data: &[u8]) {
+        let mut block = [0u8; BLOCK_LEN];
+        for chunk in data.chunks(BLOCK_LEN) {
+            block.fill(0);
+            block[..chunk.len()].copy_from_slice(chunk);
+            *y = gf_mul(*y ^ u128::from_be_bytes(block), self.h);
+        }
+        block.zeroize();
+    }
+}
+
+impl<C: BlockCipher> Zeroize for AesGcm<C> {
+    fn zeroize(&mut self) {
+        self.h.zeroize();
+    }
+}
+
+impl<C: BlockCipher> Drop for AesGcm<C> {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+
+fn check_length(message: &[u8]) -> Result<()> {
+    // len(A) is limited to 2^64 - 1 bits, which no slice reaches
+    if message.len() as u64 > MAX_MESSAGE_LEN {
+        return Err(Error::MessageTooLong);
+    }
+    Ok(())
+}
+
+/// J0 = IV || 0^31 || 1 for 96 bit nonces.
+fn initial_counter(nonce: &[u8; NONCE_LEN]) -> [u8; BLOCK_LEN] {
+    let mut counter = [0u8; BLOCK_LEN];
+    counter[..NONCE_LEN].copy_from_slice(nonce);
+    counter[BLOCK_LEN - 1] = 1;
+    counter
+}
+
+/// inc32, increments the last 4 bytes as a big endian integer.
+fn increment(counter: &mut [u8; BLOCK_LEN]) {
+    let value = u32::from_be_bytes(counter[NONCE_LEN..].try_into().unwrap()).wrapping_add(1);
+    counter[NONCE_LEN..].copy_from_slice(&value.to_be_bytes());
+}
+
+/// Multiplication in GF(2^128) (SP 800-38D 6.3), without branches or lookups that depend on the operands.
+fn gf_mul(x: u128, y: u128) -> u128 {
+    let mut z = 0u128;
+    let mut v = y;
+    for i in (0..128).rev() {
+        z ^= v & 0u128.wrapping_sub((x >> i) & 1);
+        v = (v >> 1) ^ (R & 0u128.wrapping_sub(v & 1));
+    }
+    z
+}
@@ -0,0 +1,178 @@
+//! AES key wrap (RFC 3394, NIST SP 800-38F KW) and key wrap with padding (RFC 5649, KWP).
+//!
+//! A failed integrity check on unwrapping is reported as `Error::KeyUnwrap`.
+
+use crypto_bigint::subtle::{Choice, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+
+use super::{BlockCipher, BLOCK_LEN};
+
+
+/// Length of the integrity check value that is added by wrapping.
+pub const OVERHEAD: usize = 8;
+
+/// Default initial value of KW (RFC 3394 2.2.3.1).
+const IV: [u8; 8] = [0xa6; 8];
+
+/// First half of the alternative initial value of KWP, followed by the length of the key (RFC 5649 3).
+const AIV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+const SEMIBLOCK_LEN: usize = 8;
+
+
+/// Wraps `key` under the key-encryption key of `cipher`, returns the wrapped key that is 8 bytes longer.
+///
+/// `key` has to be a multiple of 8 bytes and at least 16 bytes, use `wrap_with_padding` for other lengths.
+pub fn wrap<'a, C: BlockCipher>(cipher: &mut C, key: &[u8], out: &'a mut [u8]) -> Result<&'a [u8]> {
+    if key.len() < 2 * SEMIBLOCK_LEN || key.len() % SEMIBLOCK_LEN != 0 {
+        return Err(Error::InvalidLength);
+    }
+    if out.len() < key.len() + OVERHEAD {
+        return Err(Error::BufferTooSmall);
+    }
+
+    out[OVERHEAD..key.len() + OVERHEAD].copy_from_slice(key);
+    let (a, r) = out[..key.len() + OVERHEAD].split_at_mut(SEMIBLOCK_LEN);
+    a.copy_from_slice(&IV);
+    wrap_semiblocks(cipher, a.try_into().unwrap(), r);
+
+    Ok(&out[..key.len() + OVERHEAD])
+}
+
+/// Unwraps a key wrapped with `wrap`, returns the key that is 8 bytes shorter.
+pub fn unwrap<'a, C: BlockCipher>(cipher: &mut C, wrapped: &[u8], out: &'a mut [u8]) -> Result<&'a [u8]> {
+    if wrapped.len() < 3 * SEMIBLOCK_LEN || wrapped.len() % SEMIBLOCK_LEN != 0 {
+        return Err(Error::InvalidLength);
+    }
+    let len = wrapped.len() - OVERHEAD;
+    if out.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let mut a: [u8; SEMIBLOCK_LEN] = wrapped[..SEMIBLOCK_LEN].try_into().unwrap();
+    out[..len].copy_from_slice(&wrapped[SEMIBLOCK_LEN..]);
+    unwrap_semiblocks(cipher, &mut a, &mut out[..len]);
+
+    if !bool::from(a.ct_eq(&IV)) {
+        out[..len].zeroize();
+        return Err(Error::KeyUnwrap);
+    }
+
+    Ok(&out[..len])
+}
+
+/// Wraps `key` of any length from 1 byte on, returns the wrapped key. It is `key` padded with zeros
+/// to a multiple of 8 bytes, plus 8 bytes.
+pub fn wrap_with_padding<'a, C: BlockCipher>(cipher: &mut C, key: &[u8], out: &'a mut [u8]) -> Result<&'a [u8]> {
+    let key_len = u32::try_from(key.len()).map_err(|_| Error::InvalidLength)?;
+    if key.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    let padded_len = key.len().div_ceil(SEMIBLOCK_LEN) * SEMIBLOCK_LEN;
+    if out.len() < padded_len + OVERHEAD {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let out = &mut out[..padded_len + OVERHEAD];
+    out[..4].copy_from_slice(&AIV_PREFIX);
+    out[4..OVERHEAD].copy_from_slice(&key_len.to_be_bytes());
+    out[OVERHEAD..OVERHEAD + key.len()].copy_from_slice(key);
+    out[OVERHEAD + key.len()..].fill(0);
+
+    if padded_len == SEMIBLOCK_LEN {
+        // A single semiblock is encrypted together with the AIV as one block
+        cipher.encrypt_block(out.try_into().unwrap());
+    } else {
+        let (a, r) = out.split_at_mut(SEMIBLOCK_LEN);
+        wrap_semiblocks(cipher, a.try_into().unwrap(), r);
+    }
+
+    Ok(out)
+}
+
+/// Unwraps a key wrapped with `wrap_with_padding`, returns the key without padding.
+///
+/// `out` needs room for the padded key, which is 8 bytes shorter than `wrapped`.
+pub fn unwrap_with_padding<'a, C: BlockCipher>(cipher: &mut C, wrapped: &[u8], out: &'a mut [u8]) -> Result<&'a [u8]> {
+    if wrapped.len() < 2 * SEMIBLOCK_LEN || wrapped.len() % SEMIBLOCK_LEN != 0 {
+        return Err(Error::InvalidLength);
+    }
+    let padded_len = wrapped.len() - OVERHEAD;
+    if out.len() < padded_len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let mut a = [0u8; SEMIBLOCK_LEN];
+    if padded_len == SEMIBLOCK_LEN {
+        let mut block: [u8; BLOCK_LEN] = wrapped.try_into().unwrap();
+        cipher.decrypt_block(&mut block);
+        a.copy_from_slice(&block[..SEMIBLOCK_LEN]);
+        out[..padded_len].copy_from_slice(&block[SEMIBLOCK_LEN..]);
+        block.zeroize();
+    } else {
+        a.copy_from_slice(&wrapped[..SEMIBLOCK_LEN]);
+        out[..padded_len].copy_from_slice(&wrapped[SEMIBLOCK_LEN..]);
+        unwrap_semiblocks(cipher, &mut a, &mut out[..padded_len]);
+    }
+
+    // The AIV prefix, the key length and the zero padding are checked in constant time (RFC 5649 3)
+    let key_len = u32::from_be_bytes(a[4..].try_into().unwrap()) as u64;
+    let mut valid = a[..4].ct_eq(&AIV_PREFIX)
+        & key_len.ct_gt(&((padded_len - SEMIBLOCK_LEN) as u64))
+        & !key_len.ct_gt(&(padded_len as u64));
+    for (i, b) in out[..padded_len].iter().enumerate() {
+        let in_padding: Choice = !(i as u64).ct_lt(&key_len);
+        valid &= !in_padding | b.ct_eq(&0);
+    }
+    a.zeroize();
+
+    if !bool::from(valid) {
+        out[..padded_len].zeroize();
+        return Err(Error::KeyUnwrap);
+    }
+
+    Ok(&out[..key_len as usize])
+}
+
+
+/// Wrapping process W (RFC 3394 2.2.1) on the initial value `a` and the semiblocks `r` in place.
+fn wrap_semiblocks<C: BlockCipher>(cipher: &mut C, a: &mut [u8; SEMIBLOCK_LEN], r: &mut [u8]) {
+    let n = (r.len() / SEMIBLOCK_LEN) as u64;
+    let mut block = [0u8; BLOCK_LEN];
+    for j in 0..6 {
+        for (i, semiblock) in r.chunks_exact_mut(SEMIBLOCK_LEN).enumerate() {
+            block[..SEMIBLOCK_LEN].copy_from_slice(a);
+            block[SEMIBLOCK_LEN..].copy_from_slice(semiblock);
+            cipher.encrypt_block(&mut block);
+
+            let t = n * j + i as u64 + 1;
+            for (x, (b, t)) in a.iter_mut().zip(block.iter().zip(t.to_be_bytes().iter())) {
+                *x = b ^ t;
+            }
+            semiblock.copy_from_slice(&block[SEMIBLOCK_LEN..]);
+        }
+    }
+    block.zeroize();
+}
+
+/// Unwrapping process W^-1 (RFC 3394 2.2.2), leaves the recovered initial value in `a`.
+fn unwrap_semiblocks<C: BlockCipher>(cipher: &mut C, a: &mut [u8; SEMIBLOCK_LEN], r: &mut [u8]) {
+    let n = (r.len() / SEMIBLOCK_LEN) as u64;
+    let mut block = [0u8; BLOCK_LEN];
+    for j in (0..6).rev() {
+        for (i, semiblock) in r.chunks_exact_mut(SEMIBLOCK_LEN).enumerate().rev() {
+            let t = n * j + i as u64 + 1;
+            for (b, (x, t)) in block.iter_mut().zip(a.iter().zip(t.to_be_bytes().iter())) {
+                *b = x ^ t;
+            }
+            block[SEMIBLOCK_LEN..].copy_from_slice(semiblock);
+            cipher.decrypt_block(&mut block);
+
+            a.copy_from_slice(&block[..SEMIBLOCK_LEN]);
+            semiblock.copy_from_slice(&block[SEMIBLOCK_LEN..]);
+        }
+    }
+    block.zeroize();
+}
@@ -0,0 +1,84 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::error::{Error, Result};
+
+pub mod ecb;
+pub mod cbc;
+pub mod ctr;
+pub mod gcm;
+pub mod ccm;
+pub mod kw;
+
+mod soft;
+pub use soft::SoftAes;
+
+#[cfg(feature = "esp32c3")]
+mod esp32c3;
+#[cfg(feature = "esp32c3")]
+pub use esp32c3::Esp32C3Aes;
+
+pub use gcm::AesGcm;
+pub use ccm::AesCcm;
+
+
+/// Block length of AES in bytes.
+pub const BLOCK_LEN: usize = 16;
+
+
+/// Encryption and decryption of single blocks, implemented by the AES peripheral and `SoftAes`.
+/// The modes in `ecb`, `cbc`, `ctr`, `gcm`, `ccm` and `kw` work on top of it.
+pub trait BlockCipher {
+    fn encrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]);
+    fn decrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]);
+}
+
+
+/// An AES-128 or AES-256 key, zeroized on drop.
+pub enum AesKey {
+    Aes128([u8; 16]),
+    Aes256([u8; 32]),
+}
+
+impl AesKey {
+    /// `key` has to be 16 bytes for AES-128 or 32 bytes for AES-256.
+    pub fn new(key: &[u8]) -> Result<Self> {
+        match key.len() {
+            16 => Ok(Self::Aes128(key.try_into().map_err(|_| Error::Internal)?)),
+            32 => Ok(Self::Aes256(key.try_into().map_err(|_| Error::Internal)?)),
+            _ => Err(Error::InvalidLength),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Aes128(key) => key,
+            Self::Aes256(key) => key,
+        }
+    }
+}
+
+impl Zeroize for AesKey {
+    fn zeroize(&mut self) {
+        match self {
+            Self::Aes128(key) => key.zeroize(),
+            Self::Aes256(key) => key.zeroize(),
+        }
+    }
+}
+
+impl Drop for AesKey {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for AesKey {}
+
+
+/// Checks that `len` is a whole number of blocks.
+fn check_block_len(len: usize) -> Result<()> {
+    if len % BLOCK_LEN != 0 {
+        return Err(Error::InvalidBlockSize);
+    }
+    Ok(())
+}
@@ -0,0 +1,267 @@
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+
+use super::{BlockCipher, BLOCK_LEN};
+
+
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+const INV_SBOX: [u8; 256] = invert(&SBOX);
+
+/// Round constants of the key expansion.
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// AES-256 has 14 rounds and 15 round keys.
+const MAX_ROUND_KEYS: usize = 15;
+
+
+const fn invert(sbox: &[u8; 256]) -> [u8; 256] {
+    let mut inverse = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        inverse[sbox[i] as usize] = i as u8;
+        i += 1;
+    }
+    inverse
+}
+
+
+/// AES in software, e.g. to check the modes on the host or while the AES peripheral is used elsewhere.
+///
+/// The S-box is a lookup table, so unlike the peripheral the timing depends on the key and the data.
+pub struct SoftAes {
+    round_keys: [[u8; BLOCK_LEN]; MAX_ROUND_KEYS],
+    rounds: usize,
+}
+
+impl SoftAes {
+    /// `key` has to be 16 bytes for AES-128 or 32 bytes for AES-256.
+    pub fn new(key: &[u8]) -> Result<Self> {
+        let rounds = match key.len() {
+            16 => 10,
+            32 => 14,
+            _ => return Err(Error::InvalidLength),
+        };
+
+        // Key expansion (FIPS 197 5.2) on 4 byte words
+        let key_words = key.len() / 4;
+        let mut words = [[0u8; 4]; 4 * MAX_ROUND_KEYS];
+        for (word, bytes) in words.iter_mut().zip(key.chunks_exact(4)) {
+            word.copy_from_slice(bytes);
+        }
+        for i in key_words..4 * (rounds + 1) {
+            let mut temp = words[i - 1];
+            if i % key_words == 0 {
+                temp = [
+                    SBOX[temp[1] as usize] ^ RCON[i / key_words - 1],
+                    SBOX[temp[2] as usize],
+                    SBOX[temp[3] as usize],
+                    SBOX[temp[0] as usize],
+                ];
+            } else if key_words > 6 && i % key_words == 4 {
+                for b in temp.iter_mut() {
+                    *b = SBOX[*b as usize];
+                }
+            }
+            for (j, b) in temp.iter().enumerate() {
+                words[i][j] = words[i - key_words][j] ^ b;
+            }
+        }
+
+        let mut round_keys = [[0u8; BLOCK_LEN]; MAX_ROUND_KEYS];
+        for (round_key, words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            for (bytes, word) in round_key.chunks_exact_mut(4).zip(words.iter()) {
+                bytes.copy_from_slice(word);
+            }
+        }
+        words.zeroize();
+
+        Ok(Self { round_keys, rounds })
+    }
+}
+
+impl BlockCipher for SoftAes {
+    fn encrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]) {
+        add_round_key(block, &self.round_keys[0]);
+        for round in 1..self.rounds {
+            sub_bytes(block, &SBOX);
+            shift_rows(block);
+            mix_columns(block);
+            add_round_key(block, &self.round_keys[round]);
+        }
+        sub_bytes(block, &SBOX);
+        shift_rows(block);
+        add_round_key(block, &self.round_keys[self.rounds]);
+    }
+
+    fn decrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]) {
+        add_round_key(block, &self.round_keys[self.rounds]);
+        for round in (1..self.rounds).rev() {
+            inv_shift_rows(block);
+            sub_bytes(block, &INV_SBOX);
+            add_round_key(block, &self.round_keys[round]);
+            inv_mix_columns(block);
+        }
+        inv_shift_rows(block);
+        sub_bytes(block, &INV_SBOX);
+        add_round_key(block, &self.round_keys[0]);
+    }
+}
+
+impl Zeroize for SoftAes {
+    fn zeroize(&mut self) {
+        self.round_keys.zeroize();
+        self.rounds.zeroize();
+    }
+}
+
+impl Drop for SoftAes {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+
+// The state is stored column by column: byte `4 * c + r` is row `r` of column `c`.
+
+fn add_round_key(block: &mut [u8; BLOCK_LEN], round_key: &[u8; BLOCK_LEN]) {
+    for (b, k) in block.iter_mut().zip(round_key.iter()) {
+        *b ^= k;
+    }
+}
+
+fn sub_bytes(block: &mut [u8; BLOCK_LEN], sbox: &[u8; 256]) {
+    for b in block.iter_mut() {
+        *b = sbox[*b as usize];
+    }
+}
+
+/// Rotates row `r` left by `r` columns.
+fn shift_rows(block: &mut [u8; BLOCK_LEN]) {
+    let state = *block;
+    for c in 0..4 {
+        for r in 0..4 {
+            block[4 * c + r] = state[4 * ((c + r) % 4) + r];
+        }
+    }
+}
+
+fn inv_shift_rows(block: &mut [u8; BLOCK_LEN]) {
+    let state = *block;
+    for c in 0..4 {
+        for r in 0..4 {
+            block[4 * ((c + r) % 4) + r] = state[4 * c + r];
+        }
+    }
+}
+
+/// Multiplication by x in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1.
+fn xtime(b: u8) -> u8 {
+    (b << 1) ^ (0x1b & 0u8.wrapping_sub(b >> 7))
+}
+
+fn mix_columns(block: &mut [u8; BLOCK_LEN]) {
+    for column in block.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        column[0] ^= all ^ xtime(a0 ^ a1);
+        column[1] ^= all ^ xtime(a1 ^ a2);
+        column[2] ^= all ^ xtime(a2 ^ a3);
+        column[3] ^= all ^ xtime(a3 ^ a0);
+    }
+}
+
+fn inv_mix_columns(block: &mut [u8; BLOCK_LEN]) {
+    // InvMixColumns = MixColumns after multiplying with 4x^2 + 5 (FIPS 197 4.3 / The Design of Rijndael 4.1.3)
+    for column in block.chunks_exact_mut(4) {
+        let u = xtime(xtime(column[0] ^ column[2]));
+        let v = xtime(xtime(column[1] ^ column[3]));
+        column[0] ^= u;
+        column[1] ^= v;
+        column[2] ^= u;
+        column[3] ^= v;
+    }
+    mix_columns(block);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes::{cbc, ctr, ecb};
+
+    // NIST SP 800-38A F.1, F.2 and F.5 with AES-128 and AES-256, all modes encrypt the same four blocks
+    const PLAINTEXT: &[u8] = &[0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a, 0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51, 0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11, 0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef, 0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17, 0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10];
+    const KEY_128: &[u8] = &[0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+    const KEY_256: &[u8] = &[0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4];
+    const CBC_IV: [u8; BLOCK_LEN] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+    const CTR_INITIAL_COUNTER: [u8; BLOCK_LEN] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff];
+    const ECB_128: &[u8] = &[0x3a, 0xd7, 0x7b, 0xb4, 0x0d, 0x7a, 0x36, 0x60, 0xa8, 0x9e, 0xca, 0xf3, 0x24, 0x66, 0xef, 0x97, 0xf5, 0xd3, 0xd5, 0x85, 0x03, 0xb9, 0x69, 0x9d, 0xe7, 0x85, 0x89, 0x5a, 0x96, 0xfd, 0xba, 0xaf, 0x43, 0xb1, 0xcd, 0x7f, 0x59, 0x8e, 0xce, 0x23, 0x88, 0x1b, 0x00, 0xe3, 0xed, 0x03, 0x06, 0x88, 0x7b, 0x0c, 0x78, 0x5e, 0x27, 0xe8, 0xad, 0x3f, 0x82, 0x23, 0x20, 0x71, 0x04, 0x72, 0x5d, 0xd4];
+    const ECB_256: &[u8] = &[0xf3, 0xee, 0xd1, 0xbd, 0xb5, 0xd2, 0xa0, 0x3c, 0x06, 0x4b, 0x5a, 0x7e, 0x3d, 0xb1, 0x81, 0xf8, 0x59, 0x1c, 0xcb, 0x10, 0xd4, 0x10, 0xed, 0x26, 0xdc, 0x5b, 0xa7, 0x4a, 0x31, 0x36, 0x28, 0x70, 0xb6, 0xed, 0x21, 0xb9, 0x9c, 0xa6, 0xf4, 0xf9, 0xf1, 0x53, 0xe7, 0xb1, 0xbe, 0xaf, 0xed, 0x1d, 0x23, 0x30, 0x4b, 0x7a, 0x39, 0xf9, 0xf3, 0xff, 0x06, 0x7d, 0x8d, 0x8f, 0x9e, 0x24, 0xec, 0xc7];
+    const CBC_128: &[u8] = &[0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, 0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9, 0x19, 0x7d, 0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee, 0x95, 0xdb, 0x11, 0x3a, 0x91, 0x76, 0x78, 0xb2, 0x73, 0xbe, 0xd6, 0xb8, 0xe3, 0xc1, 0x74, 0x3b, 0x71, 0x16, 0xe6, 0x9e, 0x22, 0x22, 0x95, 0x16, 0x3f, 0xf1, 0xca, 0xa1, 0x68, 0x1f, 0xac, 0x09, 0x12, 0x0e, 0xca, 0x30, 0x75, 0x86, 0xe1, 0xa7];
+    const CBC_256: &[u8] = &[0xf5, 0x8c, 0x4c, 0x04, 0xd6, 0xe5, 0xf1, 0xba, 0x77, 0x9e, 0xab, 0xfb, 0x5f, 0x7b, 0xfb, 0xd6, 0x9c, 0xfc, 0x4e, 0x96, 0x7e, 0xdb, 0x80, 0x8d, 0x67, 0x9f, 0x77, 0x7b, 0xc6, 0x70, 0x2c, 0x7d, 0x39, 0xf2, 0x33, 0x69, 0xa9, 0xd9, 0xba, 0xcf, 0xa5, 0x30, 0xe2, 0x63, 0x04, 0x23, 0x14, 0x61, 0xb2, 0xeb, 0x05, 0xe2, 0xc3, 0x9b, 0xe9, 0xfc, 0xda, 0x6c, 0x19, 0x07, 0x8c, 0x6a, 0x9d, 0x1b];
+    const CTR_128: &[u8] = &[0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce, 0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff, 0x5a, 0xe4, 0xdf, 0x3e, 0xdb, 0xd5, 0xd3, 0x5e, 0x5b, 0x4f, 0x09, 0x02, 0x0d, 0xb0, 0x3e, 0xab, 0x1e, 0x03, 0x1d, 0xda, 0x2f, 0xbe, 0x03, 0xd1, 0x79, 0x21, 0x70, 0xa0, 0xf3, 0x00, 0x9c, 0xee];
+    const CTR_256: &[u8] = &[0x60, 0x1e, 0xc3, 0x13, 0x77, 0x57, 0x89, 0xa5, 0xb7, 0xa7, 0xf5, 0x04, 0xbb, 0xf3, 0xd2, 0x28, 0xf4, 0x43, 0xe3, 0xca, 0x4d, 0x62, 0xb5, 0x9a, 0xca, 0x84, 0xe9, 0x90, 0xca, 0xca, 0xf5, 0xc5, 0x2b, 0x09, 0x30, 0xda, 0xa2, 0x3d, 0xe9, 0x4c, 0xe8, 0x70, 0x17, 0xba, 0x2d, 0x84, 0x98, 0x8d, 0xdf, 0xc9, 0xc5, 0x8d, 0xb6, 0x7a, 0xad, 0xa6, 0x13, 0xc2, 0xdd, 0x08, 0x45, 0x79, 0x41, 0xa6];
+
+    #[test]
+    fn sp800_38a_ecb() {
+        for (key, ciphertext) in [(KEY_128, ECB_128), (KEY_256, ECB_256)] {
+            let mut cipher = SoftAes::new(key).unwrap();
+            let mut data = [0u8; 64];
+            data.copy_from_slice(PLAINTEXT);
+
+            ecb::encrypt(&mut cipher, &mut data).unwrap();
+            assert_eq!(&data[..], ciphertext);
+            ecb::decrypt(&mut cipher, &mut data).unwrap();
+            assert_eq!(&data[..], PLAINTEXT);
+        }
+    }
+
+    #[test]
+    fn sp800_38a_cbc() {
+        for (key, ciphertext) in [(KEY_128, CBC_128), (KEY_256, CBC_256)] {
+            let mut cipher = SoftAes::new(key).unwrap();
+            let mut data = [0u8; 64];
+            data.copy_from_slice(PLAINTEXT);
+
+            cbc::encrypt_blocks(&mut cipher, &CBC_IV, &mut data).unwrap();
+            assert_eq!(&data[..], ciphertext);
+            cbc::decrypt_blocks(&mut cipher, &CBC_IV, &mut data).unwrap();
+            assert_eq!(&data[..], PLAINTEXT);
+        }
+    }
+
+    #[test]
+    fn sp800_38a_ctr() {
+        for (key, ciphertext) in [(KEY_128, CTR_128), (KEY_256, CTR_256)] {
+            let mut cipher = SoftAes::new(key).unwrap();
+            let mut data = [0u8; 64];
+            data.copy_from_slice(PLAINTEXT);
+
+            let mut counter = CTR_INITIAL_COUNTER;
+            ctr::apply_keystream(&mut cipher, &mut counter, &mut data);
+            assert_eq!(&data[..], ciphertext);
+            let mut counter = CTR_INITIAL_COUNTER;
+            ctr::apply_keystream(&mut cipher, &mut counter, &mut data);
+            assert_eq!(&data[..], PLAINTEXT);
+        }
+    }
+}
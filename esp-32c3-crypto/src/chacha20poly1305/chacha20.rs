@@ -0,0 +1,112 @@
+//! ChaCha20 block function (RFC 8439 2.3) and HChaCha20 (draft-irtf-cfrg-xchacha 2.2).
+
+use zeroize::Zeroize;
+
+use super::{KEY_LEN, NONCE_LEN};
+
+
+/// Length of a keystream block.
+pub(crate) const BLOCK_LEN: usize = 64;
+
+/// "expand 32-byte k"
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// 20 rounds as 10 double rounds of column and diagonal rounds.
+fn rounds(state: &mut [u32; 16]) {
+    for _ in 0..10 {
+        quarter_round(state, 0, 4, 8, 12);
+        quarter_round(state, 1, 5, 9, 13);
+        quarter_round(state, 2, 6, 10, 14);
+        quarter_round(state, 3, 7, 11, 15);
+        quarter_round(state, 0, 5, 10, 15);
+        quarter_round(state, 1, 6, 11, 12);
+        quarter_round(state, 2, 7, 8, 13);
+        quarter_round(state, 3, 4, 9, 14);
+    }
+}
+
+/// constants || key || `input`, with `input` the block counter and nonce or the HChaCha20 nonce.
+fn initial_state(key: &[u8; KEY_LEN], input: &[u8; 16]) -> [u32; 16] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    for (word, bytes) in state[4..12].iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    for (word, bytes) in state[12..].iter_mut().zip(input.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    state
+}
+
+/// Writes the keystream block for `counter` to `out`.
+pub(crate) fn block(key: &[u8; KEY_LEN], counter: u32, nonce: &[u8; NONCE_LEN], out: &mut [u8; BLOCK_LEN]) {
+    let mut input = [0u8; 16];
+    input[..4].copy_from_slice(&counter.to_le_bytes());
+    input[4..].copy_from_slice(nonce);
+
+    let initial = initial_state(key, &input);
+    let mut state = initial;
+    rounds(&mut state);
+
+    for ((bytes, word), initial) in out.chunks_exact_mut(4).zip(state.iter()).zip(initial.iter()) {
+        bytes.copy_from_slice(&word.wrapping_add(*initial).to_le_bytes());
+    }
+
+    state.zeroize();
+}
+
+/// XORs `data` in place with the keystream starting at block `counter`.
+pub(crate) fn apply_keystream(key: &[u8; KEY_LEN], counter: u32, nonce: &[u8; NONCE_LEN], data: &mut [u8]) {
+    let mut keystream = [0u8; BLOCK_LEN];
+    for (i, chunk) in data.chunks_mut(BLOCK_LEN).enumerate() {
+        block(key, counter.wrapping_add(i as u32), nonce, &mut keystream);
+        for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+            *b ^= k;
+        }
+    }
+    keystream.zeroize();
+}
+
+/// Derives the subkey of XChaCha20 from the key and the first 16 bytes of the extended nonce.
+pub(crate) fn hchacha20(key: &[u8; KEY_LEN], nonce: &[u8; 16]) -> [u8; KEY_LEN] {
+    let mut state = initial_state(key, nonce);
+    rounds(&mut state);
+
+    let mut subkey = [0u8; KEY_LEN];
+    for (bytes, word) in subkey.chunks_exact_mut(4).zip(state[..4].iter().chain(state[12..].iter())) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+
+    state.zeroize();
+    subkey
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8439 2.3.2
+    const KEY: [u8; KEY_LEN] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f];
+    const NONCE: [u8; NONCE_LEN] = [0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00];
+    const BLOCK: [u8; BLOCK_LEN] = [0x10, 0xf1, 0xe7, 0xe4, 0xd1, 0x3b, 0x59, 0x15, 0x50, 0x0f, 0xdd, 0x1f, 0xa3, 0x20, 0x71, 0xc4, 0xc7, 0xd1, 0xf4, 0xc7, 0x33, 0xc0, 0x68, 0x03, 0x04, 0x22, 0xaa, 0x9a, 0xc3, 0xd4, 0x6c, 0x4e, 0xd2, 0x82, 0x64, 0x46, 0x07, 0x9f, 0xaa, 0x09, 0x14, 0xc2, 0xd7, 0x05, 0xd9, 0x8b, 0x02, 0xa2, 0xb5, 0x12, 0x9c, 0xd1, 0xde, 0x16, 0x4e, 0xb9, 0xcb, 0xd0, 0x83, 0xe8, 0xa2, 0x50, 0x3c, 0x4e];
+
+    #[test]
+    fn rfc8439_block_function() {
+        let mut out = [0u8; BLOCK_LEN];
+        block(&KEY, 1, &NONCE, &mut out);
+        assert_eq!(out, BLOCK);
+    }
+}
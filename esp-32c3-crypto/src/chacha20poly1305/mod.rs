@@ -0,0 +1,318 @@
+//! ChaCha20-Poly1305 (RFC 8439) and XChaCha20-Poly1305 (draft-irtf-cfrg-xchacha) in software.
+//!
+//! Only additions, rotations and xors on 32 bit words, so unlike `aes::SoftAes` the timing does not
+//! depend on the key or the data, and the AES peripheral stays free.
+
+use crypto_bigint::subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::error::{Error, Result};
+
+mod chacha20;
+mod poly1305;
+
+use poly1305::Poly1305;
+
+
+/// Length of the key.
+pub const KEY_LEN: usize = 32;
+
+/// Length of the nonce of ChaCha20-Poly1305.
+pub const NONCE_LEN: usize = 12;
+
+/// Length of the nonce of XChaCha20-Poly1305.
+pub const XNONCE_LEN: usize = 24;
+
+/// Length of the authentication tag.
+pub const TAG_LEN: usize = 16;
+
+/// Upper bound of the plaintext length of a single message, 2^32 - 1 blocks of 64 bytes (RFC 8439 2.8).
+const MAX_MESSAGE_LEN: u64 = ((1 << 32) - 1) * chacha20::BLOCK_LEN as u64;
+
+
+/// ChaCha20-Poly1305 authenticated encryption with associated data.
+///
+/// The 12 byte nonce must never be used twice with the same key, use `XChaCha20Poly1305`
+/// for random nonces.
+pub struct ChaCha20Poly1305 {
+    key: [u8; KEY_LEN],
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8; KEY_LEN]) -> Self {
+        Self { key: *key }
+    }
+
+    /// Encrypts the message in the first `message_len` bytes of `buffer` in place and appends the tag,
+    /// returns the ciphertext followed by the tag.
+    pub fn encrypt<'a>(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &'a mut [u8],
+        message_len: usize
+    ) -> Result<&'a [u8]> {
+        let len = message_len + TAG_LEN;
+        if buffer.len() < len {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let (message, tag) = buffer[..len].split_at_mut(message_len);
+        tag.copy_from_slice(&self.encrypt_detached(nonce, aad, message)?);
+
+        Ok(&buffer[..len])
+    }
+
+    /// Checks the tag at the end of `buffer` and decrypts the ciphertext before it in place,
+    /// returns the message.
+    pub fn decrypt<'a>(&self, nonce: &[u8; NONCE_LEN], aad: &[u8], buffer: &'a mut [u8]) -> Result<&'a [u8]> {
+        if buffer.len() < TAG_LEN {
+            return Err(Error::Decryption);
+        }
+
+        let message_len = buffer.len() - TAG_LEN;
+        let (ciphertext, tag) = buffer.split_at_mut(message_len);
+        self.decrypt_detached(nonce, aad, ciphertext, (&*tag).try_into().unwrap())?;
+
+        Ok(&buffer[..message_len])
+    }
+
+    /// Encrypts `buffer` in place, returns the tag.
+    pub fn encrypt_detached(&self, nonce: &[u8; NONCE_LEN], aad: &[u8], buffer: &mut [u8]) -> Result<[u8; TAG_LEN]> {
+        seal(&self.key, nonce, aad, buffer)
+    }
+
+    /// Checks `tag` and decrypts `buffer` in place.
+    ///
+    /// A wrong tag, key, nonce or associated data fails with `Error::Decryption` and leaves `buffer` unchanged.
+    pub fn decrypt_detached(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; TAG_LEN]
+    ) -> Result<()> {
+        open(&self.key, nonce, aad, buffer, tag)
+    }
+}
+
+impl Zeroize for ChaCha20Poly1305 {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl Drop for ChaCha20Poly1305 {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for ChaCha20Poly1305 {}
+
+
+/// XChaCha20-Poly1305, ChaCha20-Poly1305 with a 24 byte nonce that is long enough to be chosen at random.
+pub struct XChaCha20Poly1305 {
+    key: [u8; KEY_LEN],
+}
+
+impl XChaCha20Poly1305 {
+    pub fn new(key: &[u8; KEY_LEN]) -> Self {
+        Self { key: *key }
+    }
+
+    /// Encrypts the message in the first `message_len` bytes of `buffer` in place and appends the tag,
+    /// returns the ciphertext followed by the tag.
+    pub fn encrypt<'a>(
+        &self,
+        nonce: &[u8; XNONCE_LEN],
+        aad: &[u8],
+        buffer: &'a mut [u8],
+        message_len: usize
+    ) -> Result<&'a [u8]> {
+        let len = message_len + TAG_LEN;
+        if buffer.len() < len {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let (message, tag) = buffer[..len].split_at_mut(message_len);
+        tag.copy_from_slice(&self.encrypt_detached(nonce, aad, message)?);
+
+        Ok(&buffer[..len])
+    }
+
+    /// Checks the tag at the end of `buffer` and decrypts the ciphertext before it in place,
+    /// returns the message.
+    pub fn decrypt<'a>(&self, nonce: &[u8; XNONCE_LEN], aad: &[u8], buffer: &'a mut [u8]) -> Result<&'a [u8]> {
+        if buffer.len() < TAG_LEN {
+            return Err(Error::Decryption);
+        }
+
+        let message_len = buffer.len() - TAG_LEN;
+        let (ciphertext, tag) = buffer.split_at_mut(message_len);
+        self.decrypt_detached(nonce, aad, ciphertext, (&*tag).try_into().unwrap())?;
+
+        Ok(&buffer[..message_len])
+    }
+
+    /// Encrypts `buffer` in place, returns the tag.
+    pub fn encrypt_detached(&self, nonce: &[u8; XNONCE_LEN], aad: &[u8], buffer: &mut [u8]) -> Result<[u8; TAG_LEN]> {
+        let (mut subkey, nonce) = derive_subkey(&self.key, nonce);
+        let result = seal(&subkey, &nonce, aad, buffer);
+        subkey.zeroize();
+        result
+    }
+
+    /// Checks `tag` and decrypts `buffer` in place.
+    ///
+    /// A wrong tag, key, nonce or associated data fails with `Error::Decryption` and leaves `buffer` unchanged.
+    pub fn decrypt_detached(
+        &self,
+        nonce: &[u8; XNONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; TAG_LEN]
+    ) -> Result<()> {
+        let (mut subkey, nonce) = derive_subkey(&self.key, nonce);
+        let result = open(&subkey, &nonce, aad, buffer, tag);
+        subkey.zeroize();
+        result
+    }
+}
+
+impl Zeroize for XChaCha20Poly1305 {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl Drop for XChaCha20Poly1305 {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for XChaCha20Poly1305 {}
+
+
+/// HChaCha20 subkey from the first 16 bytes of the nonce, and the ChaCha20 nonce 0^4 || last 8 bytes.
+fn derive_subkey(key: &[u8; KEY_LEN], nonce: &[u8; XNONCE_LEN]) -> ([u8; KEY_LEN], [u8; NONCE_LEN]) {
+    let subkey = chacha20::hchacha20(key, nonce[..16].try_into().unwrap());
+    let mut chacha_nonce = [0u8; NONCE_LEN];
+    chacha_nonce[4..].copy_from_slice(&nonce[16..]);
+    (subkey, chacha_nonce)
+}
+
+fn seal(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], aad: &[u8], buffer: &mut [u8]) -> Result<[u8; TAG_LEN]> {
+    if buffer.len() as u64 > MAX_MESSAGE_LEN {
+        return Err(Error::MessageTooLong);
+    }
+
+    chacha20::apply_keystream(key, 1, nonce, buffer);
+    Ok(tag(key, nonce, aad, buffer))
+}
+
+fn open(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], aad: &[u8], buffer: &mut [u8], tag_in: &[u8; TAG_LEN]) -> Result<()> {
+    if buffer.len() as u64 > MAX_MESSAGE_LEN {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut expected_tag = tag(key, nonce, aad, buffer);
+    let valid = expected_tag.ct_eq(tag_in);
+    expected_tag.zeroize();
+    if !bool::from(valid) {
+        return Err(Error::Decryption);
+    }
+
+    chacha20::apply_keystream(key, 1, nonce, buffer);
+    Ok(())
+}
+
+/// Poly1305 over aad || pad || ciphertext || pad || len(aad) || len(ciphertext), keyed with the
+/// first 32 bytes of keystream block 0 (RFC 8439 2.6, 2.8).
+fn tag(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+    let mut block = [0u8; chacha20::BLOCK_LEN];
+    chacha20::block(key, 0, nonce, &mut block);
+    let mut poly = Poly1305::new(block[..32].try_into().unwrap());
+    block.zeroize();
+
+    let mut lengths = [0u8; 16];
+    lengths[..8].copy_from_slice(&(aad.len() as u64).to_le_bytes());
+    lengths[8..].copy_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+    poly.update_padded(aad);
+    poly.update_padded(ciphertext);
+    poly.update_padded(&lengths);
+    poly.finalize()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8439 2.8.2, the same message and key are used by draft-irtf-cfrg-xchacha A.3.1
+    const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    const KEY: [u8; KEY_LEN] = [0x80, 0x81, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89, 0x8a, 0x8b, 0x8c, 0x8d, 0x8e, 0x8f, 0x90, 0x91, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9a, 0x9b, 0x9c, 0x9d, 0x9e, 0x9f];
+    const AAD: &[u8] = &[0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7];
+    const NONCE: [u8; NONCE_LEN] = [0x07, 0x00, 0x00, 0x00, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47];
+    const CIPHERTEXT: &[u8] = &[0xd3, 0x1a, 0x8d, 0x34, 0x64, 0x8e, 0x60, 0xdb, 0x7b, 0x86, 0xaf, 0xbc, 0x53, 0xef, 0x7e, 0xc2, 0xa4, 0xad, 0xed, 0x51, 0x29, 0x6e, 0x08, 0xfe, 0xa9, 0xe2, 0xb5, 0xa7, 0x36, 0xee, 0x62, 0xd6, 0x3d, 0xbe, 0xa4, 0x5e, 0x8c, 0xa9, 0x67, 0x12, 0x82, 0xfa, 0xfb, 0x69, 0xda, 0x92, 0x72, 0x8b, 0x1a, 0x71, 0xde, 0x0a, 0x9e, 0x06, 0x0b, 0x29, 0x05, 0xd6, 0xa5, 0xb6, 0x7e, 0xcd, 0x3b, 0x36, 0x92, 0xdd, 0xbd, 0x7f, 0x2d, 0x77, 0x8b, 0x8c, 0x98, 0x03, 0xae, 0xe3, 0x28, 0x09, 0x1b, 0x58, 0xfa, 0xb3, 0x24, 0xe4, 0xfa, 0xd6, 0x75, 0x94, 0x55, 0x85, 0x80, 0x8b, 0x48, 0x31, 0xd7, 0xbc, 0x3f, 0xf4, 0xde, 0xf0, 0x8e, 0x4b, 0x7a, 0x9d, 0xe5, 0x76, 0xd2, 0x65, 0x86, 0xce, 0xc6, 0x4b, 0x61, 0x16];
+    const TAG: [u8; TAG_LEN] = [0x1a, 0xe1, 0x0b, 0x59, 0x4f, 0x09, 0xe2, 0x6a, 0x7e, 0x90, 0x2e, 0xcb, 0xd0, 0x60, 0x06, 0x91];
+
+    const XNONCE: [u8; XNONCE_LEN] = [0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e, 0x4f, 0x50, 0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x57];
+    const XCIPHERTEXT: &[u8] = &[0xbd, 0x6d, 0x17, 0x9d, 0x3e, 0x83, 0xd4, 0x3b, 0x95, 0x76, 0x57, 0x94, 0x93, 0xc0, 0xe9, 0x39, 0x57, 0x2a, 0x17, 0x00, 0x25, 0x2b, 0xfa, 0xcc, 0xbe, 0xd2, 0x90, 0x2c, 0x21, 0x39, 0x6c, 0xbb, 0x73, 0x1c, 0x7f, 0x1b, 0x0b, 0x4a, 0xa6, 0x44, 0x0b, 0xf3, 0xa8, 0x2f, 0x4e, 0xda, 0x7e, 0x39, 0xae, 0x64, 0xc6, 0x70, 0x8c, 0x54, 0xc2, 0x16, 0xcb, 0x96, 0xb7, 0x2e, 0x12, 0x13, 0xb4, 0x52, 0x2f, 0x8c, 0x9b, 0xa4, 0x0d, 0xb5, 0xd9, 0x45, 0xb1, 0x1b, 0x69, 0xb9, 0x82, 0xc1, 0xbb, 0x9e, 0x3f, 0x3f, 0xac, 0x2b, 0xc3, 0x69, 0x48, 0x8f, 0x76, 0xb2, 0x38, 0x35, 0x65, 0xd3, 0xff, 0xf9, 0x21, 0xf9, 0x66, 0x4c, 0x97, 0x63, 0x7d, 0xa9, 0x76, 0x88, 0x12, 0xf6, 0x15, 0xc6, 0x8b, 0x13, 0xb5, 0x2e];
+    const XTAG: [u8; TAG_LEN] = [0xc0, 0x87, 0x59, 0x24, 0xc1, 0xc7, 0x98, 0x79, 0x47, 0xde, 0xaf, 0xd8, 0x78, 0x0a, 0xcf, 0x49];
+
+    // RFC 8439 A.5
+    const A5_KEY: [u8; KEY_LEN] = [0x1c, 0x92, 0x40, 0xa5, 0xeb, 0x55, 0xd3, 0x8a, 0xf3, 0x33, 0x88, 0x86, 0x04, 0xf6, 0xb5, 0xf0, 0x47, 0x39, 0x17, 0xc1, 0x40, 0x2b, 0x80, 0x09, 0x9d, 0xca, 0x5c, 0xbc, 0x20, 0x70, 0x75, 0xc0];
+    const A5_NONCE: [u8; NONCE_LEN] = [0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+    const A5_AAD: &[u8] = &[0xf3, 0x33, 0x88, 0x86, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4e, 0x91];
+    const A5_CIPHERTEXT: &[u8] = &[0x64, 0xa0, 0x86, 0x15, 0x75, 0x86, 0x1a, 0xf4, 0x60, 0xf0, 0x62, 0xc7, 0x9b, 0xe6, 0x43, 0xbd, 0x5e, 0x80, 0x5c, 0xfd, 0x34, 0x5c, 0xf3, 0x89, 0xf1, 0x08, 0x67, 0x0a, 0xc7, 0x6c, 0x8c, 0xb2, 0x4c, 0x6c, 0xfc, 0x18, 0x75, 0x5d, 0x43, 0xee, 0xa0, 0x9e, 0xe9, 0x4e, 0x38, 0x2d, 0x26, 0xb0, 0xbd, 0xb7, 0xb7, 0x3c, 0x32, 0x1b, 0x01, 0x00, 0xd4, 0xf0, 0x3b, 0x7f, 0x35, 0x58, 0x94, 0xcf, 0x33, 0x2f, 0x83, 0x0e, 0x71, 0x0b, 0x97, 0xce, 0x98, 0xc8, 0xa8, 0x4a, 0xbd, 0x0b, 0x94, 0x81, 0x14, 0xad, 0x17, 0x6e, 0x00, 0x8d, 0x33, 0xbd, 0x60, 0xf9, 0x82, 0xb1, 0xff, 0x37, 0xc8, 0x55, 0x97, 0x97, 0xa0, 0x6e, 0xf4, 0xf0, 0xef, 0x61, 0xc1, 0x86, 0x32, 0x4e, 0x2b, 0x35, 0x06, 0x38, 0x36, 0x06, 0x90, 0x7b, 0x6a, 0x7c, 0x02, 0xb0, 0xf9, 0xf6, 0x15, 0x7b, 0x53, 0xc8, 0x67, 0xe4, 0xb9, 0x16, 0x6c, 0x76, 0x7b, 0x80, 0x4d, 0x46, 0xa5, 0x9b, 0x52, 0x16, 0xcd, 0xe7, 0xa4, 0xe9, 0x90, 0x40, 0xc5, 0xa4, 0x04, 0x33, 0x22, 0x5e, 0xe2, 0x82, 0xa1, 0xb0, 0xa0, 0x6c, 0x52, 0x3e, 0xaf, 0x45, 0x34, 0xd7, 0xf8, 0x3f, 0xa1, 0x15, 0x5b, 0x00, 0x47, 0x71, 0x8c, 0xbc, 0x54, 0x6a, 0x0d, 0x07, 0x2b, 0x04, 0xb3, 0x56, 0x4e, 0xea, 0x1b, 0x42, 0x22, 0x73, 0xf5, 0x48, 0x27, 0x1a, 0x0b, 0xb2, 0x31, 0x60, 0x53, 0xfa, 0x76, 0x99, 0x19, 0x55, 0xeb, 0xd6, 0x31, 0x59, 0x43, 0x4e, 0xce, 0xbb, 0x4e, 0x46, 0x6d, 0xae, 0x5a, 0x10, 0x73, 0xa6, 0x72, 0x76, 0x27, 0x09, 0x7a, 0x10, 0x49, 0xe6, 0x17, 0xd9, 0x1d, 0x36, 0x10, 0x94, 0xfa, 0x68, 0xf0, 0xff, 0x77, 0x98, 0x71, 0x30, 0x30, 0x5b, 0xea, 0xba, 0x2e, 0xda, 0x04, 0xdf, 0x99, 0x7b, 0x71, 0x4d, 0x6c, 0x6f, 0x2c, 0x29, 0xa6, 0xad, 0x5c, 0xb4, 0x02, 0x2b, 0x02, 0x70, 0x9b];
+    const A5_TAG: [u8; TAG_LEN] = [0xee, 0xad, 0x9d, 0x67, 0x89, 0x0c, 0xbb, 0x22, 0x39, 0x23, 0x36, 0xfe, 0xa1, 0x85, 0x1f, 0x38];
+    const A5_PLAINTEXT: &[u8] = &[0x49, 0x6e, 0x74, 0x65, 0x72, 0x6e, 0x65, 0x74, 0x2d, 0x44, 0x72, 0x61, 0x66, 0x74, 0x73, 0x20, 0x61, 0x72, 0x65, 0x20, 0x64, 0x72, 0x61, 0x66, 0x74, 0x20, 0x64, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x73, 0x20, 0x76, 0x61, 0x6c, 0x69, 0x64, 0x20, 0x66, 0x6f, 0x72, 0x20, 0x61, 0x20, 0x6d, 0x61, 0x78, 0x69, 0x6d, 0x75, 0x6d, 0x20, 0x6f, 0x66, 0x20, 0x73, 0x69, 0x78, 0x20, 0x6d, 0x6f, 0x6e, 0x74, 0x68, 0x73, 0x20, 0x61, 0x6e, 0x64, 0x20, 0x6d, 0x61, 0x79, 0x20, 0x62, 0x65, 0x20, 0x75, 0x70, 0x64, 0x61, 0x74, 0x65, 0x64, 0x2c, 0x20, 0x72, 0x65, 0x70, 0x6c, 0x61, 0x63, 0x65, 0x64, 0x2c, 0x20, 0x6f, 0x72, 0x20, 0x6f, 0x62, 0x73, 0x6f, 0x6c, 0x65, 0x74, 0x65, 0x64, 0x20, 0x62, 0x79, 0x20, 0x6f, 0x74, 0x68, 0x65, 0x72, 0x20, 0x64, 0x6f, 0x63, 0x75, 0x6d, 0x65, 0x6e, 0x74, 0x73, 0x20, 0x61, 0x74, 0x20, 0x61, 0x6e, 0x79, 0x20, 0x74, 0x69, 0x6d, 0x65, 0x2e, 0x20, 0x49, 0x74, 0x20, 0x69, 0x73, 0x20, 0x69, 0x6e, 0x61, 0x70, 0x70, 0x72, 0x6f, 0x70, 0x72, 0x69, 0x61, 0x74, 0x65, 0x20, 0x74, 0x6f, 0x20, 0x75, 0x73, 0x65, 0x20, 0x49, 0x6e, 0x74, 0x65, 0x72, 0x6e, 0x65, 0x74, 0x2d, 0x44, 0x72, 0x61, 0x66, 0x74, 0x73, 0x20, 0x61, 0x73, 0x20, 0x72, 0x65, 0x66, 0x65, 0x72, 0x65, 0x6e, 0x63, 0x65, 0x20, 0x6d, 0x61, 0x74, 0x65, 0x72, 0x69, 0x61, 0x6c, 0x20, 0x6f, 0x72, 0x20, 0x74, 0x6f, 0x20, 0x63, 0x69, 0x74, 0x65, 0x20, 0x74, 0x68, 0x65, 0x6d, 0x20, 0x6f, 0x74, 0x68, 0x65, 0x72, 0x20, 0x74, 0x68, 0x61, 0x6e, 0x20, 0x61, 0x73, 0x20, 0x2f, 0xe2, 0x80, 0x9c, 0x77, 0x6f, 0x72, 0x6b, 0x20, 0x69, 0x6e, 0x20, 0x70, 0x72, 0x6f, 0x67, 0x72, 0x65, 0x73, 0x73, 0x2e, 0x2f, 0xe2, 0x80, 0x9d];
+
+    #[test]
+    fn rfc8439_aead() {
+        let cipher = ChaCha20Poly1305::new(&KEY);
+        let mut buffer = [0u8; 114];
+        buffer.copy_from_slice(SUNSCREEN);
+
+        assert_eq!(cipher.encrypt_detached(&NONCE, AAD, &mut buffer).unwrap(), TAG);
+        assert_eq!(&buffer[..], CIPHERTEXT);
+        cipher.decrypt_detached(&NONCE, AAD, &mut buffer, &TAG).unwrap();
+        assert_eq!(&buffer[..], SUNSCREEN);
+    }
+
+    #[test]
+    fn rfc8439_aead_decryption() {
+        let mut buffer = [0u8; 265];
+        buffer.copy_from_slice(A5_CIPHERTEXT);
+
+        ChaCha20Poly1305::new(&A5_KEY).decrypt_detached(&A5_NONCE, A5_AAD, &mut buffer, &A5_TAG).unwrap();
+        assert_eq!(&buffer[..], A5_PLAINTEXT);
+    }
+
+    #[test]
+    fn xchacha_aead() {
+        let cipher = XChaCha20Poly1305::new(&KEY);
+        let mut buffer = [0u8; 114];
+        buffer.copy_from_slice(SUNSCREEN);
+
+        assert_eq!(cipher.encrypt_detached(&XNONCE, AAD, &mut buffer).unwrap(), XTAG);
+        assert_eq!(&buffer[..], XCIPHERTEXT);
+        cipher.decrypt_detached(&XNONCE, AAD, &mut buffer, &XTAG).unwrap();
+        assert_eq!(&buffer[..], SUNSCREEN);
+    }
+
+    #[test]
+    fn modified_tag() {
+        let mut buffer = [0u8; 265];
+        buffer.copy_from_slice(A5_CIPHERTEXT);
+        let mut tag = A5_TAG;
+        tag[0] ^= 1;
+
+        let result = ChaCha20Poly1305::new(&A5_KEY).decrypt_detached(&A5_NONCE, A5_AAD, &mut buffer, &tag);
+        assert!(matches!(result, Err(Error::Decryption)));
+        assert_eq!(&buffer[..], A5_CIPHERTEXT);
+    }
+}
@@ -0,0 +1,149 @@
+//! Poly1305 one-time authenticator (RFC 8439 2.5) on 26 bit limbs, without branches or lookups
+//! that depend on the key or the data.
+
+use zeroize::Zeroize;
+
+use super::TAG_LEN;
+
+
+const BLOCK_LEN: usize = 16;
+
+const LIMB_MASK: u32 = 0x3ffffff;
+
+
+pub(crate) struct Poly1305 {
+    /// Clamped r
+    r: [u32; 5],
+    /// Accumulator
+    h: [u32; 5],
+    /// s, added to the accumulator at the end
+    pad: [u32; 4],
+}
+
+impl Poly1305 {
+    /// `key` is r || s and must only be used for one message.
+    pub(crate) fn new(key: &[u8; 32]) -> Self {
+        let le32 = |i: usize| u32::from_le_bytes(key[i..i + 4].try_into().unwrap());
+
+        let r = [
+            le32(0) & 0x3ffffff,
+            (le32(3) >> 2) & 0x3ffff03,
+            (le32(6) >> 4) & 0x3ffc0ff,
+            (le32(9) >> 6) & 0x3f03fff,
+            (le32(12) >> 8) & 0x00fffff,
+        ];
+        let pad = [le32(16), le32(20), le32(24), le32(28)];
+
+        Self { r, h: [0; 5], pad }
+    }
+
+    /// Absorbs `data`, the last block is padded with zeros to 16 bytes like the AEAD construction
+    /// (RFC 8439 2.8) does for the associated data and the ciphertext.
+    pub(crate) fn update_padded(&mut self, data: &[u8]) {
+        let mut block = [0u8; BLOCK_LEN];
+        for chunk in data.chunks(BLOCK_LEN) {
+            block.fill(0);
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.process_block(&block);
+        }
+        block.zeroize();
+    }
+
+    /// h = (h + block + 2^128) * r mod 2^130 - 5
+    fn process_block(&mut self, block: &[u8; BLOCK_LEN]) {
+        let le32 = |i: usize| u32::from_le_bytes(block[i..i + 4].try_into().unwrap());
+        let [r0, r1, r2, r3, r4] = self.r.map(u64::from);
+        let [s1, s2, s3, s4] = [r1 * 5, r2 * 5, r3 * 5, r4 * 5];
+
+        let h0 = (self.h[0] + (le32(0) & LIMB_MASK)) as u64;
+        let h1 = (self.h[1] + ((le32(3) >> 2) & LIMB_MASK)) as u64;
+        let h2 = (self.h[2] + ((le32(6) >> 4) & LIMB_MASK)) as u64;
+        let h3 = (self.h[3] + ((le32(9) >> 6) & LIMB_MASK)) as u64;
+        let h4 = (self.h[4] + ((le32(12) >> 8) | (1 << 24))) as u64;
+
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let mut d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let mut d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        // Partial carry propagation, the limbs stay small enough for the next block
+        d1 += d0 >> 26;
+        d2 += d1 >> 26;
+        d3 += d2 >> 26;
+        d4 += d3 >> 26;
+        let mut h0 = (d0 as u32 & LIMB_MASK) + (d4 >> 26) as u32 * 5;
+        let h1 = (d1 as u32 & LIMB_MASK) + (h0 >> 26);
+        h0 &= LIMB_MASK;
+
+        self.h = [h0, h1, d2 as u32 & LIMB_MASK, d3 as u32 & LIMB_MASK, d4 as u32 & LIMB_MASK];
+    }
+
+    /// tag = ((h mod 2^130 - 5) + s) mod 2^128
+    pub(crate) fn finalize(mut self) -> [u8; TAG_LEN] {
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+
+        // Full carry propagation
+        h2 += h1 >> 26;
+        h1 &= LIMB_MASK;
+        h3 += h2 >> 26;
+        h2 &= LIMB_MASK;
+        h4 += h3 >> 26;
+        h3 &= LIMB_MASK;
+        h0 += (h4 >> 26) * 5;
+        h4 &= LIMB_MASK;
+        h1 += h0 >> 26;
+        h0 &= LIMB_MASK;
+
+        // g = h + 5 - 2^130, selected instead of h if it does not underflow
+        let mut g0 = h0 + 5;
+        let mut g1 = h1 + (g0 >> 26);
+        g0 &= LIMB_MASK;
+        let mut g2 = h2 + (g1 >> 26);
+        g1 &= LIMB_MASK;
+        let mut g3 = h3 + (g2 >> 26);
+        g2 &= LIMB_MASK;
+        let g4 = (h4 + (g3 >> 26)).wrapping_sub(1 << 26);
+        g3 &= LIMB_MASK;
+
+        let select_g = (g4 >> 31).wrapping_sub(1);
+        h0 = (h0 & !select_g) | (g0 & select_g);
+        h1 = (h1 & !select_g) | (g1 & select_g);
+        h2 = (h2 & !select_g) | (g2 & select_g);
+        h3 = (h3 & !select_g) | (g3 & select_g);
+        h4 = (h4 & !select_g) | (g4 & select_g);
+
+        // To 4 words of 32 bits, the bits above 2^128 are dropped
+        let words = [
+            h0 | (h1 << 26),
+            (h1 >> 6) | (h2 << 20),
+            (h2 >> 12) | (h3 << 14),
+            (h3 >> 18) | (h4 << 8),
+        ];
+
+        let mut tag = [0u8; TAG_LEN];
+        let mut carry = 0u64;
+        for ((bytes, word), pad) in tag.chunks_exact_mut(4).zip(words.iter()).zip(self.pad.iter()) {
+            carry += *word as u64 + *pad as u64;
+            bytes.copy_from_slice(&(carry as u32).to_le_bytes());
+            carry >>= 32;
+        }
+
+        self.zeroize();
+        tag
+    }
+}
+
+impl Zeroize for Poly1305 {
+    fn zeroize(&mut self) {
+        self.r.zeroize();
+        self.h.zeroize();
+        self.pad.zeroize();
+    }
+}
+
+impl Drop for Poly1305 {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
@@ -3,16 +3,39 @@ pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
+    /// A SubjectPublicKeyInfo could not be decoded or encoded.
     SPKIError(spki::Error),
+    /// A pkcs1 RSAPublicKey or RSAPrivateKey could not be decoded or encoded.
     PKCS1Error(pkcs1::Error),
+    /// A pkcs8 PrivateKeyInfo or its encryption parameters could not be decoded or encoded.
     PKCS8Error(pkcs8::Error),
+    /// A bit string is not byte aligned, contains a description of the value.
     AlignmentError(&'static str),
+    /// The key does not match the key size of the `RsaKey` type or does not fit into its operands.
     RsaKeySizeError,
+    /// The input of a signature scheme does not have the output length of its hash function.
     InputNotHashed,
+    /// The message does not fit into the padding or exceeds the limit of the cipher.
     MessageTooLong,
+    /// The output buffer is too small for the result.
     BufferTooSmall,
+    /// An operation failed that cannot fail for valid inputs.
     Internal,
+    /// A signature or a MAC does not match the message.
     Verification,
+    /// The padding or the authentication tag of a ciphertext is invalid.
+    Decryption,
+    /// The input is not a multiple of the block size, or not exactly one block for rsa operations.
     InvalidBlockSize,
+    /// The input is not valid base64, pem or envelope data, or the parts of a private key do not belong together.
     InvalidEncoding,
+    /// A PEM document has a different label than expected, contains the label that was found.
+    UnexpectedPemLabel(heapless::String<32>),
+    /// The key or its encryption uses an unsupported algorithm, contains the algorithm OID that was found.
+    UnsupportedAlgorithm(spki::ObjectIdentifier),
+    /// A requested output, a key or an iteration count has a size the algorithm does not support.
+    InvalidLength,
+    /// The integrity check of an unwrapped key failed: the wrapped key was modified or wrapped
+    /// under another key-encryption key.
+    KeyUnwrap,
 }
\ No newline at end of file
@@ -0,0 +1,307 @@
+use core::marker::PhantomData;
+
+use esp_hal::peripheral::Peripheral;
+use esp_hal::peripherals::SHA;
+use esp_hal::sha::{Sha, ShaMode};
+use esp_hal::Blocking;
+use log::error;
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+
+use super::sha::{Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, HashFunction, HashMode, Sha384, Sha512};
+use super::sha512;
+
+
+/// Number of H registers, SHA-1 only uses the first five.
+/// SHA-384/512 keep their eight 64 bit words in the same state.
+const STATE_WORDS: usize = 8;
+
+
+/// Value of the MODE register, `None` for the algorithms computed in software.
+fn hardware_mode(mode: HashMode) -> Option<u8> {
+    match mode {
+        HashMode::SHA1 => Some(0),
+        HashMode::SHA224 => Some(1),
+        HashMode::SHA256 => Some(2),
+        HashMode::SHA384 | HashMode::SHA512 => None,
+    }
+}
+
+
+/// The SHA accelerator shared by any number of `HashContext`s.
+///
+/// A context only occupies the peripheral while one of its methods runs: the intermediate
+/// H registers are restored before and saved after processing, so independent digests can be
+/// interleaved in any order.
+pub struct ShaAccelerator {
+    // Owns the peripheral and keeps its clock enabled, the registers are driven directly.
+    _sha: Sha<'static, Blocking>,
+}
+
+impl ShaAccelerator {
+    pub fn new(
+        sha_peripheral: impl Peripheral<P = SHA> + 'static,
+    ) -> Self {
+        Self {
+            _sha: Sha::new(sha_peripheral, ShaMode::SHA256, None),
+        }
+    }
+
+    fn registers(&mut self) -> &<SHA as core::ops::Deref>::Target {
+        // SAFETY: `new` moved the SHA peripheral singleton into `_sha` for the lifetime of the accelerator,
+        // so no other `Sha` driver can be created for it without `Peripherals::steal`, and `_sha` itself is
+        // never used to hash. `&mut self` keeps contexts on this accelerator from using the registers at once.
+        unsafe { &*SHA::ptr() }
+    }
+
+    /// Selects the algorithm and loads a saved state, a first block starts from the initial state instead.
+    fn load(&mut self, mode_bits: u8, state: &[u64; STATE_WORDS], first_block: bool) {
+        let registers = self.registers();
+        // SAFETY: `hardware_mode` only returns the valid MODE values 0 to 2.
+        registers.mode().write(|w| unsafe { w.mode().bits(mode_bits) });
+
+        if !first_block {
+            for (i, word) in state.iter().enumerate() {
+                registers.h_mem(i).write(|w| unsafe { w.bits(*word as u32) });
+            }
+        }
+    }
+
+    /// Processes one block, the first block of a message uses START, all following CONTINUE.
+    fn process_block(&mut self, block: &[u8], first_block: bool) {
+        let registers = self.registers();
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            let word = u32::from_ne_bytes([word[0], word[1], word[2], word[3]]);
+            registers.m_mem(i).write(|w| unsafe { w.bits(word) });
+        }
+
+        if first_block {
+            registers.start().write(|w| unsafe { w.bits(1) });
+        } else {
+            registers.continue_().write(|w| unsafe { w.bits(1) });
+        }
+
+        while registers.busy().read().bits() != 0 {}
+    }
+
+    fn save(&mut self, state: &mut [u64; STATE_WORDS]) {
+        let registers = self.registers();
+        for (i, word) in state.iter_mut().enumerate() {
+            *word = registers.h_mem(i).read().bits() as u64;
+        }
+    }
+}
+
+
+/// State of one streaming digest, kept in memory while other contexts use the accelerator.
+///
+/// Cloning a context forks the digest, e.g. to reuse a precomputed prefix.
+pub struct HashContext<HA: HashFunction> {
+    state: [u64; STATE_WORDS],
+    buffer: HA::Block,
+    buffer_len: usize,
+    processed_blocks: u64,
+    phantom: PhantomData<HA>
+}
+
+impl<HA: HashFunction> HashContext<HA> {
+    pub fn new() -> Self {
+        Self {
+            state: [0; STATE_WORDS],
+            buffer: HA::EMPTY_BLOCK,
+            buffer_len: 0,
+            processed_blocks: 0,
+            phantom: PhantomData
+        }
+    }
+
+    /// Feeds `data` into the digest, only complete blocks are processed on the accelerator
+    /// or, for SHA-384/512, in software.
+    pub fn update(&mut self, sha: &mut ShaAccelerator, data: &[u8]) {
+        let mut remaining = data;
+        let mut loaded = false;
+
+        while !remaining.is_empty() {
+            let take = core::cmp::min(HA::BLOCK_LEN - self.buffer_len, remaining.len());
+            self.buffer.as_mut()[self.buffer_len..self.buffer_len + take].copy_from_slice(&remaining[..take]);
+            self.buffer_len += take;
+            remaining = &remaining[take..];
+
+            if self.buffer_len == HA::BLOCK_LEN {
+                let first_block = self.processed_blocks == 0;
+                match hardware_mode(HA::MODE) {
+                    Some(mode_bits) => {
+                        if !loaded {
+                            sha.load(mode_bits, &self.state, first_block);
+                            loaded = true;
+                        }
+
+                        sha.process_block(self.buffer.as_ref(), first_block);
+                    },
+                    None => {
+                        if first_block {
+                            self.state = match HA::MODE {
+                                HashMode::SHA384 => sha512::SHA384_INITIAL_STATE,
+                                _ => sha512::SHA512_INITIAL_STATE,
+                            };
+                        }
+
+                        sha512::compress(&mut self.state, self.buffer.as_ref());
+                    }
+                }
+                self.processed_blocks += 1;
+                self.buffer_len = 0;
+            }
+        }
+
+        if loaded {
+            sha.save(&mut self.state);
+        }
+    }
+
+    /// Writes the digest of all data passed to `update` to `out` and resets the context.
+    pub fn finalize_into<'a>(&mut self, sha: &mut ShaAccelerator, out: &'a mut [u8]) -> Result<&'a [u8]> {
+        if out.len() < HA::OUTPUT_LEN {
+            error!("Output buffer is smaller then the output length of hash algorithm {:?}", HA::MODE);
+            return Err(Error::BufferTooSmall);
+        }
+
+        self.finish(sha, out);
+
+        Ok(&out[..HA::OUTPUT_LEN])
+    }
+
+    /// Discards all data passed to `update`.
+    pub fn reset(&mut self) {
+        self.zeroize();
+    }
+
+    /// Like `finalize_into` without the length check, `out` has to hold at least `HA::OUTPUT_LEN` bytes.
+    pub(crate) fn finish(&mut self, sha: &mut ShaAccelerator, out: &mut [u8]) {
+        let bit_len = ((self.processed_blocks as u128 * HA::BLOCK_LEN as u128 + self.buffer_len as u128) * 8).to_be_bytes();
+
+        // Padding: 0x80, zeros and the message length in bits as big endian integer,
+        // 64 bit for 64 byte blocks and 128 bit for the 128 byte blocks of SHA-384/512
+        let length_len = HA::BLOCK_LEN / 8;
+        self.update(sha, &[0x80]);
+        let zeros_len = (2 * HA::BLOCK_LEN - length_len - self.buffer_len) % HA::BLOCK_LEN;
+        self.update(sha, &HA::EMPTY_BLOCK.as_ref()[..zeros_len]);
+        self.update(sha, &bit_len[16 - length_len..]);
+
+        let out = &mut out[..HA::OUTPUT_LEN];
+        if hardware_mode(HA::MODE).is_some() {
+            for (bytes, word) in out.chunks_exact_mut(4).zip(self.state.iter()) {
+                bytes.copy_from_slice(&(*word as u32).to_ne_bytes());
+            }
+        } else {
+            for (bytes, word) in out.chunks_exact_mut(8).zip(self.state.iter()) {
+                bytes.copy_from_slice(&word.to_be_bytes());
+            }
+        }
+
+        self.reset();
+    }
+}
+
+impl<HA: HashFunction> Default for HashContext<HA> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<HA: HashFunction> Clone for HashContext<HA> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state,
+            buffer: self.buffer,
+            buffer_len: self.buffer_len,
+            processed_blocks: self.processed_blocks,
+            phantom: PhantomData
+        }
+    }
+}
+
+impl<HA: HashFunction> Zeroize for HashContext<HA> {
+    fn zeroize(&mut self) {
+        self.state.zeroize();
+        self.buffer.as_mut().zeroize();
+        self.buffer_len = 0;
+        self.processed_blocks = 0;
+    }
+}
+
+impl<HA: HashFunction> Drop for HashContext<HA> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+
+/// A `HashContext` whose algorithm is only known at runtime, e.g. the one bound to a signature scheme.
+pub enum DynHashContext {
+    Sha1(HashContext<Esp32C3Sha1>),
+    Sha224(HashContext<Esp32C3Sha224>),
+    Sha256(HashContext<Esp32C3Sha256>),
+    Sha384(HashContext<Sha384>),
+    Sha512(HashContext<Sha512>),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $context:ident => $e:expr) => {
+        match $self {
+            DynHashContext::Sha1($context) => $e,
+            DynHashContext::Sha224($context) => $e,
+            DynHashContext::Sha256($context) => $e,
+            DynHashContext::Sha384($context) => $e,
+            DynHashContext::Sha512($context) => $e,
+        }
+    };
+}
+
+impl DynHashContext {
+    pub fn new(algorithm: HashMode) -> Self {
+        match algorithm {
+            HashMode::SHA1 => Self::Sha1(HashContext::new()),
+            HashMode::SHA224 => Self::Sha224(HashContext::new()),
+            HashMode::SHA256 => Self::Sha256(HashContext::new()),
+            HashMode::SHA384 => Self::Sha384(HashContext::new()),
+            HashMode::SHA512 => Self::Sha512(HashContext::new()),
+        }
+    }
+
+    /// Feeds `data` into the digest.
+    pub fn update(&mut self, sha: &mut ShaAccelerator, data: &[u8]) {
+        dispatch!(self, context => context.update(sha, data))
+    }
+
+    /// Writes the digest of all data passed to `update` to `out` and resets the context.
+    pub fn finalize_into<'a>(&mut self, sha: &mut ShaAccelerator, out: &'a mut [u8]) -> Result<&'a [u8]> {
+        dispatch!(self, context => context.finalize_into(sha, out))
+    }
+
+    /// Discards all data passed to `update`.
+    pub fn reset(&mut self) {
+        dispatch!(self, context => context.reset())
+    }
+
+    pub fn algorithm(&self) -> HashMode {
+        match self {
+            Self::Sha1(_) => HashMode::SHA1,
+            Self::Sha224(_) => HashMode::SHA224,
+            Self::Sha256(_) => HashMode::SHA256,
+            Self::Sha384(_) => HashMode::SHA384,
+            Self::Sha512(_) => HashMode::SHA512,
+        }
+    }
+
+    pub fn output_len(&self) -> usize {
+        match self {
+            Self::Sha1(_) => Esp32C3Sha1::OUTPUT_LEN,
+            Self::Sha224(_) => Esp32C3Sha224::OUTPUT_LEN,
+            Self::Sha256(_) => Esp32C3Sha256::OUTPUT_LEN,
+            Self::Sha384(_) => Sha384::OUTPUT_LEN,
+            Self::Sha512(_) => Sha512::OUTPUT_LEN,
+        }
+    }
+}
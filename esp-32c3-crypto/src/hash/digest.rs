@@ -0,0 +1,54 @@
+//! RustCrypto `digest` traits for `Hash`, so it can be passed to crates generic over `digest::Update`/`FixedOutput`.
+//!
+//! `digest::Digest` additionally requires `Default`, which a hasher owning the SHA peripheral cannot provide.
+
+use ::digest::consts::{U128, U20, U28, U32, U48, U64};
+use ::digest::{FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update};
+use ::digest::crypto_common::BlockSizeUser;
+
+use super::sha::{Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, Hash, Sha384, Sha512};
+
+
+macro_rules! impl_digest {
+    ($algorithm:ty, $output_size:ty, $block_size:ty) => {
+        impl HashMarker for Hash<$algorithm> {}
+
+        impl OutputSizeUser for Hash<$algorithm> {
+            type OutputSize = $output_size;
+        }
+
+        impl BlockSizeUser for Hash<$algorithm> {
+            type BlockSize = $block_size;
+        }
+
+        impl Update for Hash<$algorithm> {
+            fn update(&mut self, data: &[u8]) {
+                Hash::update(self, data);
+            }
+        }
+
+        impl FixedOutput for Hash<$algorithm> {
+            fn finalize_into(mut self, out: &mut Output<Self>) {
+                self.finish(out);
+            }
+        }
+
+        impl Reset for Hash<$algorithm> {
+            fn reset(&mut self) {
+                Hash::reset(self);
+            }
+        }
+
+        impl FixedOutputReset for Hash<$algorithm> {
+            fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                self.finish(out);
+            }
+        }
+    };
+}
+
+impl_digest!(Esp32C3Sha1, U20, U64);
+impl_digest!(Esp32C3Sha224, U28, U64);
+impl_digest!(Esp32C3Sha256, U32, U64);
+impl_digest!(Sha384, U48, U128);
+impl_digest!(Sha512, U64, U128);
@@ -0,0 +1,144 @@
+use crypto_bigint::subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+
+use super::context::{HashContext, ShaAccelerator};
+use super::sha::{Hash, HashFunction, MAX_HASH_LEN};
+
+
+/// HMAC (RFC 2104) with the hash algorithm `HA` on the SHA accelerator.
+///
+/// The padded key is hashed once in `new`, every MAC continues from the saved inner and outer state.
+pub struct Hmac<HA: HashFunction> {
+    hash: Hash<HA>,
+    key: HmacKey<HA>,
+    context: HashContext<HA>,
+}
+
+impl<HA: HashFunction> Hmac<HA> {
+    /// Keys longer than the block length of `HA` are replaced by their digest.
+    pub fn new(mut hash: Hash<HA>, key: &[u8]) -> Self {
+        let key = HmacKey::new(hash.accelerator(), key);
+        let context = key.inner();
+
+        Self {
+            hash,
+            key,
+            context
+        }
+    }
+
+    /// Computes the MAC of `data` in one go, data passed to `update` before is discarded.
+    pub fn mac<'a>(&mut self, data: &[u8], out: &'a mut [u8]) -> Result<&'a [u8]> {
+        self.reset();
+        self.update(data);
+        self.finalize_into(out)
+    }
+
+    /// Feeds `data` into the running MAC computation.
+    pub fn update(&mut self, data: &[u8]) {
+        self.context.update(self.hash.accelerator(), data);
+    }
+
+    /// Writes the MAC of all data passed to `update` since the last `finalize_into` or `reset` to `out`
+    /// and starts a new computation with the same key.
+    pub fn finalize_into<'a>(&mut self, out: &'a mut [u8]) -> Result<&'a [u8]> {
+        if out.len() < HA::OUTPUT_LEN {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let context = core::mem::replace(&mut self.context, self.key.inner());
+        self.key.finish(self.hash.accelerator(), context, out);
+
+        Ok(&out[..HA::OUTPUT_LEN])
+    }
+
+    /// Discards all data passed to `update` since the last `finalize_into`.
+    pub fn reset(&mut self) {
+        self.context = self.key.inner();
+    }
+
+    /// Checks in constant time that `tag` is the MAC of `data`.
+    pub fn verify(&mut self, data: &[u8], tag: &[u8]) -> Result<()> {
+        self.reset();
+        self.update(data);
+        self.finalize_verify(tag)
+    }
+
+    /// Checks in constant time that `tag` is the MAC of all data passed to `update`.
+    pub fn finalize_verify(&mut self, tag: &[u8]) -> Result<()> {
+        let mut mac = [0u8; MAX_HASH_LEN];
+        let mac_len = self.finalize_into(&mut mac)?.len();
+
+        let valid = mac[..mac_len].ct_eq(tag);
+        mac.zeroize();
+
+        if valid.into() {
+            Ok(())
+        } else {
+            Err(Error::Verification)
+        }
+    }
+
+    /// Consumes the MAC and returns the hasher.
+    pub fn into_inner(self) -> Hash<HA> {
+        self.hash
+    }
+
+    pub fn output_len(&self) -> usize {
+        HA::OUTPUT_LEN
+    }
+}
+
+
+/// Hash states after the inner and outer padded key block, shared by all MACs with the same key.
+pub(crate) struct HmacKey<HA: HashFunction> {
+    inner: HashContext<HA>,
+    outer: HashContext<HA>,
+}
+
+impl<HA: HashFunction> HmacKey<HA> {
+    pub(crate) fn new(sha: &mut ShaAccelerator, key: &[u8]) -> Self {
+        let mut block = HA::EMPTY_BLOCK;
+        if key.len() > HA::BLOCK_LEN {
+            let mut context = HashContext::<HA>::new();
+            context.update(sha, key);
+            context.finish(sha, block.as_mut());
+        } else {
+            block.as_mut()[..key.len()].copy_from_slice(key);
+        }
+
+        let mut pad = HA::EMPTY_BLOCK;
+        let mut inner = HashContext::new();
+        let mut outer = HashContext::new();
+        for (context, pad_byte) in [(&mut inner, 0x36u8), (&mut outer, 0x5cu8)] {
+            for (p, k) in pad.as_mut().iter_mut().zip(block.as_ref().iter()) {
+                *p = k ^ pad_byte;
+            }
+            context.update(sha, pad.as_ref());
+        }
+
+        block.as_mut().zeroize();
+        pad.as_mut().zeroize();
+
+        Self { inner, outer }
+    }
+
+    /// A context to feed the message into.
+    pub(crate) fn inner(&self) -> HashContext<HA> {
+        self.inner.clone()
+    }
+
+    /// Writes the MAC of the message fed into `inner` to `out`, `out` has to hold at least `HA::OUTPUT_LEN` bytes.
+    pub(crate) fn finish(&self, sha: &mut ShaAccelerator, mut inner: HashContext<HA>, out: &mut [u8]) {
+        let mut digest = [0u8; MAX_HASH_LEN];
+        inner.finish(sha, &mut digest);
+
+        let mut outer = self.outer.clone();
+        outer.update(sha, &digest[..HA::OUTPUT_LEN]);
+        outer.finish(sha, out);
+
+        digest.zeroize();
+    }
+}
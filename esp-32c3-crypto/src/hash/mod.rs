@@ -1 +1,5 @@
-pub mod sha;
\ No newline at end of file
+pub mod sha;
+pub mod context;
+pub mod hmac;
+mod sha512;
+mod digest;
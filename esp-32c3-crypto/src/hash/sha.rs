@@ -1,19 +1,65 @@
-use core::marker::PhantomData;
-
 use esp_hal::peripheral::Peripheral;
-use esp_hal::prelude::nb::block;
-use esp_hal::sha::{Sha, ShaMode};
 use esp_hal::peripherals::SHA;
-use esp_hal::Blocking;
+use esp_hal::sha::ShaMode;
 use log::error;
 
+use super::context::{HashContext, ShaAccelerator};
+
+
+/// Output length of the biggest supported hash algorithm.
+pub(crate) const MAX_HASH_LEN: usize = 64;
+
+/// All supported hash algorithms: SHA-1 and SHA-224/256 run on the SHA accelerator,
+/// SHA-384/512 are computed in software.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashMode {
+    SHA1,
+    SHA224,
+    SHA256,
+    SHA384,
+    SHA512,
+}
+
+impl HashMode {
+    pub const fn from_sha_mode(mode: ShaMode) -> Self {
+        match mode {
+            ShaMode::SHA1 => Self::SHA1,
+            ShaMode::SHA224 => Self::SHA224,
+            ShaMode::SHA256 => Self::SHA256,
+        }
+    }
+}
 
+/// Hash algorithms of the SHA accelerator.
 pub trait HashAlgorithm {
     const hash_algorithm: ShaMode;
     const output_len: usize;
     const prefix_len: usize;
 }
 
+/// Any hash function `Hash`, `HashContext`, `Hmac` and the padding schemes work with:
+/// every `HashAlgorithm` of the SHA accelerator and SHA-384/512 in software.
+pub trait HashFunction {
+    const MODE: HashMode;
+    const OUTPUT_LEN: usize;
+    const PREFIX_LEN: usize;
+    const BLOCK_LEN: usize;
+
+    /// A `[u8; BLOCK_LEN]`, so a context only buffers as much as its own algorithm needs.
+    type Block: Copy + AsRef<[u8]> + AsMut<[u8]>;
+    const EMPTY_BLOCK: Self::Block;
+}
+
+impl<HA: HashAlgorithm> HashFunction for HA {
+    const MODE: HashMode = HashMode::from_sha_mode(HA::hash_algorithm);
+    const OUTPUT_LEN: usize = HA::output_len;
+    const PREFIX_LEN: usize = HA::prefix_len;
+    const BLOCK_LEN: usize = 64;
+
+    type Block = [u8; 64];
+    const EMPTY_BLOCK: Self::Block = [0; 64];
+}
+
 
 pub struct Esp32C3Sha256;
 
@@ -37,48 +83,111 @@ impl HashAlgorithm for Esp32C3Sha1 {
     const prefix_len: usize = 15;
 }
 
-pub struct Hash<HA: HashAlgorithm> {
-    sha: Sha<'static, Blocking>,
-    phantom: PhantomData<HA>
+/// SHA-384 in software, a `Hash<Sha384>` still takes the SHA peripheral so it can be used like the other algorithms.
+pub struct Sha384;
+impl HashFunction for Sha384 {
+    const MODE: HashMode = HashMode::SHA384;
+    const OUTPUT_LEN: usize = 48;
+    const PREFIX_LEN: usize = 19;
+    const BLOCK_LEN: usize = 128;
+
+    type Block = [u8; 128];
+    const EMPTY_BLOCK: Self::Block = [0; 128];
 }
 
-impl<HA: HashAlgorithm> Hash<HA> {
+/// SHA-512 in software, a `Hash<Sha512>` still takes the SHA peripheral so it can be used like the other algorithms.
+pub struct Sha512;
+impl HashFunction for Sha512 {
+    const MODE: HashMode = HashMode::SHA512;
+    const OUTPUT_LEN: usize = 64;
+    const PREFIX_LEN: usize = 19;
+    const BLOCK_LEN: usize = 128;
+
+    type Block = [u8; 128];
+    const EMPTY_BLOCK: Self::Block = [0; 128];
+}
+
+/// A single streaming digest on its own `ShaAccelerator`.
+/// Use `HashContext`s on a shared accelerator to interleave several digests.
+pub struct Hash<HA: HashFunction> {
+    sha: ShaAccelerator,
+    context: HashContext<HA>
+}
+
+impl<HA: HashFunction> Hash<HA> {
     pub fn new(
         sha_peripheral: impl Peripheral<P = SHA> + 'static,
     ) -> Self {
-        Self { 
-            sha: Sha::new(sha_peripheral, HA::hash_algorithm, None),
-            phantom: PhantomData
+        Self::from_accelerator(ShaAccelerator::new(sha_peripheral))
+    }
+
+    pub fn from_accelerator(sha: ShaAccelerator) -> Self {
+        Self {
+            sha,
+            context: HashContext::new()
         }
     }
 
+    /// Hashes `data` in one go, data passed to `update` before is discarded.
     pub fn hash<'a>(
         &mut self,
         data: &[u8],
         out: &'a mut [u8]
     ) -> Result<&'a [u8], ()> {
-        if out.len() < HA::output_len {
-            error!("Output buffer is smaller then the output length of hash algorithm {:?}", HA::hash_algorithm);
+        if out.len() < HA::OUTPUT_LEN {
+            error!("Output buffer is smaller then the output length of hash algorithm {:?}", HA::MODE);
             return Err(());
         }
 
-        let mut remaining = data;
-        while remaining.len() > 0 {
-            remaining = block!(self.sha.update(remaining))
-                .expect(".update() should never fail.");
-        }
-        
-        block!(self.sha.finish(out))
-            .expect(".finish() should never fail.");
+        self.reset();
+        self.update(data);
+        self.finish(out);
 
-        Ok(&out[..HA::output_len])
+        Ok(&out[..HA::OUTPUT_LEN])
     }
 
-    pub fn algorithm(&self) -> ShaMode {
-        HA::hash_algorithm
+    /// Feeds `data` into the running hash computation, the state is kept between calls
+    /// so a message can be hashed in chunks of any size.
+    pub fn update(&mut self, data: &[u8]) {
+        self.context.update(&mut self.sha, data);
+    }
+
+    /// Writes the digest of all data passed to `update` since the last `finalize_into` or `reset` to `out`
+    /// and starts a new computation.
+    pub fn finalize_into<'a>(&mut self, out: &'a mut [u8]) -> crate::error::Result<&'a [u8]> {
+        self.context.finalize_into(&mut self.sha, out)
+    }
+
+    /// Discards all data passed to `update` since the last `finalize_into`.
+    pub fn reset(&mut self) {
+        self.context.reset();
+    }
+
+    /// Like `finalize_into` without the length check, `out` has to hold at least `HA::OUTPUT_LEN` bytes.
+    pub(crate) fn finish(&mut self, out: &mut [u8]) {
+        self.context.finish(&mut self.sha, out);
+    }
+
+    /// The accelerator of this hash, to run further `HashContext`s on it in between.
+    pub fn accelerator(&mut self) -> &mut ShaAccelerator {
+        &mut self.sha
+    }
+
+    pub fn into_accelerator(self) -> ShaAccelerator {
+        self.sha
+    }
+
+    pub fn mode(&self) -> HashMode {
+        HA::MODE
     }
 
     pub fn output_len(&self) -> usize {
-        HA::output_len
+        HA::OUTPUT_LEN
+    }
+}
+
+impl<HA: HashAlgorithm> Hash<HA> {
+    pub fn algorithm(&self) -> ShaMode {
+        HA::hash_algorithm
     }
 }
\ No newline at end of file
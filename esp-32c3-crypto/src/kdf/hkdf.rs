@@ -0,0 +1,64 @@
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+use crate::hash::hmac::HmacKey;
+use crate::hash::sha::{Hash, HashFunction, MAX_HASH_LEN};
+
+
+/// HKDF-Extract (RFC 5869): writes PRK = HMAC-Hash(salt, IKM) to `prk` and returns it.
+/// An empty salt is replaced by `HA::OUTPUT_LEN` zero bytes.
+pub fn extract<'a, HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    salt: &[u8],
+    ikm: &[u8],
+    prk: &'a mut [u8]
+) -> Result<&'a [u8]> {
+    if prk.len() < HA::OUTPUT_LEN {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let zero_salt = [0u8; MAX_HASH_LEN];
+    let salt = if salt.is_empty() { &zero_salt[..HA::OUTPUT_LEN] } else { salt };
+
+    let sha = hash.accelerator();
+    let key = HmacKey::<HA>::new(sha, salt);
+    let mut context = key.inner();
+    context.update(sha, ikm);
+    key.finish(sha, context, prk);
+
+    Ok(&prk[..HA::OUTPUT_LEN])
+}
+
+/// HKDF-Expand (RFC 5869): fills `okm` with key material derived from `prk` and `info`.
+/// `okm` can be at most 255 * `HA::OUTPUT_LEN` bytes long, `prk` has to be at least `HA::OUTPUT_LEN` bytes.
+pub fn expand<HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    prk: &[u8],
+    info: &[u8],
+    okm: &mut [u8]
+) -> Result<()> {
+    if okm.len() > 255 * HA::OUTPUT_LEN || prk.len() < HA::OUTPUT_LEN {
+        return Err(Error::InvalidLength);
+    }
+
+    let sha = hash.accelerator();
+    let key = HmacKey::<HA>::new(sha, prk);
+
+    // T(i) = HMAC-Hash(PRK, T(i - 1) | info | i), T(0) is empty
+    let mut t = [0u8; MAX_HASH_LEN];
+    for (index, chunk) in okm.chunks_mut(HA::OUTPUT_LEN).enumerate() {
+        let mut context = key.inner();
+        if index > 0 {
+            context.update(sha, &t[..HA::OUTPUT_LEN]);
+        }
+        context.update(sha, info);
+        context.update(sha, &[index as u8 + 1]);
+        key.finish(sha, context, &mut t);
+
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+
+    t.zeroize();
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+use crate::hash::context::ShaAccelerator;
+use crate::hash::hmac::HmacKey;
+use crate::hash::sha::{Hash, HashFunction, MAX_HASH_LEN};
+
+
+/// Derives `out.len()` bytes from `password` and `salt` with PBKDF2 (RFC 8018) using HMAC with `HA` as PRF,
+/// e.g. a WPA2 PSK is `pbkdf2(&mut Hash::<Esp32C3Sha1>::new(sha), passphrase, ssid, 4096, &mut psk)` with a 32 byte `psk`.
+///
+/// The inner and outer HMAC states of the padded password are computed once and restored on the SHA
+/// accelerator behind `hash` for every iteration, so an iteration costs two block operations.
+/// `iterations` has to be at least one.
+pub fn pbkdf2<HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    out: &mut [u8]
+) -> Result<()> {
+    pbkdf2_on_accelerator::<HA>(hash.accelerator(), password, salt, iterations, out)
+}
+
+/// `pbkdf2` on a bare accelerator, for callers that pick the PRF at runtime.
+pub(crate) fn pbkdf2_on_accelerator<HA: HashFunction>(
+    sha: &mut ShaAccelerator,
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    out: &mut [u8]
+) -> Result<()> {
+    if iterations == 0 {
+        return Err(Error::InvalidLength);
+    }
+
+    let key = HmacKey::<HA>::new(sha, password);
+
+    let mut u = [0u8; MAX_HASH_LEN];
+    let mut t = [0u8; MAX_HASH_LEN];
+    let mut previous = [0u8; MAX_HASH_LEN];
+    for (index, chunk) in out.chunks_mut(HA::OUTPUT_LEN).enumerate() {
+        // U_1 = PRF(P, S || INT(i))
+        let block_index = (index as u32 + 1).to_be_bytes();
+        hmac(sha, &key, &[salt, &block_index], &mut u);
+        t.copy_from_slice(&u);
+
+        // U_j = PRF(P, U_{j-1}), T_i = U_1 ^ ... ^ U_c
+        for _ in 1..iterations {
+            previous.copy_from_slice(&u);
+            hmac(sha, &key, &[&previous[..HA::OUTPUT_LEN]], &mut u);
+            for (t, u) in t.iter_mut().zip(u.iter()) {
+                *t ^= u;
+            }
+        }
+
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+
+    u.zeroize();
+    t.zeroize();
+    previous.zeroize();
+
+    Ok(())
+}
+
+/// HMAC over the concatenation of `parts`.
+fn hmac<HA: HashFunction>(
+    sha: &mut ShaAccelerator,
+    key: &HmacKey<HA>,
+    parts: &[&[u8]],
+    out: &mut [u8; MAX_HASH_LEN]
+) {
+    let mut context = key.inner();
+    for part in parts {
+        context.update(sha, part);
+    }
+    key.finish(sha, context, out);
+}
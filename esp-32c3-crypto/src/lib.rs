@@ -1,9 +1,22 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 #![feature(generic_const_exprs)]
 
+#[cfg(feature = "esp32c3")]
 pub mod hash;
+#[cfg(feature = "esp32c3")]
 pub mod rsa;
+#[cfg(feature = "esp32c3")]
 pub mod padding;
+#[cfg(feature = "esp32c3")]
+pub mod kdf;
+#[cfg(feature = "esp32c3")]
 mod utils;
+#[cfg(feature = "esp32c3")]
+mod pem;
+#[cfg(feature = "esp32c3")]
+mod pkcs5;
 pub mod error;
+#[cfg(feature = "esp32c3")]
 pub mod traits;
+pub mod aes;
+pub mod chacha20poly1305;
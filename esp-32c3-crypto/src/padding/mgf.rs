@@ -0,0 +1,44 @@
+use zeroize::Zeroize;
+
+use crate::{
+    error::{Error, Result},
+    hash::sha::{Hash, HashFunction, MAX_HASH_LEN},
+    rsa::MAX_BLOCKSIZE,
+};
+
+
+/// Largest seed that can be fed into `mgf1_xor`, the block size of the biggest supported key.
+const MAX_SEED_LEN: usize = MAX_BLOCKSIZE;
+
+/// Mask generation function MGF1 as defined in RFC 8017 B.2.1.
+/// The generated mask is xored into `out`.
+pub(crate) fn mgf1_xor<HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    seed: &[u8],
+    out: &mut [u8]
+) -> Result<()> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(Error::Internal);
+    }
+
+    // seed || counter
+    let mut input = [0u8; MAX_SEED_LEN + 4];
+    input[..seed.len()].copy_from_slice(seed);
+
+    let mut digest = [0u8; MAX_HASH_LEN];
+
+    for (counter, chunk) in out.chunks_mut(HA::OUTPUT_LEN).enumerate() {
+        input[seed.len()..seed.len() + 4].copy_from_slice(&(counter as u32).to_be_bytes());
+
+        let mask = hash.hash(&input[..seed.len() + 4], &mut digest)
+            .map_err(|_| Error::Internal)?;
+
+        for (o, &m) in chunk.iter_mut().zip(mask.iter()) {
+            *o ^= m;
+        }
+    }
+
+    input.zeroize();
+    digest.zeroize();
+    Ok(())
+}
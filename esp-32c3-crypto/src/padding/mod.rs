@@ -1,4 +1,4 @@
 pub mod oaep;
 pub mod pkcs1v15;
-
-
+pub mod pss;
+mod mgf;
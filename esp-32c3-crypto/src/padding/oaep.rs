@@ -0,0 +1,218 @@
+use core::cell::RefCell;
+
+use crypto_bigint::subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use esp_hal::{rng::Rng, rsa::Rsa, Blocking};
+use zeroize::Zeroize;
+
+use crate::{
+    error::{Error, Result},
+    hash::sha::{Hash, HashFunction, MAX_HASH_LEN},
+    rsa::{Decrypt, Encrypt, RsaKey, RsaPrivateKey, RsaPublicKey},
+    traits::PaddingScheme
+};
+
+use super::mgf::mgf1_xor;
+
+
+/// RSAES-OAEP padding scheme (RFC 8017 7.1).
+/// The hash algorithm `HA` is used for the label hash as well as for MGF1.
+pub struct Oaep<HA: HashFunction> {
+    hash: RefCell<Hash<HA>>,
+    label_hash: [u8; MAX_HASH_LEN],
+}
+
+impl<HA: HashFunction> Oaep<HA> {
+    /// Creates the padding scheme with an empty label.
+    pub fn new(hash: Hash<HA>) -> Self {
+        Self::new_with_label(hash, &[])
+    }
+
+    /// Creates the padding scheme with the given label.
+    /// The same label has to be used for encryption and decryption.
+    pub fn new_with_label(mut hash: Hash<HA>, label: &[u8]) -> Self {
+        let mut label_hash = [0u8; MAX_HASH_LEN];
+        hash.hash(label, &mut label_hash)
+            .expect("label_hash fits the output of every hash algorithm");
+
+        Self {
+            hash: RefCell::new(hash),
+            label_hash
+        }
+    }
+
+    /// Consumes the padding scheme and returns the hasher.
+    pub fn into_inner(self) -> Hash<HA> {
+        self.hash.into_inner()
+    }
+
+    fn label_hash(&self) -> &[u8] {
+        &self.label_hash[..HA::OUTPUT_LEN]
+    }
+}
+
+impl<T, HA> PaddingScheme<T> for Oaep<HA>
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]>,
+    HA: HashFunction,
+    [(); T::BLOCKSIZE]: Sized,
+{
+    fn encrypt<'a>(
+        &self,
+        rsa: &mut Rsa<Blocking>,
+        rng: &mut Rng,
+        pub_key: &RsaPublicKey<T>,
+        plaintext: &[u8],
+        ciphertext_buffer: &'a mut [u8]
+    )
+    -> Result<&'a [u8]>
+    where
+        T: Encrypt<T>
+    {
+        if ciphertext_buffer.len() < T::BLOCKSIZE {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let mut em = [0u8; T::BLOCKSIZE];
+        oaep_encode(
+            &mut self.hash.borrow_mut(),
+            rng,
+            self.label_hash(),
+            plaintext,
+            &mut em
+        )?;
+
+        // The rsa peripheral expects its operands in little endian
+        em.reverse();
+        let result = T::encrypt(rsa, pub_key, &em, ciphertext_buffer);
+        em.zeroize();
+        result
+    }
+
+    fn decrypt<'a>(
+        &self,
+        rsa: &mut Rsa<Blocking>,
+        rng: &mut Rng,
+        priv_key: &RsaPrivateKey<T>,
+        ciphertext: &[u8],
+        plaintext_buffer: &'a mut [u8]
+    )
+    -> Result<&'a [u8]>
+    where
+        T: Decrypt<T>
+    {
+        if ciphertext.len() != T::BLOCKSIZE {
+            return Err(Error::InvalidBlockSize);
+        }
+
+        if T::BLOCKSIZE < 2 * HA::OUTPUT_LEN + 2 {
+            return Err(Error::Decryption);
+        }
+
+        if plaintext_buffer.len() < T::BLOCKSIZE - 2 * HA::OUTPUT_LEN - 2 {
+            return Err(Error::BufferTooSmall);
+        }
+
+        // Write ciphertext in le to cipher_buffer
+        let mut cipher_buffer = [0u8; T::BLOCKSIZE];
+        for (i, &b) in ciphertext.iter().rev().enumerate() {
+            cipher_buffer[i] = b;
+        }
+
+        let mut em = [0u8; T::BLOCKSIZE];
+        T::decrypt(rsa, rng, priv_key, &cipher_buffer, &mut em)?;
+
+        let result = oaep_decode(
+            &mut self.hash.borrow_mut(),
+            self.label_hash(),
+            &mut em,
+            plaintext_buffer
+        );
+
+        em.zeroize();
+        cipher_buffer.zeroize();
+        result
+    }
+}
+
+
+/// EME-OAEP encoding, writes the big endian encoded message to `em`.
+fn oaep_encode<HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    rng: &mut Rng,
+    label_hash: &[u8],
+    plaintext: &[u8],
+    em: &mut [u8]
+) -> Result<()> {
+    let k = em.len();
+    let h_len = label_hash.len();
+
+    if k < 2 * h_len + 2 || plaintext.len() > k - 2 * h_len - 2 {
+        return Err(Error::MessageTooLong);
+    }
+
+    // EM = 0x00 || maskedSeed || maskedDB
+    em[0] = 0;
+    let (seed, db) = em[1..].split_at_mut(h_len);
+
+    // DB = lHash || PS || 0x01 || M
+    let db_len = db.len();
+    db[..h_len].copy_from_slice(label_hash);
+    db[h_len..db_len - plaintext.len() - 1].fill(0);
+    db[db_len - plaintext.len() - 1] = 1;
+    db[db_len - plaintext.len()..].copy_from_slice(plaintext);
+
+    rng.read(seed);
+
+    mgf1_xor(hash, seed, db)?;
+    mgf1_xor(hash, db, seed)?;
+
+    Ok(())
+}
+
+
+/// EME-OAEP decoding of the big endian encoded message `em`.
+/// All checks are done in constant time and only a single error is reported.
+fn oaep_decode<'a, HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    label_hash: &[u8],
+    em: &mut [u8],
+    plaintext_buffer: &'a mut [u8]
+) -> Result<&'a [u8]> {
+    let h_len = label_hash.len();
+
+    let first_byte_is_zero = em[0].ct_eq(&0u8);
+    let (seed, db) = em[1..].split_at_mut(h_len);
+
+    mgf1_xor(hash, db, seed)?;
+    mgf1_xor(hash, seed, db)?;
+
+    let label_hash_matches = db[..h_len].ct_eq(label_hash);
+
+    // Search the 0x01 byte seperating PS and M, any other non zero byte invalidates the padding
+    let mut looking_for_index = Choice::from(1u8);
+    let mut index = 0u32;
+    let mut invalid_ps = Choice::from(0u8);
+
+    for (i, el) in db.iter().enumerate().skip(h_len) {
+        let equals_zero = el.ct_eq(&0u8);
+        let equals_one = el.ct_eq(&1u8);
+
+        index.conditional_assign(&(i as u32), looking_for_index & equals_one);
+        invalid_ps |= looking_for_index & !equals_zero & !equals_one;
+        looking_for_index &= !equals_one;
+    }
+
+    let valid = first_byte_is_zero
+        & label_hash_matches
+        & !looking_for_index
+        & !invalid_ps;
+
+    if valid.unwrap_u8() != 1 {
+        return Err(Error::Decryption);
+    }
+
+    let message = &db[index as usize + 1..];
+    plaintext_buffer[..message.len()].copy_from_slice(message);
+
+    Ok(&plaintext_buffer[..message.len()])
+}
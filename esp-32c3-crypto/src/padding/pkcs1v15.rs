@@ -1,45 +1,80 @@
+use core::cell::{RefCell, RefMut};
+
 use crypto_bigint::subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
-use esp_hal::{rng::Rng, rsa::Rsa, sha::ShaMode, Blocking};
+use esp_hal::{rng::Rng, rsa::Rsa, Blocking};
 use zeroize::Zeroize;
 
 use crate::{
     error::{Error, Result},
-    hash::sha::HashAlgorithm,
+    hash::{context::ShaAccelerator, sha::{Hash, HashFunction, HashMode}},
     rsa::{Decrypt, Encrypt, RsaKey, RsaPrivateKey, RsaPublicKey},
-    traits::{PaddingScheme, SignatureScheme}
+    traits::{MessageSignatureScheme, PaddingScheme, SignatureScheme}
 };
 
 
 pub struct Pkcs1v15Sign 
 {
     hash_len: usize,
-    prefix: &'static[u8]
+    prefix: &'static[u8],
+    hash_algorithm: HashMode
 }
 
 impl Pkcs1v15Sign 
 {
-    pub fn new<H: HashAlgorithm>() -> Self {
+    /// Creates the signature scheme for digests created with `H`.
+    pub fn new<H: HashFunction>() -> Self {
         Self { 
-            hash_len: H::output_len,
+            hash_len: H::OUTPUT_LEN,
             prefix: pkcs1v15_get_prefix::<H>(),
+            hash_algorithm: H::MODE
         }
     }
 }
 
+/// `Pkcs1v15Sign` with its own hasher, which additionally signs and verifies whole messages
+/// through `MessageSignatureScheme`.
+pub struct Pkcs1v15SignWithHash {
+    scheme: Pkcs1v15Sign,
+    sha: RefCell<ShaAccelerator>
+}
+
+impl Pkcs1v15SignWithHash {
+    /// Creates the signature scheme with the hasher used by `sign_message` and `verify_message`.
+    pub fn new<H: HashFunction>(hash: Hash<H>) -> Self {
+        Self {
+            scheme: Pkcs1v15Sign::new::<H>(),
+            sha: RefCell::new(hash.into_accelerator())
+        }
+    }
+
+    /// Consumes the signature scheme and returns the accelerator of the hasher passed to `new`.
+    pub fn into_accelerator(self) -> ShaAccelerator {
+        self.sha.into_inner()
+    }
+}
+
+static SHA512PREFIX: &[u8] = &[0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04, 0x40];
+static SHA384PREFIX: &[u8] = &[0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02, 0x05, 0x00, 0x04, 0x30];
 static SHA256PREFIX: &[u8] = &[0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20];
 static SHA224PREFIX: &[u8] = &[0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04, 0x05, 0x00, 0x04, 0x1c];
 static SHA1PREFIX: &[u8] = &[0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14];
 
-fn pkcs1v15_get_prefix<HA: HashAlgorithm>() -> &'static [u8] {
-    match HA::hash_algorithm {
-        ShaMode::SHA1 => {
+fn pkcs1v15_get_prefix<HA: HashFunction>() -> &'static [u8] {
+    match HA::MODE {
+        HashMode::SHA1 => {
             SHA1PREFIX
         },
-        ShaMode::SHA224 => {
+        HashMode::SHA224 => {
             SHA224PREFIX
         },
-        ShaMode::SHA256 => {
+        HashMode::SHA256 => {
             SHA256PREFIX
+        },
+        HashMode::SHA384 => {
+            SHA384PREFIX
+        },
+        HashMode::SHA512 => {
+            SHA512PREFIX
         }
     }
 }
@@ -120,9 +155,58 @@ where
     }
 }
 
+impl<T> SignatureScheme<T> for Pkcs1v15SignWithHash
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]>,
+    [(); T::BLOCKSIZE]: Sized,
+{
+    fn sign<'a>(
+        &self,
+        priv_key: &RsaPrivateKey<T>,
+        rng: esp_hal::rng::Rng,
+        rsa: &mut esp_hal::rsa::Rsa<Blocking>,
+        digest_in: &[u8],
+        signature_out: &'a mut [u8]
+    )
+    -> Result<&'a [u8]>
+    where
+        T: Decrypt<T>
+    {
+        self.scheme.sign(priv_key, rng, rsa, digest_in, signature_out)
+    }
+
+    fn verify(
+        &self,
+        pub_key: &RsaPublicKey<T>,
+        rsa: &mut esp_hal::rsa::Rsa<Blocking>,
+        hashed: &[u8],
+        sig: &[u8]
+    )
+    -> Result<()>
+    where
+        T: Encrypt<T>
+    {
+        self.scheme.verify(pub_key, rsa, hashed, sig)
+    }
+}
+
+impl<T> MessageSignatureScheme<T> for Pkcs1v15SignWithHash
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]>,
+    [(); T::BLOCKSIZE]: Sized,
+{
+    fn hash_algorithm(&self) -> HashMode {
+        self.scheme.hash_algorithm
+    }
+
+    fn accelerator(&self) -> RefMut<'_, ShaAccelerator> {
+        self.sha.borrow_mut()
+    }
+}
+
 
 fn sign<'a, T: RsaKey>(
-    rng: esp_hal::rng::Rng,
+    mut rng: esp_hal::rng::Rng,
     rsa: &mut esp_hal::rsa::Rsa<Blocking>,
     priv_key: &RsaPrivateKey<T>,
     prefix: &[u8],
@@ -137,7 +221,7 @@ where
     let mut em_buffer = [0xffu8; T::BLOCKSIZE];
     let em = pkcs1v15_sign_pad(prefix, digest_in, T::BLOCKSIZE, &mut em_buffer)?;
 
-    T::decrypt(rsa, priv_key, &em, signature_out)
+    T::decrypt(rsa, &mut rng, priv_key, &em, signature_out)
 }
 
 
@@ -202,6 +286,7 @@ where
     fn decrypt<'a>(
         &self,
         rsa: &mut Rsa<Blocking>,
+        rng: &mut Rng,
         priv_key: &RsaPrivateKey<T>,
         ciphertext: &[u8],
         plaintext_buffer: &'a mut [u8]
@@ -221,7 +306,7 @@ where
             }
 
             let mut buffer = [0u8; T::BLOCKSIZE];
-            let decryption_result = T::decrypt(rsa, priv_key, &cipher_buffer, &mut buffer)?;
+            let decryption_result = T::decrypt(rsa, rng, priv_key, &cipher_buffer, &mut buffer)?;
 
             let result = pkcs1v15_encrypt_unpad_be::<{T::BLOCKSIZE}>(decryption_result, plaintext_buffer)?;
             buffer.zeroize();
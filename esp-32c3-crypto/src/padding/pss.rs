@@ -0,0 +1,286 @@
+use core::cell::{RefCell, RefMut};
+
+use crypto_bigint::subtle::ConstantTimeEq;
+use esp_hal::{rng::Rng, rsa::Rsa, Blocking};
+use zeroize::Zeroize;
+
+use crate::{
+    error::{Error, Result},
+    hash::{context::ShaAccelerator, sha::{Hash, HashFunction, HashMode, MAX_HASH_LEN}},
+    rsa::{Decrypt, Encrypt, RsaKey, RsaPrivateKey, RsaPublicKey, MAX_BLOCKSIZE},
+    traits::{MessageSignatureScheme, SignatureScheme}
+};
+
+use super::mgf::mgf1_xor;
+
+
+/// Largest possible M' = (0x)00 00 00 00 00 00 00 00 || mHash || salt
+const MAX_M_PRIME_LEN: usize = 8 + MAX_BLOCKSIZE;
+
+/// Salt length used by the PSS signature scheme.
+#[derive(Debug, Clone, Copy)]
+pub enum PssSaltLength {
+    /// The salt is as long as the output of the hash algorithm.
+    Digest,
+    /// Signing uses the longest salt that fits into the key,
+    /// verification accepts any salt length.
+    Auto,
+    /// The salt has exactly the given length.
+    Fixed(usize),
+}
+
+/// RSASSA-PSS signature scheme (RFC 8017 8.1).
+/// The hash algorithm `HA` is used for hashing M' as well as for MGF1,
+/// the digest passed to `sign` and `verify` has to be created with the same algorithm.
+pub struct Pss<HA: HashFunction> {
+    hash: RefCell<Hash<HA>>,
+    salt_len: PssSaltLength,
+}
+
+impl<HA: HashFunction> Pss<HA> {
+    /// Creates the signature scheme with a salt as long as the digest.
+    pub fn new(hash: Hash<HA>) -> Self {
+        Self::new_with_salt_len(hash, PssSaltLength::Digest)
+    }
+
+    pub fn new_with_salt_len(hash: Hash<HA>, salt_len: PssSaltLength) -> Self {
+        Self {
+            hash: RefCell::new(hash),
+            salt_len
+        }
+    }
+
+    /// Consumes the signature scheme and returns the hasher.
+    pub fn into_inner(self) -> Hash<HA> {
+        self.hash.into_inner()
+    }
+}
+
+impl<T, HA> SignatureScheme<T> for Pss<HA>
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]>,
+    HA: HashFunction,
+    [(); T::BLOCKSIZE]: Sized,
+{
+    fn sign<'a>(
+        &self,
+        priv_key: &RsaPrivateKey<T>,
+        mut rng: Rng,
+        rsa: &mut Rsa<Blocking>,
+        digest_in: &[u8],
+        signature_out: &'a mut [u8]
+    )
+    -> Result<&'a [u8]>
+    where
+        T: Decrypt<T>
+    {
+        if digest_in.len() != HA::OUTPUT_LEN {
+            return Err(Error::InputNotHashed);
+        }
+
+        if signature_out.len() < T::BLOCKSIZE {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let em_len = T::BLOCKSIZE;
+        let salt_len = match self.salt_len {
+            PssSaltLength::Digest => HA::OUTPUT_LEN,
+            PssSaltLength::Auto => em_len.checked_sub(HA::OUTPUT_LEN + 2)
+                .ok_or(Error::MessageTooLong)?,
+            PssSaltLength::Fixed(len) => len,
+        };
+
+        let mut em = [0u8; T::BLOCKSIZE];
+        pss_encode(
+            &mut self.hash.borrow_mut(),
+            &mut rng,
+            digest_in,
+            salt_len,
+            T::KEYSIZE - 1,
+            &mut em
+        )?;
+
+        // The rsa peripheral expects its operands in little endian
+        em.reverse();
+        let result = T::decrypt(rsa, &mut rng, priv_key, &em, signature_out);
+        em.zeroize();
+        result
+    }
+
+    fn verify(
+        &self,
+        pub_key: &RsaPublicKey<T>,
+        rsa: &mut Rsa<Blocking>,
+        hashed: &[u8],
+        sig: &[u8]
+    )
+    -> Result<()>
+    where
+        T: Encrypt<T>
+    {
+        if hashed.len() != HA::OUTPUT_LEN {
+            return Err(Error::InputNotHashed);
+        }
+
+        if sig.len() != T::BLOCKSIZE {
+            return Err(Error::Verification);
+        }
+
+        let mut out_buffer = [0u8; T::BLOCKSIZE];
+        let mut sig_buffer = [0u8; T::BLOCKSIZE];
+        for (i, &b) in sig.iter().rev().enumerate() {
+            sig_buffer[i] = b;
+        }
+        T::encrypt(rsa, pub_key, &sig_buffer, &mut out_buffer)?;
+
+        let salt_len = match self.salt_len {
+            PssSaltLength::Digest => Some(HA::OUTPUT_LEN),
+            PssSaltLength::Auto => None,
+            PssSaltLength::Fixed(len) => Some(len),
+        };
+
+        pss_verify(
+            &mut self.hash.borrow_mut(),
+            hashed,
+            salt_len,
+            T::KEYSIZE - 1,
+            &mut out_buffer
+        )
+    }
+}
+
+impl<T, HA> MessageSignatureScheme<T> for Pss<HA>
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]>,
+    HA: HashFunction,
+    [(); T::BLOCKSIZE]: Sized,
+{
+    fn hash_algorithm(&self) -> HashMode {
+        HA::MODE
+    }
+
+    fn accelerator(&self) -> RefMut<'_, ShaAccelerator> {
+        RefMut::map(self.hash.borrow_mut(), |hash| hash.accelerator())
+    }
+}
+
+
+/// EMSA-PSS encoding, writes the big endian encoded message to `em`.
+fn pss_encode<HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    rng: &mut Rng,
+    m_hash: &[u8],
+    salt_len: usize,
+    em_bits: usize,
+    em: &mut [u8]
+) -> Result<()> {
+    let h_len = m_hash.len();
+    let em_len = em.len();
+
+    let min_em_len = h_len.checked_add(salt_len)
+        .and_then(|len| len.checked_add(2))
+        .ok_or(Error::MessageTooLong)?;
+    if em_len < min_em_len {
+        return Err(Error::MessageTooLong);
+    }
+
+    // M' = (0x)00 00 00 00 00 00 00 00 || mHash || salt
+    let mut m_prime = [0u8; MAX_M_PRIME_LEN];
+    let m_prime_len = 8 + h_len + salt_len;
+    if m_prime_len > MAX_M_PRIME_LEN {
+        return Err(Error::MessageTooLong);
+    }
+    m_prime[8..8 + h_len].copy_from_slice(m_hash);
+    rng.read(&mut m_prime[8 + h_len..m_prime_len]);
+
+    // EM = maskedDB || H || 0xbc
+    let (db, rest) = em.split_at_mut(em_len - h_len - 1);
+    let (h, trailer) = rest.split_at_mut(h_len);
+
+    hash.hash(&m_prime[..m_prime_len], h)
+        .map_err(|_| Error::Internal)?;
+
+    // DB = PS || 0x01 || salt
+    let db_len = db.len();
+    db[..db_len - salt_len - 1].fill(0);
+    db[db_len - salt_len - 1] = 1;
+    db[db_len - salt_len..].copy_from_slice(&m_prime[8 + h_len..m_prime_len]);
+
+    mgf1_xor(hash, h, db)?;
+
+    // Clear the leftmost 8 * emLen - emBits bits
+    db[0] &= 0xff >> (8 * em_len - em_bits);
+    trailer[0] = 0xbc;
+
+    m_prime.zeroize();
+    Ok(())
+}
+
+
+/// EMSA-PSS verification of the big endian encoded message `em`.
+/// If `salt_len` is `None` the salt length is recovered from the padding.
+fn pss_verify<HA: HashFunction>(
+    hash: &mut Hash<HA>,
+    m_hash: &[u8],
+    salt_len: Option<usize>,
+    em_bits: usize,
+    em: &mut [u8]
+) -> Result<()> {
+    let h_len = m_hash.len();
+    let em_len = em.len();
+
+    if em_len < h_len + 2 {
+        return Err(Error::Verification);
+    }
+
+    let top_bits_mask = 0xff_u8 >> (8 * em_len - em_bits);
+
+    let (db, rest) = em.split_at_mut(em_len - h_len - 1);
+    let (h, trailer) = rest.split_at_mut(h_len);
+
+    let mut ok = trailer[0].ct_eq(&0xbc);
+    ok &= (db[0] & !top_bits_mask).ct_eq(&0u8);
+
+    mgf1_xor(hash, h, db)?;
+    db[0] &= top_bits_mask;
+
+    // DB = PS || 0x01 || salt
+    let db_len = db.len();
+    let ps_len = match salt_len {
+        Some(salt_len) => {
+            let min_db_len = salt_len.checked_add(1).ok_or(Error::InvalidLength)?;
+            if db_len < min_db_len {
+                return Err(Error::Verification);
+            }
+            db_len - min_db_len
+        },
+        None => db.iter().position(|&b| b != 0).ok_or(Error::Verification)?,
+    };
+
+    for el in db.iter().take(ps_len) {
+        ok &= el.ct_eq(&0u8);
+    }
+    ok &= db[ps_len].ct_eq(&1u8);
+
+    // M' = (0x)00 00 00 00 00 00 00 00 || mHash || salt
+    let salt = &db[ps_len + 1..];
+    let m_prime_len = 8 + h_len + salt.len();
+    if m_prime_len > MAX_M_PRIME_LEN {
+        return Err(Error::Verification);
+    }
+    let mut m_prime = [0u8; MAX_M_PRIME_LEN];
+    m_prime[8..8 + h_len].copy_from_slice(m_hash);
+    m_prime[8 + h_len..m_prime_len].copy_from_slice(salt);
+
+    let mut h_prime = [0u8; MAX_HASH_LEN];
+    let h_prime = hash.hash(&m_prime[..m_prime_len], &mut h_prime)
+        .map_err(|_| Error::Internal)?;
+
+    ok &= h_prime.ct_eq(h);
+
+    if ok.unwrap_u8() != 1 {
+        return Err(Error::Verification);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use base64::Engine;
+
+use crate::error::{Error, Result};
+
+
+/// Number of base64 characters per line of the PEM body (RFC 7468).
+const LINE_LEN: usize = 64;
+
+/// Number of der bytes encoded into one line.
+const LINE_BYTES: usize = LINE_LEN / 4 * 3;
+
+/// Writes `der` as PEM document with the given label to `out`.
+pub(crate) fn encode<'a>(label: &str, der: &[u8], out: &'a mut [u8]) -> Result<&'a str> {
+    let body_len = der.len().div_ceil(3) * 4;
+    let line_count = body_len.div_ceil(LINE_LEN);
+    let header_len = "-----BEGIN -----\n".len() + label.len();
+    let footer_len = "-----END -----\n".len() + label.len();
+
+    let pem_len = header_len + body_len + line_count + footer_len;
+    if out.len() < pem_len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let mut position = 0;
+    for part in ["-----BEGIN ", label, "-----\n"] {
+        out[position..position + part.len()].copy_from_slice(part.as_bytes());
+        position += part.len();
+    }
+
+    for line in der.chunks(LINE_BYTES) {
+        position += base64::prelude::BASE64_STANDARD.encode_slice(line, &mut out[position..])
+            .map_err(|_| Error::Internal)?;
+        out[position] = b'\n';
+        position += 1;
+    }
+
+    for part in ["-----END ", label, "-----\n"] {
+        out[position..position + part.len()].copy_from_slice(part.as_bytes());
+        position += part.len();
+    }
+
+    core::str::from_utf8(&out[..position]).map_err(|_| Error::Internal)
+}
+
+
+/// Parses a PEM document, writes the decoded der to `out` and returns the label together with the der.
+/// Line breaks (LF or CRLF) and other whitespace in the base64 body are ignored.
+pub(crate) fn decode<'a, 'b>(pem: &'a str, out: &'b mut [u8]) -> Result<(&'a str, &'b [u8])> {
+    let pem = pem.trim();
+
+    // -----BEGIN label-----
+    let rest = pem.strip_prefix("-----BEGIN ").ok_or(Error::InvalidEncoding)?;
+    let label_end = rest.find("-----").ok_or(Error::InvalidEncoding)?;
+    let label = &rest[..label_end];
+    let rest = &rest[label_end + "-----".len()..];
+
+    // -----END label-----
+    let body = rest
+        .strip_suffix("-----")
+        .and_then(|rest| rest.strip_suffix(label))
+        .and_then(|rest| rest.strip_suffix("-----END "))
+        .ok_or(Error::InvalidEncoding)?;
+
+    // Decode the body in groups of four characters, only the last group may contain padding
+    let mut group = [0u8; 4];
+    let mut group_len = 0;
+    let mut position = 0;
+    let mut padded = false;
+    for c in body.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        if padded {
+            return Err(Error::InvalidEncoding);
+        }
+
+        group[group_len] = c;
+        group_len += 1;
+
+        if group_len == group.len() {
+            position += base64::prelude::BASE64_STANDARD.decode_slice(group, &mut out[position..])
+                .map_err(|e| match e {
+                    base64::DecodeSliceError::DecodeError(_) => Error::InvalidEncoding,
+                    base64::DecodeSliceError::OutputSliceTooSmall => Error::BufferTooSmall,
+                })?;
+            padded = group[3] == b'=';
+            group_len = 0;
+        }
+    }
+
+    if group_len != 0 {
+        return Err(Error::InvalidEncoding);
+    }
+
+    Ok((label, &out[..position]))
+}
+
+/// Creates the error for a PEM document with an unexpected label,
+/// labels longer than the error can hold are truncated.
+pub(crate) fn unexpected_label(label: &str) -> Error {
+    let mut found = heapless::String::new();
+    for c in label.chars() {
+        if found.push(c).is_err() {
+            break;
+        }
+    }
+
+    Error::UnexpectedPemLabel(found)
+}
@@ -0,0 +1,155 @@
+use der::{asn1::{AnyRef, OctetStringRef}, Decode, Reader, SliceReader};
+use esp_hal::aes::Aes;
+use pkcs8::{AlgorithmIdentifierRef, ObjectIdentifier};
+use zeroize::Zeroize;
+
+use crate::aes::{cbc, Esp32C3Aes, BLOCK_LEN as AES_BLOCK_LEN};
+use crate::error::{Error, Result};
+use crate::hash::context::ShaAccelerator;
+use crate::hash::sha::{Esp32C3Sha1, Esp32C3Sha256};
+use crate::kdf::pbkdf2::pbkdf2_on_accelerator;
+
+
+/// PBES2 encryption scheme (RFC 8018).
+const PBES2_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.5.13");
+
+/// PBKDF2 key derivation function (RFC 8018).
+const PBKDF2_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.5.12");
+
+/// PRFs of PBKDF2, the default PRF is HMAC-SHA1.
+const HMAC_WITH_SHA256_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.2.9");
+const HMAC_WITH_SHA1_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.2.7");
+
+const AES_128_CBC_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.1.2");
+const AES_256_CBC_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.1.42");
+
+
+/// PRF of PBKDF2.
+#[derive(Clone, Copy)]
+enum Prf {
+    HmacSha1,
+    HmacSha256,
+}
+
+/// Parameters of a PBES2 encryption with PBKDF2 and AES-CBC.
+struct Pbes2Parameters<'a> {
+    prf: Prf,
+    salt: &'a [u8],
+    iterations: u32,
+    key_len: usize,
+    iv: &'a [u8; AES_BLOCK_LEN],
+}
+
+/// Decrypts a der encoded pkcs8 EncryptedPrivateKeyInfo encrypted with PBES2 and writes the
+/// der encoded PrivateKeyInfo to `out`.
+///
+/// Only PBKDF2 with HMAC-SHA1 or HMAC-SHA256 and AES-128-CBC or AES-256-CBC is supported, the key
+/// derivation runs on `sha` with the PRF named in the parameters and the decryption on the AES accelerator.
+pub(crate) fn decrypt<'a>(
+    encrypted_der: &[u8],
+    password: &[u8],
+    sha: &mut ShaAccelerator,
+    aes: &mut Aes<'_>,
+    out: &'a mut [u8]
+) -> Result<&'a [u8]> {
+    // EncryptedPrivateKeyInfo ::= SEQUENCE { encryptionAlgorithm, encryptedData OCTET STRING }
+    let (algorithm, ciphertext) = SliceReader::new(encrypted_der)
+        .and_then(|mut reader| {
+            let value = reader.sequence(|sequence| {
+                let algorithm = AlgorithmIdentifierRef::decode(sequence)?;
+                let ciphertext = OctetStringRef::decode(sequence)?;
+                Ok((algorithm, ciphertext.as_bytes()))
+            })?;
+            reader.finish(value)
+        })
+        .map_err(|e| Error::PKCS8Error(e.into()))?;
+
+    if algorithm.oid != PBES2_OID {
+        return Err(Error::UnsupportedAlgorithm(algorithm.oid));
+    }
+
+    let parameters = parse_pbes2_parameters(algorithm.parameters)?;
+
+    if ciphertext.is_empty() || ciphertext.len() % AES_BLOCK_LEN != 0 {
+        return Err(Error::InvalidBlockSize);
+    }
+    if out.len() < ciphertext.len() {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let mut key = [0u8; 32];
+    let derived_key = &mut key[..parameters.key_len];
+    match parameters.prf {
+        Prf::HmacSha1 => pbkdf2_on_accelerator::<Esp32C3Sha1>(sha, password, parameters.salt, parameters.iterations, derived_key)?,
+        Prf::HmacSha256 => pbkdf2_on_accelerator::<Esp32C3Sha256>(sha, password, parameters.salt, parameters.iterations, derived_key)?,
+    }
+
+    let mut cipher = Esp32C3Aes::new(aes, &key[..parameters.key_len])?;
+    key.zeroize();
+
+    // A wrong password shows up as invalid pkcs7 padding
+    out[..ciphertext.len()].copy_from_slice(ciphertext);
+    cbc::decrypt(&mut cipher, parameters.iv, &mut out[..ciphertext.len()])
+}
+
+/// Parses PBES2-params ::= SEQUENCE { keyDerivationFunc, encryptionScheme }.
+fn parse_pbes2_parameters(parameters: Option<AnyRef<'_>>) -> Result<Pbes2Parameters<'_>> {
+    let (kdf, encryption_scheme) = parameters
+        .ok_or(Error::PKCS8Error(pkcs8::Error::ParametersMalformed))?
+        .sequence(|sequence| {
+            let kdf = AlgorithmIdentifierRef::decode(sequence)?;
+            let encryption_scheme = AlgorithmIdentifierRef::decode(sequence)?;
+            Ok((kdf, encryption_scheme))
+        })
+        .map_err(|e| Error::PKCS8Error(e.into()))?;
+
+    let key_len = match encryption_scheme.oid {
+        AES_128_CBC_OID => 16,
+        AES_256_CBC_OID => 32,
+        oid => return Err(Error::UnsupportedAlgorithm(oid)),
+    };
+
+    let iv: &[u8; AES_BLOCK_LEN] = encryption_scheme.parameters
+        .ok_or(Error::PKCS8Error(pkcs8::Error::ParametersMalformed))?
+        .decode_as::<OctetStringRef>()
+        .map_err(|e| Error::PKCS8Error(e.into()))?
+        .as_bytes()
+        .try_into()
+        .map_err(|_| Error::PKCS8Error(pkcs8::Error::ParametersMalformed))?;
+
+    if kdf.oid != PBKDF2_OID {
+        return Err(Error::UnsupportedAlgorithm(kdf.oid));
+    }
+
+    // PBKDF2-params ::= SEQUENCE { salt OCTET STRING, iterationCount INTEGER,
+    //                              keyLength INTEGER OPTIONAL, prf AlgorithmIdentifier DEFAULT hmacWithSHA1 }
+    let (salt, iterations, derived_key_len, prf) = kdf.parameters
+        .ok_or(Error::PKCS8Error(pkcs8::Error::ParametersMalformed))?
+        .sequence(|sequence| {
+            let salt = OctetStringRef::decode(sequence)?;
+            let iterations = u32::decode(sequence)?;
+            let derived_key_len = match sequence.peek_tag() {
+                Ok(der::Tag::Integer) => Some(u16::decode(sequence)?),
+                _ => None,
+            };
+            let prf = if sequence.is_finished() {
+                None
+            } else {
+                Some(AlgorithmIdentifierRef::decode(sequence)?)
+            };
+            Ok((salt.as_bytes(), iterations, derived_key_len, prf))
+        })
+        .map_err(|e| Error::PKCS8Error(e.into()))?;
+
+    let prf = match prf.map_or(HMAC_WITH_SHA1_OID, |prf| prf.oid) {
+        HMAC_WITH_SHA1_OID => Prf::HmacSha1,
+        HMAC_WITH_SHA256_OID => Prf::HmacSha256,
+        oid => return Err(Error::UnsupportedAlgorithm(oid)),
+    };
+
+    if iterations == 0 || derived_key_len.is_some_and(|len| len as usize != key_len) {
+        return Err(Error::PKCS8Error(pkcs8::Error::ParametersMalformed));
+    }
+
+    Ok(Pbes2Parameters { prf, salt, iterations, key_len, iv })
+}
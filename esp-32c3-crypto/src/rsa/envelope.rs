@@ -0,0 +1,154 @@
+//! Hybrid encryption of payloads of any length to an RSA public key.
+//!
+//! A random AES-256 key encrypts the payload with AES-GCM and is itself encrypted with RSA under
+//! the padding scheme passed in (`Oaep` or `Pkcs1v15Encrypt`). The envelope is
+//!
+//! ```text
+//! version          1 byte              ENVELOPE_VERSION
+//! wrapped key len  2 bytes big endian  block size of the RSA key
+//! wrapped key      wrapped key len     RSA encryption of the 32 byte AES key
+//! nonce            12 bytes            AES-GCM nonce
+//! ciphertext len   4 bytes big endian  length of the payload
+//! ciphertext       ciphertext len      AES-256-GCM encryption of the payload
+//! tag              16 bytes            AES-GCM tag
+//! ```
+//!
+//! Everything before the ciphertext is the associated data of AES-GCM, so the header cannot be
+//! changed or combined with a ciphertext of another envelope. The padding scheme is not recorded,
+//! the recipient has to open the envelope with the scheme it was sealed with.
+//!
+//! Opening an envelope with a wrapped key that does not decrypt continues with a random key, so any
+//! bad envelope fails with `Error::Decryption` at the tag check of AES-GCM.
+
+use crypto_bigint::subtle::{ConditionallySelectable, ConstantTimeEq};
+use esp_hal::{aes::Aes, rng::Rng, rsa::Rsa, Blocking};
+use zeroize::Zeroize;
+
+use crate::{
+    aes::{gcm::{NONCE_LEN, TAG_LEN}, AesGcm, Esp32C3Aes},
+    error::{Error, Result},
+    traits::PaddingScheme
+};
+
+use super::{Decrypt, Encrypt, RsaKey, RsaPrivateKey, RsaPublicKey, MAX_BLOCKSIZE};
+
+
+/// Version of the envelope format.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Length of an envelope besides the wrapped key and the payload.
+pub const ENVELOPE_OVERHEAD: usize = 1 + 2 + NONCE_LEN + 4 + TAG_LEN;
+
+/// Length of the AES-256 key.
+const KEY_LEN: usize = 32;
+
+
+/// Length of the header up to the ciphertext for a wrapped key of `wrapped_key_len` bytes.
+const fn header_len(wrapped_key_len: usize) -> usize {
+    1 + 2 + wrapped_key_len + NONCE_LEN + 4
+}
+
+pub(crate) fn seal<'a, T, P>(
+    pub_key: &RsaPublicKey<T>,
+    rsa: &mut Rsa<Blocking>,
+    rng: &mut Rng,
+    aes: &mut Aes<'_>,
+    padding: &P,
+    plaintext: &[u8],
+    out: &'a mut [u8]
+) -> Result<&'a [u8]>
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]> + Encrypt<T>,
+    P: PaddingScheme<T>
+{
+    let ciphertext_len = u32::try_from(plaintext.len()).map_err(|_| Error::MessageTooLong)?;
+    let header_len = header_len(T::BLOCKSIZE);
+    let len = header_len + plaintext.len() + TAG_LEN;
+    if out.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let mut key = [0u8; KEY_LEN];
+    rng.read(&mut key);
+
+    out[0] = ENVELOPE_VERSION;
+    out[1..3].copy_from_slice(&(T::BLOCKSIZE as u16).to_be_bytes());
+    let wrapped = padding.encrypt(rsa, rng, pub_key, &key, &mut out[3..]);
+    if let Err(e) = wrapped {
+        key.zeroize();
+        return Err(e);
+    }
+
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.read(&mut nonce);
+    out[3 + T::BLOCKSIZE..header_len - 4].copy_from_slice(&nonce);
+    out[header_len - 4..header_len].copy_from_slice(&ciphertext_len.to_be_bytes());
+
+    let cipher = Esp32C3Aes::new(aes, &key);
+    key.zeroize();
+    let mut gcm = AesGcm::new(cipher?);
+
+    let (header, body) = out[..len].split_at_mut(header_len);
+    let (ciphertext, tag) = body.split_at_mut(plaintext.len());
+    ciphertext.copy_from_slice(plaintext);
+    tag.copy_from_slice(&gcm.encrypt_detached(&nonce, header, ciphertext)?);
+
+    Ok(&out[..len])
+}
+
+pub(crate) fn open<'a, T, P>(
+    priv_key: &RsaPrivateKey<T>,
+    rsa: &mut Rsa<Blocking>,
+    rng: &mut Rng,
+    aes: &mut Aes<'_>,
+    padding: &P,
+    envelope: &[u8],
+    plaintext_buffer: &'a mut [u8]
+) -> Result<&'a [u8]>
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]> + Decrypt<T>,
+    P: PaddingScheme<T>
+{
+    let header_len = header_len(T::BLOCKSIZE);
+    if envelope.len() < header_len + TAG_LEN || envelope[0] != ENVELOPE_VERSION {
+        return Err(Error::InvalidEncoding);
+    }
+
+    let wrapped_key_len = u16::from_be_bytes([envelope[1], envelope[2]]) as usize;
+    let ciphertext_len = u32::from_be_bytes(envelope[header_len - 4..header_len].try_into().unwrap()) as usize;
+    if wrapped_key_len != T::BLOCKSIZE || envelope.len() - header_len - TAG_LEN != ciphertext_len {
+        return Err(Error::InvalidEncoding);
+    }
+    if plaintext_buffer.len() < ciphertext_len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let (header, body) = envelope.split_at(header_len);
+    let (ciphertext, tag) = body.split_at(ciphertext_len);
+    let wrapped_key = &header[3..3 + T::BLOCKSIZE];
+    let nonce: &[u8; NONCE_LEN] = header[3 + T::BLOCKSIZE..header_len - 4].try_into().unwrap();
+
+    // Implicit rejection: a wrapped key that does not unwrap to 32 bytes is replaced by a random key
+    // without branching, so a bad envelope always fails at the tag check and the RSA padding check
+    // cannot be told apart by time or error (Bleichenbacher).
+    let mut key = [0u8; KEY_LEN];
+    rng.read(&mut key);
+
+    let mut key_buffer = [0u8; MAX_BLOCKSIZE];
+    let key_len = padding.decrypt(rsa, rng, priv_key, wrapped_key, &mut key_buffer).map_or(0, <[u8]>::len);
+    let valid = (key_len as u32).ct_eq(&(KEY_LEN as u32));
+    for (k, b) in key.iter_mut().zip(key_buffer.iter()) {
+        k.conditional_assign(b, valid);
+    }
+    key_buffer.zeroize();
+
+    let cipher = Esp32C3Aes::new(aes, &key);
+    key.zeroize();
+    let mut gcm = AesGcm::new(cipher?);
+
+    let plaintext = &mut plaintext_buffer[..ciphertext_len];
+    plaintext.copy_from_slice(ciphertext);
+    gcm.decrypt_detached(nonce, header, plaintext, tag.try_into().unwrap())?;
+
+    Ok(&plaintext_buffer[..ciphertext_len])
+}
@@ -1,17 +1,77 @@
 mod publick_key;
-use esp_hal::{rsa::Rsa, Blocking};
+use esp_hal::{rng::Rng, rsa::Rsa, Blocking};
 pub use publick_key::RsaPublicKey;
 
+use der::{asn1::AnyRef, Encode, Reader, SliceReader, Tag};
+use pkcs8::{AlgorithmIdentifierRef, ObjectIdentifier};
+
 use crate::{
     error::{Result, Error},
     traits::{PrivateKeyParts, PublicKeyParts},
 };
+use zeroize::Zeroize;
 
 mod private_key;
 pub use private_key::RsaPrivateKey;
 
+mod verifier;
+pub use verifier::Verifier;
+
+pub mod envelope;
+
 use paste::paste;
 
+/// Block size of the biggest supported key.
+pub(crate) const MAX_BLOCKSIZE: usize = 512;
+
+/// Public exponent of generated keys.
+const PUBLIC_EXPONENT: u32 = 65537;
+
+/// Upper bound of the length of a der encoded public key.
+const MAX_PUBLIC_KEY_DER_LEN: usize = 2 * MAX_BLOCKSIZE + 64;
+
+/// Upper bound of the length of a der encoded private key.
+const MAX_PRIVATE_KEY_DER_LEN: usize = 6 * MAX_BLOCKSIZE;
+
+/// AlgorithmIdentifier of rsaEncryption keys (RFC 8017 A.1).
+const RSA_ALGORITHM_ID: AlgorithmIdentifierRef<'static> = AlgorithmIdentifierRef {
+    oid: ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1"),
+    parameters: Some(AnyRef::NULL),
+};
+
+/// Der encodes `value` to `out`, encoding errors are converted with `map_err`.
+fn encode_der<'a>(value: &impl Encode, out: &'a mut [u8], map_err: fn(der::Error) -> Error) -> Result<&'a [u8]> {
+    let len = usize::try_from(value.encoded_len().map_err(map_err)?).map_err(map_err)?;
+    if out.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    value.encode_to_slice(out).map_err(map_err)
+}
+
+/// Returns the tag of the element at `index` of the der encoded SEQUENCE `der`,
+/// used to tell the key formats apart before parsing them.
+fn sequence_element_tag(der: &[u8], index: usize) -> Option<Tag> {
+    let mut reader = SliceReader::new(der).ok()?;
+    reader.sequence(|sequence| {
+        for _ in 0..index {
+            sequence.tlv_bytes()?;
+        }
+        let tag = sequence.peek_tag()?;
+        sequence.read_slice(sequence.remaining_len())?;
+        Ok(tag)
+    }).ok()
+}
+
+/// Checks that the AlgorithmIdentifier of a SubjectPublicKeyInfo or PrivateKeyInfo is rsaEncryption.
+fn check_algorithm(algorithm: &AlgorithmIdentifierRef) -> Result<()> {
+    if algorithm.oid != RSA_ALGORITHM_ID.oid {
+        return Err(Error::UnsupportedAlgorithm(algorithm.oid));
+    }
+
+    Ok(())
+}
+
 pub trait RsaKey {
     const BLOCKSIZE: usize;
     type OperandType;
@@ -29,16 +89,27 @@ pub trait Encrypt<T: RsaKey> where T: RsaKey<OperandType = [u32; T::OperandWords
 }
 
 pub trait Decrypt<T: RsaKey> where T: RsaKey<OperandType = [u32; T::OperandWords]> {
+    /// Runs the private key operation on a blinded base,
+    /// `rng` is used to draw a new blinding factor for every call.
     fn decrypt<'a>(
         rsa: &mut Rsa<Blocking>,
+        rng: &mut Rng,
         priv_key: &RsaPrivateKey<T>,
         base: &[u8],
         out: &'a mut [u8]
     ) -> Result<&'a [u8]>;
 }
 
+pub trait Generate<T: RsaKey> where T: RsaKey<OperandType = [u32; T::OperandWords]> {
+    /// Generates a new key pair with two random primes of half the key size.
+    fn generate(
+        rsa: &mut Rsa<Blocking>,
+        rng: &mut Rng
+    ) -> Result<RsaPrivateKey<T>>;
+}
+
 macro_rules! implement_rsakey {
-    (($x: literal)) => {
+    (($x: literal, $half: literal)) => {
         paste! {
             #[derive(Debug)]
             pub struct [<RsaKeySize $x>];
@@ -85,29 +156,95 @@ macro_rules! implement_rsakey {
             impl Decrypt<[<RsaKeySize $x>]> for [<RsaKeySize $x>] {
                 fn decrypt<'a>(
                     rsa: &mut Rsa<Blocking>,
+                    rng: &mut Rng,
                     priv_key: &RsaPrivateKey<[<RsaKeySize $x>]>,
                     base: &[u8],
                     out: &'a mut [u8]
                 ) -> Result<&'a [u8]> {
+                    if base.len() != Self::BLOCKSIZE {
+                        return Err(Error::InvalidBlockSize);
+                    }
                     let base = unsafe { &*(base.as_ptr() as *const[u32; Self::OperandWords]) };
+                    let mut blinded_base = [0u32; Self::OperandWords];
+                    let mut blinded_output = [0u32; Self::OperandWords];
                     let mut output_buffer = [0u32; Self::OperandWords];
-                    crate::utils::[<run_expo_ $x>](
+
+                    let public = crate::utils::ExpoOperands {
+                        modulus: priv_key.n(),
+                        exponent: priv_key.e(),
+                        m_prime: priv_key.mprime(),
+                        r: priv_key.r(),
+                    };
+                    let mut r_inv = crate::utils::blind(
                         rsa,
-                        unsafe { core::mem::transmute(priv_key.d()) },
-                        unsafe { core::mem::transmute(priv_key.n()) },
-                        priv_key.mprime(),
+                        rng,
+                        &public,
                         base,
-                        unsafe { core::mem::transmute(priv_key.r()) },
-                        &mut output_buffer
+                        &mut blinded_base,
+                        crate::utils::[<run_expo_ $x>]
+                    );
+
+                    let p = crate::utils::ExpoOperands {
+                        modulus: priv_key.p(),
+                        exponent: priv_key.dp(),
+                        m_prime: priv_key.p_mprime(),
+                        r: priv_key.p_r(),
+                    };
+                    let q = crate::utils::ExpoOperands {
+                        modulus: priv_key.q(),
+                        exponent: priv_key.dq(),
+                        m_prime: priv_key.q_mprime(),
+                        r: priv_key.q_r(),
+                    };
+
+                    crate::utils::run_crt_expo::<{ $x / 32 }, { $half / 32 }>(
+                        rsa,
+                        &p,
+                        &q,
+                        priv_key.qinv(),
+                        &blinded_base,
+                        &mut blinded_output,
+                        crate::utils::[<run_expo_ $half>]
                     );
+                    crate::utils::unblind(priv_key.n(), &blinded_output, &r_inv, &mut output_buffer);
 
                     for (i, &b) in unsafe { core::mem::transmute::<[u32; Self::OperandWords] ,[u8; Self::BLOCKSIZE]>(output_buffer) }.iter().rev().enumerate() {
                         out[i] = b;
                     }
+                    output_buffer.zeroize();
+                    blinded_base.zeroize();
+                    blinded_output.zeroize();
+                    r_inv.zeroize();
 
                     Ok(&out[..Self::BLOCKSIZE])
                 }
             }
+
+            impl Generate<[<RsaKeySize $x>]> for [<RsaKeySize $x>] {
+                fn generate(
+                    rsa: &mut Rsa<Blocking>,
+                    rng: &mut Rng
+                ) -> Result<RsaPrivateKey<[<RsaKeySize $x>]>> {
+                    loop {
+                        let p = crate::utils::generate_prime::<{ $half / 32 }>(
+                            rsa,
+                            rng,
+                            PUBLIC_EXPONENT,
+                            crate::utils::[<run_expo_ $half>]
+                        );
+                        let q = crate::utils::generate_prime::<{ $half / 32 }>(
+                            rsa,
+                            rng,
+                            PUBLIC_EXPONENT,
+                            crate::utils::[<run_expo_ $half>]
+                        );
+
+                        if crate::utils::primes_far_apart(&p, &q) {
+                            return RsaPrivateKey::from_primes(&p.resize(), &q.resize(), PUBLIC_EXPONENT);
+                        }
+                    }
+                }
+            }
         }
     };
 
@@ -119,6 +256,8 @@ macro_rules! implement_rsakey {
 
 
 implement_rsakey!(
-    (1024),
-    (2048)
+    (1024, 512),
+    (2048, 1024),
+    (3072, 1536),
+    (4096, 2048)
 );
\ No newline at end of file
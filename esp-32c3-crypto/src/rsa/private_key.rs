@@ -1,14 +1,15 @@
 use core::marker::PhantomData;
 
 use base64::Engine;
-use crypto_bigint::Uint;
-use esp_hal::{rng::Rng, rsa::Rsa, Blocking};
+use crypto_bigint::{subtle::{Choice, ConstantTimeEq}, Uint};
+use esp_hal::{aes::Aes, rng::Rng, rsa::Rsa, Blocking};
+use der::{asn1::UintRef, Tag};
 use pkcs8::PrivateKeyInfo;
 use pkcs1::RsaPrivateKey as RsaPrivate;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use super::{Decrypt, RsaKey};
-use crate::{error::{Error, Result}, traits::{PaddingScheme, PrivateKeyParts, PublicKeyParts, SignatureScheme}};
+use super::{check_algorithm, encode_der, envelope, sequence_element_tag, Decrypt, Generate, RsaKey, RsaPublicKey, MAX_PRIVATE_KEY_DER_LEN, RSA_ALGORITHM_ID};
+use crate::{error::{Error, Result}, hash::context::ShaAccelerator, traits::{MessageSignatureScheme, PaddingScheme, PrivateKeyParts, PublicKeyParts, SignatureScheme}};
 
 
 
@@ -22,6 +23,15 @@ where
     m_prime: u32,
     r: T::OperandType,
     e: T::OperandType,
+    p: T::OperandType,
+    q: T::OperandType,
+    dp: T::OperandType,
+    dq: T::OperandType,
+    qinv: T::OperandType,
+    p_mprime: u32,
+    q_mprime: u32,
+    p_r: T::OperandType,
+    q_r: T::OperandType,
     phantom: PhantomData<T>
 }
 
@@ -39,33 +49,164 @@ where
         let priv_key_info = PrivateKeyInfo::try_from(private_key_der)
             .map_err(|e| Error::PKCS8Error(e))?;
 
+        check_algorithm(&priv_key_info.algorithm)?;
+
         let priv_key = RsaPrivate::try_from(priv_key_info.private_key)
             .map_err(|e| Error::PKCS1Error(e))?;
 
+        Self::from_pkcs1_key(priv_key)
+    }
+
+    /// Parses a der encoded pkcs1 RSAPrivateKey without PrivateKeyInfo wrapper.
+    pub fn from_pkcs1_der(private_key_der: &[u8]) -> Result<Self> {
+        let priv_key = RsaPrivate::try_from(private_key_der)
+            .map_err(Error::PKCS1Error)?;
+
+        Self::from_pkcs1_key(priv_key)
+    }
+
+    /// Parses a der encoded pkcs8 PrivateKeyInfo or pkcs1 RSAPrivateKey.
+    pub fn from_der_any(private_key_der: &[u8]) -> Result<Self> {
+        // Both start with the version, followed by the AlgorithmIdentifier in a PrivateKeyInfo
+        // and by the modulus in an RSAPrivateKey
+        match sequence_element_tag(private_key_der, 1) {
+            Some(Tag::Integer) => Self::from_pkcs1_der(private_key_der),
+            _ => Self::new_from_der(private_key_der),
+        }
+    }
+
+    fn from_pkcs1_key(priv_key: RsaPrivate) -> Result<Self> {
         let modulus_bytes = priv_key.modulus.as_bytes();
 
         if modulus_bytes.len() != T::BLOCKSIZE {
             return Err(Error::RsaKeySizeError);
         }
 
+        // The crt operations run on half size operands, so both primes have to fit into half a block
+        let prime_bytes = [priv_key.prime1.as_bytes(), priv_key.prime2.as_bytes()];
+        if prime_bytes.iter().any(|b| b.len() > T::BLOCKSIZE / 2) {
+            return Err(Error::RsaKeySizeError);
+        }
+
         // Extract needed values from key and convert them to U1024
         let n: Uint<{T::OperandWords}> = Uint::from_be_slice(modulus_bytes);
-        let d: Uint<{T::OperandWords}> = Uint::from_be_slice(priv_key.private_exponent.as_bytes());
+        let d = Self::operand_from_be_bytes(priv_key.private_exponent.as_bytes())?;
+        let e = Self::operand_from_be_bytes(priv_key.public_exponent.as_bytes())?;
         let m_prime = crate::utils::compute_mprime(&n);
         let r: Uint<{T::OperandWords}> = crate::utils::compute_r(&n);
 
+        // Crt components and their precomputed values for the half size operations
+        let p = Self::operand_from_be_bytes(priv_key.prime1.as_bytes())?;
+        let q = Self::operand_from_be_bytes(priv_key.prime2.as_bytes())?;
+        let dp = Self::operand_from_be_bytes(priv_key.exponent1.as_bytes())?;
+        let dq = Self::operand_from_be_bytes(priv_key.exponent2.as_bytes())?;
+        let qinv = Self::operand_from_be_bytes(priv_key.coefficient.as_bytes())?;
+        if !bool::from(Self::crt_parts_consistent(&n, &d, &p, &q, &dp, &dq, &qinv)) {
+            return Err(Error::InvalidEncoding);
+        }
+        let p_mprime = crate::utils::compute_mprime(&p);
+        let q_mprime = crate::utils::compute_mprime(&q);
+        let p_r = crate::utils::compute_r_sized(&p, T::OperandWords / 2);
+        let q_r = crate::utils::compute_r_sized(&q, T::OperandWords / 2);
+
+        Ok( Self {
+            d: d.into(), n: n.into(), m_prime, r: r.into(), e: e.into(),
+            p: p.into(), q: q.into(), dp: dp.into(), dq: dq.into(), qinv: qinv.into(),
+            p_mprime, q_mprime, p_r: p_r.into(), q_r: q_r.into(),
+            phantom: PhantomData
+        })
+    }
+
+    /// Generates a new key pair with the public exponent 65537.
+    /// Prime candidates are tested with miller rabin on the rsa peripheral,
+    /// depending on the key size this can take several seconds.
+    pub fn generate(rng: &mut Rng, rsa: &mut Rsa<Blocking>) -> Result<Self>
+    where
+        T: Generate<T>
+    {
+        T::generate(rsa, rng)
+    }
+
+    /// Derives the private exponent and crt components from the primes p and q.
+    pub(crate) fn from_primes(
+        p: &Uint<{T::OperandWords}>,
+        q: &Uint<{T::OperandWords}>,
+        e: u32
+    ) -> Result<Self>
+    {
+        // The primes are half size, so the products fit into the lower half of the wide result
+        let n = p.mul_wide(q).0;
+        let p_minus_one = p.wrapping_sub(&Uint::ONE);
+        let q_minus_one = q.wrapping_sub(&Uint::ONE);
+
+        // d = e^-1 mod lcm(p - 1, q - 1) as required by FIPS 186-4 B.3.1
+        let lambda = crate::utils::lcm(&p_minus_one, &q_minus_one);
+        let e = Uint::<{T::OperandWords}>::from_u32(e);
+        let (d, d_exists) = e.inv_mod(&lambda);
+        let (qinv, qinv_exists) = q.inv_odd_mod(p);
+        if !bool::from(d_exists) || !bool::from(qinv_exists) {
+            return Err(Error::Internal);
+        }
+
+        let dp = d.const_rem(&p_minus_one).0;
+        let dq = d.const_rem(&q_minus_one).0;
 
-        let e_bytes = priv_key.public_exponent.as_bytes();
-        let mut e_buffer: [u8; T::BLOCKSIZE] = [0u8; T::BLOCKSIZE];
-        let start_index = T::BLOCKSIZE - e_bytes.len();
-        e_buffer[start_index..].copy_from_slice(&e_bytes);
-        let e: Uint<{T::OperandWords}> = Uint::from_be_slice(&e_buffer);
+        let m_prime = crate::utils::compute_mprime(&n);
+        let r: Uint<{T::OperandWords}> = crate::utils::compute_r(&n);
+        let p_mprime = crate::utils::compute_mprime(p);
+        let q_mprime = crate::utils::compute_mprime(q);
+        let p_r = crate::utils::compute_r_sized(p, T::OperandWords / 2);
+        let q_r = crate::utils::compute_r_sized(q, T::OperandWords / 2);
 
         Ok( Self {
-            d: d.into(), n: n.into(), m_prime, r: r.into(), e: e.into(), phantom: PhantomData
+            d: d.into(), n: n.into(), m_prime, r: r.into(), e: e.into(),
+            p: (*p).into(), q: (*q).into(), dp: dp.into(), dq: dq.into(), qinv: qinv.into(),
+            p_mprime, q_mprime, p_r: p_r.into(), q_r: q_r.into(),
+            phantom: PhantomData
         })
     }
 
+    /// Checks that the crt components belong to the key: p * q = n, dp = d mod (p - 1),
+    /// dq = d mod (q - 1) and qinv * q = 1 mod p. The comparisons are constant time.
+    fn crt_parts_consistent(
+        n: &Uint<{T::OperandWords}>,
+        d: &Uint<{T::OperandWords}>,
+        p: &Uint<{T::OperandWords}>,
+        q: &Uint<{T::OperandWords}>,
+        dp: &Uint<{T::OperandWords}>,
+        dq: &Uint<{T::OperandWords}>,
+        qinv: &Uint<{T::OperandWords}>
+    ) -> Choice
+    {
+        // All values fit into half a block, so the products fit into the lower half of the wide result
+        let mut consistent = p.mul_wide(q).0.ct_eq(n);
+
+        let (d_mod_p, p_above_one) = d.const_rem(&p.wrapping_sub(&Uint::ONE));
+        let (d_mod_q, q_above_one) = d.const_rem(&q.wrapping_sub(&Uint::ONE));
+        consistent &= Choice::from(p_above_one) & Choice::from(q_above_one);
+        consistent &= d_mod_p.ct_eq(dp) & d_mod_q.ct_eq(dq);
+
+        let (q_qinv_mod_p, _) = q.mul_wide(qinv).0.const_rem(p);
+        consistent &= q_qinv_mod_p.ct_eq(&Uint::ONE);
+
+        consistent
+    }
+
+    /// Converts a big endian integer of at most BLOCKSIZE bytes to an operand.
+    fn operand_from_be_bytes(bytes: &[u8]) -> Result<Uint<{T::OperandWords}>> {
+        if bytes.len() > T::BLOCKSIZE {
+            return Err(Error::RsaKeySizeError);
+        }
+
+        let mut buffer: [u8; T::BLOCKSIZE] = [0u8; T::BLOCKSIZE];
+        let start_index = T::BLOCKSIZE - bytes.len();
+        buffer[start_index..].copy_from_slice(bytes);
+        let operand = Uint::from_be_slice(&buffer);
+        buffer.zeroize();
+
+        Ok(operand)
+    }
+
     pub fn new_from_b64_der(string: &str) -> Result<Self> {
         let mut bytes = [0u8; 4096];
         let written_bytes = match base64::prelude::BASE64_STANDARD.decode_slice(string, &mut bytes) {
@@ -80,6 +221,110 @@ where
 
         return Self::new_from_der(&bytes[..written_bytes]);
     }
+
+    /// Parses a pem encoded pkcs8 PrivateKeyInfo ("PRIVATE KEY") or pkcs1 RSAPrivateKey ("RSA PRIVATE KEY").
+    pub fn new_from_pem(pem: &str) -> Result<Self> {
+        let mut bytes = [0u8; 4096];
+        let result = match crate::pem::decode(pem, &mut bytes) {
+            Ok(("PRIVATE KEY", der)) => Self::new_from_der(der),
+            Ok(("RSA PRIVATE KEY", der)) => Self::from_pkcs1_der(der),
+            Ok((label, _)) => Err(crate::pem::unexpected_label(label)),
+            Err(e) => Err(e),
+        };
+
+        bytes.zeroize();
+        result
+    }
+
+    /// Parses a der encoded pkcs8 EncryptedPrivateKeyInfo (PBES2 with PBKDF2-HMAC-SHA1 or PBKDF2-HMAC-SHA256
+    /// and AES-128-CBC or AES-256-CBC) as created by `openssl pkcs8 -topk8 -v2 aes-256-cbc -outform DER`.
+    /// The key derivation runs on `sha` with the PRF of the encryption parameters, the decryption on the AES accelerator.
+    /// A `Hash` lends its accelerator with `Hash::accelerator`.
+    pub fn new_from_encrypted_der(
+        encrypted_der: &[u8],
+        password: &[u8],
+        sha: &mut ShaAccelerator,
+        aes: &mut Aes<'_>
+    ) -> Result<Self> {
+        let mut bytes = [0u8; MAX_PRIVATE_KEY_DER_LEN];
+        let result = crate::pkcs5::decrypt(encrypted_der, password, sha, aes, &mut bytes)
+            .and_then(Self::new_from_der);
+
+        bytes.zeroize();
+        result
+    }
+
+    /// Writes the key as der encoded pkcs1 RSAPrivateKey to `out`.
+    pub fn to_pkcs1_der<'a>(&self, out: &'a mut [u8]) -> Result<&'a [u8]> {
+        let operands = [&self.n, &self.e, &self.d, &self.p, &self.q, &self.dp, &self.dq, &self.qinv];
+        let mut components = [[0u8; T::BLOCKSIZE]; 8];
+        for (operand, bytes) in operands.into_iter().zip(components.iter_mut()) {
+            crate::utils::operand_to_be_bytes(operand, bytes);
+        }
+
+        let result = Self::encode_pkcs1(&components, out);
+        components.zeroize();
+        result
+    }
+
+    fn encode_pkcs1<'a>(components: &[[u8; T::BLOCKSIZE]; 8], out: &'a mut [u8]) -> Result<&'a [u8]> {
+        let uint = |bytes| UintRef::new(bytes).map_err(|e| Error::PKCS1Error(e.into()));
+        let [n, e, d, p, q, dp, dq, qinv] = components;
+
+        let priv_key = RsaPrivate {
+            modulus: uint(n)?,
+            public_exponent: uint(e)?,
+            private_exponent: uint(d)?,
+            prime1: uint(p)?,
+            prime2: uint(q)?,
+            exponent1: uint(dp)?,
+            exponent2: uint(dq)?,
+            coefficient: uint(qinv)?,
+            other_prime_infos: None,
+        };
+
+        encode_der(&priv_key, out, |e| Error::PKCS1Error(e.into()))
+    }
+
+    /// Writes the key as der encoded pkcs8 PrivateKeyInfo to `out`.
+    pub fn to_pkcs8_der<'a>(&self, out: &'a mut [u8]) -> Result<&'a [u8]> {
+        let mut pkcs1_buffer = [0u8; MAX_PRIVATE_KEY_DER_LEN];
+        let result = match self.to_pkcs1_der(&mut pkcs1_buffer) {
+            Ok(pkcs1) => encode_der(
+                &PrivateKeyInfo::new(RSA_ALGORITHM_ID, pkcs1),
+                out,
+                |e| Error::PKCS8Error(e.into())
+            ),
+            Err(e) => Err(e),
+        };
+
+        pkcs1_buffer.zeroize();
+        result
+    }
+
+    /// Writes the key as pem encoded pkcs1 RSAPrivateKey to `out`.
+    pub fn to_pkcs1_pem<'a>(&self, out: &'a mut [u8]) -> Result<&'a str> {
+        let mut der_buffer = [0u8; MAX_PRIVATE_KEY_DER_LEN];
+        let result = match self.to_pkcs1_der(&mut der_buffer) {
+            Ok(der) => crate::pem::encode("RSA PRIVATE KEY", der, out),
+            Err(e) => Err(e),
+        };
+
+        der_buffer.zeroize();
+        result
+    }
+
+    /// Writes the key as pem encoded pkcs8 PrivateKeyInfo to `out`.
+    pub fn to_pkcs8_pem<'a>(&self, out: &'a mut [u8]) -> Result<&'a str> {
+        let mut der_buffer = [0u8; MAX_PRIVATE_KEY_DER_LEN];
+        let result = match self.to_pkcs8_der(&mut der_buffer) {
+            Ok(der) => crate::pem::encode("PRIVATE KEY", der, out),
+            Err(e) => Err(e),
+        };
+
+        der_buffer.zeroize();
+        result
+    }
 }
 
 impl<T> PrivateKeyParts<T> for RsaPrivateKey<T>
@@ -89,6 +334,42 @@ where
     fn d(&self) -> &<T as RsaKey>::OperandType {
         &self.d
     }
+
+    fn p(&self) -> &<T as RsaKey>::OperandType {
+        &self.p
+    }
+
+    fn q(&self) -> &<T as RsaKey>::OperandType {
+        &self.q
+    }
+
+    fn dp(&self) -> &<T as RsaKey>::OperandType {
+        &self.dp
+    }
+
+    fn dq(&self) -> &<T as RsaKey>::OperandType {
+        &self.dq
+    }
+
+    fn qinv(&self) -> &<T as RsaKey>::OperandType {
+        &self.qinv
+    }
+
+    fn p_mprime(&self) -> u32 {
+        self.p_mprime
+    }
+
+    fn q_mprime(&self) -> u32 {
+        self.q_mprime
+    }
+
+    fn p_r(&self) -> &<T as RsaKey>::OperandType {
+        &self.p_r
+    }
+
+    fn q_r(&self) -> &<T as RsaKey>::OperandType {
+        &self.q_r
+    }
 }
 
 impl<T> PublicKeyParts<T> for RsaPrivateKey<T> 
@@ -117,13 +398,32 @@ impl<T> RsaPrivateKey <T>
 where
     T: RsaKey<OperandType = [u32; T::OperandWords]>
 {
+    /// Returns the public key of the key pair.
+    pub fn to_public_key(&self) -> RsaPublicKey<T> {
+        RsaPublicKey::new_from_parts(self.n, self.e, self.m_prime, self.r)
+    }
+
     pub fn decrypt<'a, P: PaddingScheme<T>>(
-        &self, rsa: &mut Rsa<Blocking>, padding: &P, ciphertext: &[u8], plaintext_buffer: &'a mut [u8]
+        &self, rsa: &mut Rsa<Blocking>, rng: &mut Rng, padding: &P, ciphertext: &[u8], plaintext_buffer: &'a mut [u8]
     ) -> Result<&'a [u8]> 
     where 
         T: Decrypt<T>
     {
-        padding.decrypt(rsa, self, ciphertext, plaintext_buffer)
+        padding.decrypt(rsa, rng, self, ciphertext, plaintext_buffer)
+    }
+
+    /// Opens an envelope created with `RsaPublicKey::seal_envelope` and the same padding scheme,
+    /// writes the payload to `plaintext_buffer`.
+    ///
+    /// A malformed envelope fails with `Error::InvalidEncoding`, a wrong key or a modified envelope
+    /// with `Error::Decryption`.
+    pub fn open_envelope<'a, P: PaddingScheme<T>>(
+        &self, rsa: &mut Rsa<Blocking>, rng: &mut Rng, aes: &mut Aes<'_>, padding: &P, envelope: &[u8], plaintext_buffer: &'a mut [u8]
+    ) -> Result<&'a [u8]>
+    where
+        T: Decrypt<T>
+    {
+        envelope::open(self, rsa, rng, aes, padding, envelope, plaintext_buffer)
     }
 
     pub fn sign<'a, S>(
@@ -134,6 +434,16 @@ where
     {
         scheme.sign(self, rng, rsa, digest_in, signature_out)
     }
+
+    /// Hashes `message` with the hash algorithm bound to `scheme` and signs the digest.
+    pub fn sign_message<'a, S>(
+        &self, rng: Rng, rsa: &mut Rsa<Blocking>, scheme: &S, message: &[u8], signature_out: &'a mut [u8]) -> Result<&'a [u8]>
+    where
+        S: MessageSignatureScheme<T>,
+        T: Decrypt<T>
+    {
+        scheme.sign_message(self, rng, rsa, message, signature_out)
+    }
 }
 
 impl<T: RsaKey> Zeroize for RsaPrivateKey<T> 
@@ -146,9 +456,18 @@ where
             self.n[i] = 0;
             self.r[i] = 0;
             self.e[i] = 0;
+            self.p[i] = 0;
+            self.q[i] = 0;
+            self.dp[i] = 0;
+            self.dq[i] = 0;
+            self.qinv[i] = 0;
+            self.p_r[i] = 0;
+            self.q_r[i] = 0;
         }
 
         self.m_prime.zeroize();
+        self.p_mprime.zeroize();
+        self.q_mprime.zeroize();
         self.phantom.zeroize();
     }
 }
@@ -1,16 +1,17 @@
 use core::marker::PhantomData;
 
-use crate::{error::{Error, Result}, traits::{PaddingScheme, PublicKeyParts, SignatureScheme}};
+use crate::{error::{Error, Result}, traits::{MessageSignatureScheme, PaddingScheme, PublicKeyParts, SignatureScheme}};
 
 
 use base64::Engine;
 use crypto_bigint::Uint;
-use esp_hal::{rng::Rng, rsa::Rsa, Blocking};
+use esp_hal::{aes::Aes, rng::Rng, rsa::Rsa, Blocking};
+use der::{asn1::{BitStringRef, UintRef}, Tag};
 use spki::SubjectPublicKeyInfoRef;
 use pkcs1::RsaPublicKey as RsaPubKey;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use super::{Encrypt, RsaKey};
+use super::{check_algorithm, encode_der, envelope, sequence_element_tag, Encrypt, RsaKey, Verifier, MAX_PUBLIC_KEY_DER_LEN, RSA_ALGORITHM_ID};
 
 
 #[derive(Debug)]
@@ -39,6 +40,8 @@ where
         let pub_key_info = SubjectPublicKeyInfoRef::try_from(bytes)
             .map_err(|e| Error::SPKIError(e) )?; 
 
+        check_algorithm(&pub_key_info.algorithm)?;
+
         let pub_key_bytes = match pub_key_info.subject_public_key.as_bytes() {
             Some(pkb) => Ok(pkb),
             None => Err(Error::AlignmentError("Subject public key BIT STRING has unused bits")),
@@ -47,6 +50,27 @@ where
         let pub_key: RsaPubKey = RsaPubKey::try_from(pub_key_bytes)
             .map_err(|e| Error::PKCS1Error(e))?;
 
+        Self::from_pkcs1_key(pub_key)
+    }
+
+    /// Parses a der encoded pkcs1 RSAPublicKey without SubjectPublicKeyInfo wrapper.
+    pub fn from_pkcs1_der(bytes: &[u8]) -> Result<Self> {
+        let pub_key = RsaPubKey::try_from(bytes)
+            .map_err(Error::PKCS1Error)?;
+
+        Self::from_pkcs1_key(pub_key)
+    }
+
+    /// Parses a der encoded SubjectPublicKeyInfo or pkcs1 RSAPublicKey.
+    pub fn from_der_any(bytes: &[u8]) -> Result<Self> {
+        // A SubjectPublicKeyInfo starts with the AlgorithmIdentifier, an RSAPublicKey with the modulus
+        match sequence_element_tag(bytes, 0) {
+            Some(Tag::Integer) => Self::from_pkcs1_der(bytes),
+            _ => Self::new_from_der(bytes),
+        }
+    }
+
+    fn from_pkcs1_key(pub_key: RsaPubKey) -> Result<Self> {
         let modulus_bytes = pub_key.modulus.as_bytes();
 
         if modulus_bytes.len() != T::BLOCKSIZE {
@@ -58,6 +82,9 @@ where
         let r: Uint<{T::OperandWords}> = crate::utils::compute_r::<{T::OperandWords}>(&n);
 
         let d_bytes = pub_key.public_exponent.as_bytes();
+        if d_bytes.len() > T::BLOCKSIZE {
+            return Err(Error::RsaKeySizeError);
+        }
         let mut d_buffer: [u8; T::BLOCKSIZE] = [0u8; T::BLOCKSIZE];
         let start_index = T::BLOCKSIZE - d_bytes.len();
         d_buffer[start_index..].copy_from_slice(&d_bytes);
@@ -82,6 +109,60 @@ where
 
         return Self::new_from_der(&bytes[..written_bytes]);
     }
+
+    /// Parses a pem encoded SubjectPublicKeyInfo ("PUBLIC KEY") or pkcs1 RSAPublicKey ("RSA PUBLIC KEY").
+    pub fn new_from_pem(pem: &str) -> Result<Self> {
+        let mut bytes = [0u8; 4096];
+        let (label, der) = crate::pem::decode(pem, &mut bytes)?;
+
+        match label {
+            "PUBLIC KEY" => Self::new_from_der(der),
+            "RSA PUBLIC KEY" => Self::from_pkcs1_der(der),
+            label => Err(crate::pem::unexpected_label(label)),
+        }
+    }
+
+    /// Writes the key as der encoded pkcs1 RSAPublicKey to `out`.
+    pub fn to_pkcs1_der<'a>(&self, out: &'a mut [u8]) -> Result<&'a [u8]> {
+        let mut n = [0u8; T::BLOCKSIZE];
+        let mut e = [0u8; T::BLOCKSIZE];
+        crate::utils::operand_to_be_bytes(&self.n, &mut n);
+        crate::utils::operand_to_be_bytes(&self.d, &mut e);
+
+        let pub_key = RsaPubKey {
+            modulus: UintRef::new(&n).map_err(|e| Error::PKCS1Error(e.into()))?,
+            public_exponent: UintRef::new(&e).map_err(|e| Error::PKCS1Error(e.into()))?,
+        };
+
+        encode_der(&pub_key, out, |e| Error::PKCS1Error(e.into()))
+    }
+
+    /// Writes the key as der encoded SubjectPublicKeyInfo to `out`.
+    pub fn to_public_key_der<'a>(&self, out: &'a mut [u8]) -> Result<&'a [u8]> {
+        let mut pkcs1_buffer = [0u8; MAX_PUBLIC_KEY_DER_LEN];
+        let pkcs1 = self.to_pkcs1_der(&mut pkcs1_buffer)?;
+
+        let pub_key_info = SubjectPublicKeyInfoRef {
+            algorithm: RSA_ALGORITHM_ID,
+            subject_public_key: BitStringRef::from_bytes(pkcs1).map_err(|e| Error::SPKIError(e.into()))?,
+        };
+
+        encode_der(&pub_key_info, out, |e| Error::SPKIError(e.into()))
+    }
+
+    /// Writes the key as pem encoded pkcs1 RSAPublicKey to `out`.
+    pub fn to_pkcs1_pem<'a>(&self, out: &'a mut [u8]) -> Result<&'a str> {
+        let mut der_buffer = [0u8; MAX_PUBLIC_KEY_DER_LEN];
+        let der = self.to_pkcs1_der(&mut der_buffer)?;
+        crate::pem::encode("RSA PUBLIC KEY", der, out)
+    }
+
+    /// Writes the key as pem encoded SubjectPublicKeyInfo to `out`.
+    pub fn to_public_key_pem<'a>(&self, out: &'a mut [u8]) -> Result<&'a str> {
+        let mut der_buffer = [0u8; MAX_PUBLIC_KEY_DER_LEN];
+        let der = self.to_public_key_der(&mut der_buffer)?;
+        crate::pem::encode("PUBLIC KEY", der, out)
+    }
 }
 
 
@@ -89,6 +170,13 @@ impl<T: RsaKey> RsaPublicKey<T>
 where 
     T: RsaKey<OperandType = [u32; T::OperandWords]>
 {
+    /// Creates the key from its modulus and public exponent together with their precomputed values.
+    pub(crate) fn new_from_parts(n: T::OperandType, e: T::OperandType, m_prime: u32, r: T::OperandType) -> Self {
+        Self {
+            d: e, n, m_prime, r, phantom: PhantomData
+        }
+    }
+
     pub fn encrypt<
         'a, P: PaddingScheme<T>
     >(
@@ -101,6 +189,19 @@ where
         padding.encrypt(rsa, rng, self, plaintext, ciphertext_buffer)
     }
 
+    /// Encrypts `plaintext` of any length with a random AES-256-GCM key that is encrypted with `padding`,
+    /// writes the envelope described in `rsa::envelope` to `out`.
+    ///
+    /// `out` needs `ENVELOPE_OVERHEAD + T::BLOCKSIZE + plaintext.len()` bytes.
+    pub fn seal_envelope<'a, P: PaddingScheme<T>>(
+        &self, rsa: &mut Rsa<Blocking>, rng: &mut Rng, aes: &mut Aes<'_>, padding: &P, plaintext: &[u8], out: &'a mut [u8]
+    ) -> Result<&'a [u8]>
+    where
+        T: Encrypt<T>
+    {
+        envelope::seal(self, rsa, rng, aes, padding, plaintext, out)
+    }
+
     pub fn verify<S: SignatureScheme<T>>(
         &self, rsa: &mut Rsa<Blocking>, padding: S, hashed: &[u8], sig: &[u8]
     ) -> Result<()> 
@@ -110,6 +211,23 @@ where
     {
         padding.verify(self, rsa, hashed, sig)
     }
+
+    /// Hashes `message` with the hash algorithm bound to `scheme` and verifies `sig` over the digest.
+    pub fn verify_message<S: MessageSignatureScheme<T>>(
+        &self, rsa: &mut Rsa<Blocking>, scheme: &S, message: &[u8], sig: &[u8]
+    ) -> Result<()>
+    where
+        T: Encrypt<T>
+    {
+        scheme.verify_message(self, rsa, message, sig)
+    }
+
+    /// Creates a `Verifier` for a message that is passed in chunks.
+    pub fn verifier<'a, 'd, S: MessageSignatureScheme<T>>(
+        &'a self, rsa: &'a mut Rsa<'d, Blocking>, scheme: &'a S
+    ) -> Verifier<'a, 'd, Self, S> {
+        Verifier::new(self, rsa, scheme)
+    }
 }
 
 impl<T: RsaKey> PublicKeyParts<T> for RsaPublicKey<T> 
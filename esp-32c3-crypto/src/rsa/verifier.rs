@@ -0,0 +1,69 @@
+use esp_hal::{rsa::Rsa, Blocking};
+use zeroize::Zeroize;
+
+use crate::{
+    error::Result,
+    hash::{context::DynHashContext, sha::MAX_HASH_LEN},
+    traits::MessageSignatureScheme
+};
+
+use super::{Encrypt, RsaKey, RsaPublicKey};
+
+
+/// Verifies a signature over a message that is passed in chunks as it arrives,
+/// e.g. an OTA image while it is written to flash. Created with `RsaPublicKey::verifier`.
+///
+/// The message is hashed on the accelerator of the signature scheme with its hash algorithm,
+/// so the scheme has to have a hasher (`Pss` or `Pkcs1v15SignWithHash`).
+///
+/// `K` is the `RsaPublicKey<T>`: with the key size as parameter of the struct, or the scheme bound
+/// on the impl, `generic_const_exprs` fails to unify the operand size in crates using the verifier.
+pub struct Verifier<'a, 'd, K, S> {
+    pub_key: &'a K,
+    rsa: &'a mut Rsa<'d, Blocking>,
+    scheme: &'a S,
+    context: DynHashContext,
+}
+
+impl<'a, 'd, T, S> Verifier<'a, 'd, RsaPublicKey<T>, S>
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]>,
+{
+    pub(crate) fn new(pub_key: &'a RsaPublicKey<T>, rsa: &'a mut Rsa<'d, Blocking>, scheme: &'a S) -> Self
+    where
+        S: MessageSignatureScheme<T>
+    {
+        Self {
+            pub_key,
+            rsa,
+            scheme,
+            context: DynHashContext::new(scheme.hash_algorithm())
+        }
+    }
+
+    /// Feeds the next chunk of the message into the digest.
+    pub fn update(&mut self, chunk: &[u8])
+    where
+        S: MessageSignatureScheme<T>
+    {
+        let mut sha = self.scheme.accelerator();
+        self.context.update(&mut sha, chunk);
+    }
+
+    /// Checks that `signature` is valid for all chunks passed to `update`.
+    pub fn finalize(mut self, signature: &[u8]) -> Result<()>
+    where
+        S: MessageSignatureScheme<T>,
+        T: Encrypt<T>
+    {
+        let mut digest = [0u8; MAX_HASH_LEN];
+        let digest_len = {
+            let mut sha = self.scheme.accelerator();
+            self.context.finalize_into(&mut sha, &mut digest)?.len()
+        };
+
+        let result = self.scheme.verify(self.pub_key, self.rsa, &digest[..digest_len], signature);
+        digest.zeroize();
+        result
+    }
+}
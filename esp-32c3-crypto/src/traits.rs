@@ -1,7 +1,11 @@
+use core::cell::RefMut;
+
 use esp_hal::rng::Rng;
 use esp_hal::rsa::Rsa;
 use esp_hal::Blocking;
 
+use crate::hash::context::{DynHashContext, ShaAccelerator};
+use crate::hash::sha::{HashMode, MAX_HASH_LEN};
 use crate::rsa::{Encrypt, Decrypt, RsaKey, RsaPrivateKey, RsaPublicKey};
 use crate::error::Result;
 
@@ -9,6 +13,7 @@ pub trait PaddingScheme<T: RsaKey> where T: RsaKey<OperandType = [u32; T::Operan
     fn decrypt<'a>(
         &self,
         rsa: &mut Rsa<Blocking>,
+        rng: &mut Rng,
         priv_key: &RsaPrivateKey<T>,
         ciphertext: &[u8],
         plaintext_buffer: &'a mut [u8]
@@ -44,9 +49,85 @@ pub trait SignatureScheme<T: RsaKey> where T: RsaKey<OperandType = [u32; T::Oper
         T: Encrypt<T>;
 }
 
+/// A `SignatureScheme` that owns a hasher, so it can sign and verify whole messages.
+pub trait MessageSignatureScheme<T: RsaKey>: SignatureScheme<T> where T: RsaKey<OperandType = [u32; T::OperandWords]> {
+    /// The hash algorithm the digests passed to `sign` and `verify` have to be created with.
+    fn hash_algorithm(&self) -> HashMode;
+
+    /// The accelerator messages are hashed on.
+    fn accelerator(&self) -> RefMut<'_, ShaAccelerator>;
+
+    /// Hashes `message` with the hash algorithm of the scheme and signs the digest.
+    fn sign_message<'a>(
+        &self, priv_key: &RsaPrivateKey<T>, rng: Rng, rsa: &mut esp_hal::rsa::Rsa<Blocking>, message: &[u8], signature_out: &'a mut [u8]
+    ) -> Result<&'a [u8]>
+    where
+        T: Decrypt<T>
+    {
+        let mut digest = [0u8; MAX_HASH_LEN];
+        let digest_len = hash_message(self, message, &mut digest)?;
+
+        self.sign(priv_key, rng, rsa, &digest[..digest_len], signature_out)
+    }
+
+    /// Hashes `message` with the hash algorithm of the scheme and verifies `sig` over the digest.
+    fn verify_message(
+        &self, pub_key: &RsaPublicKey<T>, rsa: &mut esp_hal::rsa::Rsa<Blocking>, message: &[u8], sig: &[u8]
+    ) -> Result<()>
+    where
+        T: Encrypt<T>
+    {
+        let mut digest = [0u8; MAX_HASH_LEN];
+        let digest_len = hash_message(self, message, &mut digest)?;
+
+        self.verify(pub_key, rsa, &digest[..digest_len], sig)
+    }
+}
+
+/// Writes the digest of `message` with the hash algorithm of `scheme` to `out` and returns its length.
+fn hash_message<T, S>(scheme: &S, message: &[u8], out: &mut [u8; MAX_HASH_LEN]) -> Result<usize>
+where
+    T: RsaKey<OperandType = [u32; T::OperandWords]>,
+    S: MessageSignatureScheme<T> + ?Sized,
+{
+    let mut context = DynHashContext::new(scheme.hash_algorithm());
+    let mut sha = scheme.accelerator();
+    context.update(&mut sha, message);
+    let digest_len = context.finalize_into(&mut sha, out)?.len();
+
+    Ok(digest_len)
+}
+
 pub trait PrivateKeyParts<T: RsaKey> {
     /// Returns the private exponent of the key.
     fn d(&self) -> &T::OperandType;
+
+    /// Returns the first prime factor of the modulus.
+    fn p(&self) -> &T::OperandType;
+
+    /// Returns the second prime factor of the modulus.
+    fn q(&self) -> &T::OperandType;
+
+    /// Returns the crt exponent d mod (p - 1).
+    fn dp(&self) -> &T::OperandType;
+
+    /// Returns the crt exponent d mod (q - 1).
+    fn dq(&self) -> &T::OperandType;
+
+    /// Returns the crt coefficient q^-1 mod p.
+    fn qinv(&self) -> &T::OperandType;
+
+    /// Returns the pre calculated mprime of p used for hw acceleration
+    fn p_mprime(&self) -> u32;
+
+    /// Returns the pre calculated mprime of q used for hw acceleration
+    fn q_mprime(&self) -> u32;
+
+    /// Returns the precomputed r of p for hw acceleration
+    fn p_r(&self) -> &T::OperandType;
+
+    /// Returns the precomputed r of q for hw acceleration
+    fn q_r(&self) -> &T::OperandType;
 }
 
 pub trait PublicKeyParts<T: RsaKey> {
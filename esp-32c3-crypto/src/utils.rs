@@ -1,10 +1,18 @@
-use crypto_bigint::Uint;
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess},
+    Integer,
+    Limb,
+    Uint
+};
+use zeroize::Zeroize;
 use esp_hal::{
     rsa::{
-        operand_sizes::{Op1024, Op2048},
+        operand_sizes::{Op512, Op1024, Op1536, Op2048, Op3072},
         Rsa,
         RsaModularExponentiation
     },
+    rng::Rng,
     Blocking
 };
 
@@ -17,14 +25,54 @@ pub const fn compute_mprime<const N: usize>(modulus: &Uint<N>) -> u32 {
 pub const fn compute_r<const LIMBS: usize>(modulus: &Uint<LIMBS>) 
 -> Uint<LIMBS>
 where [(); LIMBS * 2 + 1]: Sized 
+{
+    compute_r_sized(modulus, LIMBS)
+}
+
+/// Computes r for a modulus that is used with an operand size of `operand_words` words,
+/// which can be smaller than `LIMBS`.
+pub const fn compute_r_sized<const LIMBS: usize>(modulus: &Uint<LIMBS>, operand_words: usize)
+-> Uint<LIMBS>
+where [(); LIMBS * 2 + 1]: Sized
 {
     let mut d = [0_u32; LIMBS * 2 + 1];
-    d[d.len() - 1] = 1;
+    d[operand_words * 2] = 1;
     let d = Uint::from_words(d);
     d.const_rem(&modulus.resize()).0.resize()
 }
 
 
+/// Writes the little endian words of an operand as big endian integer to `out`,
+/// which has to be exactly 4 bytes per word.
+pub fn operand_to_be_bytes(operand: &[u32], out: &mut [u8]) {
+    for (word, bytes) in operand.iter().rev().zip(out.chunks_exact_mut(4)) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+
+pub fn run_expo_512(
+    rsa: &mut Rsa<Blocking>,
+    exponent: &[u32; 16],
+    modulus: &[u32; 16],
+    m_prime: u32,
+    base: &[u32; 16],
+    r: &[u32; 16],
+    output: &mut [u32; 16]
+)
+{
+    let mut rsa_exp: RsaModularExponentiation<Op512, Blocking> = RsaModularExponentiation::new(
+        rsa,
+        exponent,
+        modulus,
+        m_prime,
+    );
+
+    rsa_exp.start_exponentiation(base, r);
+    rsa_exp.read_results(output);
+}
+
+
 pub fn run_expo_1024(
     rsa: &mut Rsa<Blocking>,
     exponent: &[u32; 32],
@@ -47,6 +95,28 @@ pub fn run_expo_1024(
 }
 
 
+pub fn run_expo_1536(
+    rsa: &mut Rsa<Blocking>,
+    exponent: &[u32; 48],
+    modulus: &[u32; 48],
+    m_prime: u32,
+    base: &[u32; 48],
+    r: &[u32; 48],
+    output: &mut [u32; 48]
+)
+{
+    let mut rsa_exp: RsaModularExponentiation<Op1536, Blocking> = RsaModularExponentiation::new(
+        rsa,
+        exponent,
+        modulus,
+        m_prime,
+    );
+
+    rsa_exp.start_exponentiation(base, r);
+    rsa_exp.read_results(output);
+}
+
+
 pub fn run_expo_2048(
     rsa: &mut Rsa<Blocking>,
     exponent: &[u32; 64],
@@ -66,4 +136,376 @@ pub fn run_expo_2048(
 
     rsa_exp.start_exponentiation(base, r);
     rsa_exp.read_results(output);
-}
\ No newline at end of file
+}
+
+
+pub fn run_expo_3072(
+    rsa: &mut Rsa<Blocking>,
+    exponent: &[u32; 96],
+    modulus: &[u32; 96],
+    m_prime: u32,
+    base: &[u32; 96],
+    r: &[u32; 96],
+    output: &mut [u32; 96]
+)
+{
+    let mut rsa_exp: RsaModularExponentiation<Op3072, Blocking> = RsaModularExponentiation::new(
+        rsa,
+        exponent,
+        modulus,
+        m_prime,
+    );
+
+    rsa_exp.start_exponentiation(base, r);
+    rsa_exp.read_results(output);
+}
+
+
+/// The rsa peripheral only supports operands up to 3072 bits,
+/// so 4096 bit exponentiations are done in software.
+pub fn run_expo_4096(
+    _rsa: &mut Rsa<Blocking>,
+    exponent: &[u32; 128],
+    modulus: &[u32; 128],
+    _m_prime: u32,
+    base: &[u32; 128],
+    _r: &[u32; 128],
+    output: &mut [u32; 128]
+)
+{
+    let exponent: Uint<128> = Uint::from_words(*exponent);
+    let params = DynResidueParams::new(&Uint::from_words(*modulus));
+
+    let result = DynResidue::new(&Uint::from_words(*base), params)
+        .pow_bounded_exp(&exponent, exponent.bits());
+
+    *output = result.retrieve().to_words();
+}
+
+
+/// Signature of the `run_expo_*` functions for an operand size of `N` words.
+pub type RunExpo<const N: usize> = fn(
+    &mut Rsa<Blocking>,
+    &[u32; N],
+    &[u32; N],
+    u32,
+    &[u32; N],
+    &[u32; N],
+    &mut [u32; N]
+);
+
+/// Modulus and exponent of an exponentiation with the values precomputed for hw acceleration.
+/// For the crt primes of a private key all values are stored in full size operands,
+/// only the lower half of the words is used.
+pub struct ExpoOperands<'a, const LIMBS: usize> {
+    pub modulus: &'a [u32; LIMBS],
+    pub exponent: &'a [u32; LIMBS],
+    pub m_prime: u32,
+    pub r: &'a [u32; LIMBS],
+}
+
+/// Computes base^d mod n with two half size exponentiations on the rsa peripheral
+/// and recombines the results using the chinese remainder theorem (RFC 8017 5.1.2).
+pub fn run_crt_expo<const LIMBS: usize, const HALF: usize>(
+    rsa: &mut Rsa<Blocking>,
+    p: &ExpoOperands<LIMBS>,
+    q: &ExpoOperands<LIMBS>,
+    q_inv: &[u32; LIMBS],
+    base: &[u32; LIMBS],
+    output: &mut [u32; LIMBS],
+    run_half_expo: RunExpo<HALF>
+)
+{
+    let base: Uint<LIMBS> = Uint::from_words(*base);
+
+    // m_1 = c^dP mod p and m_2 = c^dQ mod q
+    let m_1 = run_crt_half_expo(rsa, &base, p, run_half_expo);
+    let m_2 = run_crt_half_expo(rsa, &base, q, run_half_expo);
+
+    let p: Uint<HALF> = Uint::<LIMBS>::from_words(*p.modulus).resize();
+    let q: Uint<HALF> = Uint::<LIMBS>::from_words(*q.modulus).resize();
+    let q_inv: Uint<HALF> = Uint::<LIMBS>::from_words(*q_inv).resize();
+
+    // h = (m_1 - m_2) * qInv mod p
+    let m_2_mod_p = m_2.const_rem(&p).0;
+    let difference = m_1.sub_mod(&m_2_mod_p, &p);
+    let h = Uint::const_rem_wide(difference.mul_wide(&q_inv), &p).0;
+
+    // m = m_2 + q * h
+    let (low, high) = h.mul_wide(&q);
+    let mut m = [0u32; LIMBS];
+    m[..HALF].copy_from_slice(&low.to_words());
+    m[HALF..].copy_from_slice(&high.to_words());
+
+    *output = Uint::<LIMBS>::from_words(m)
+        .wrapping_add(&m_2.resize())
+        .to_words();
+
+    m.zeroize();
+}
+
+fn run_crt_half_expo<const LIMBS: usize, const HALF: usize>(
+    rsa: &mut Rsa<Blocking>,
+    base: &Uint<LIMBS>,
+    prime: &ExpoOperands<LIMBS>,
+    run_half_expo: RunExpo<HALF>
+) -> Uint<HALF>
+{
+    // The rsa peripheral requires the base to be reduced by the modulus
+    let mut reduced_base: [u32; HALF] = base
+        .const_rem(&Uint::from_words(*prime.modulus)).0
+        .resize()
+        .to_words();
+
+    let mut output = [0u32; HALF];
+    run_half_expo(
+        rsa,
+        prime.exponent[..HALF].try_into().unwrap(),
+        prime.modulus[..HALF].try_into().unwrap(),
+        prime.m_prime,
+        &reduced_base,
+        prime.r[..HALF].try_into().unwrap(),
+        &mut output
+    );
+
+    let result = Uint::from_words(output);
+    reduced_base.zeroize();
+    output.zeroize();
+    result
+}
+
+/// Blinds `base` with a fresh random factor r, so the private exponent is never applied
+/// to an attacker chosen value: writes base * r^e mod n to `blinded_base` and returns r^-1 mod n.
+pub fn blind<const LIMBS: usize>(
+    rsa: &mut Rsa<Blocking>,
+    rng: &mut Rng,
+    public: &ExpoOperands<LIMBS>,
+    base: &[u32; LIMBS],
+    blinded_base: &mut [u32; LIMBS],
+    run_expo: RunExpo<LIMBS>
+) -> [u32; LIMBS]
+{
+    let modulus: Uint<LIMBS> = Uint::from_words(*public.modulus);
+
+    // Draw r until it is invertible mod n, which fails only if r shares a prime factor with n
+    let mut words = [0u32; LIMBS];
+    let (mut r, r_inv) = loop {
+        for word in words.iter_mut() {
+            *word = rng.random();
+        }
+        let r = Uint::from_words(words).const_rem(&modulus).0;
+        let (r_inv, invertible) = r.inv_odd_mod(&modulus);
+        if bool::from(invertible) {
+            break (r.to_words(), r_inv.to_words());
+        }
+    };
+
+    // r^e mod n
+    let mut r_e = [0u32; LIMBS];
+    run_expo(rsa, public.exponent, public.modulus, public.m_prime, &r, public.r, &mut r_e);
+
+    // base * r^e mod n, this also reduces the base for the rsa peripheral
+    let base: Uint<LIMBS> = Uint::from_words(*base);
+    *blinded_base = Uint::const_rem_wide(base.mul_wide(&Uint::from_words(r_e)), &modulus).0
+        .to_words();
+
+    words.zeroize();
+    r.zeroize();
+    r_e.zeroize();
+    r_inv
+}
+
+/// Removes the blinding factor from the result of a blinded private key operation:
+/// writes result * r^-1 mod n to `output`.
+pub fn unblind<const LIMBS: usize>(
+    modulus: &[u32; LIMBS],
+    blinded_result: &[u32; LIMBS],
+    r_inv: &[u32; LIMBS],
+    output: &mut [u32; LIMBS]
+)
+{
+    let blinded_result: Uint<LIMBS> = Uint::from_words(*blinded_result);
+    let r_inv: Uint<LIMBS> = Uint::from_words(*r_inv);
+    *output = Uint::const_rem_wide(blinded_result.mul_wide(&r_inv), &Uint::from_words(*modulus)).0
+        .to_words();
+}
+
+
+/// Odd primes below 256, used to sieve prime candidates before the miller rabin test.
+const SMALL_PRIMES: [u32; 53] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251
+];
+
+/// Generates a random prime of `HALF * 32` bits with the two most significant bits set,
+/// so the product of two of these primes has exactly `HALF * 64` bits.
+/// Candidates with p = 1 mod e are skipped, so the public exponent `e` is invertible mod p - 1.
+pub fn generate_prime<const HALF: usize>(
+    rsa: &mut Rsa<Blocking>,
+    rng: &mut Rng,
+    e: u32,
+    run_expo: RunExpo<HALF>
+) -> Uint<HALF>
+where [(); HALF * 2 + 1]: Sized
+{
+    let mut candidate = [0u32; HALF];
+    loop {
+        for word in candidate.iter_mut() {
+            *word = rng.random();
+        }
+        candidate[HALF - 1] |= 0xc000_0000;
+        candidate[0] |= 1;
+
+        if SMALL_PRIMES.iter().any(|&p| rem_u32(&candidate, p) == 0) || rem_u32(&candidate, e) == 1 {
+            continue;
+        }
+
+        let prime = Uint::from_words(candidate);
+        if is_probable_prime(rsa, rng, &prime, run_expo) {
+            candidate.zeroize();
+            return prime;
+        }
+    }
+}
+
+/// Checks that the primes of a key pair are not too close to each other:
+/// |p - q| > 2^(nlen/2 - 100) (FIPS 186-4 B.3.3 step 5.4).
+/// The comparisons are constant time, so the check does not leak which prime is larger.
+pub fn primes_far_apart<const HALF: usize>(p: &Uint<HALF>, q: &Uint<HALF>) -> bool {
+    let distance = Uint::conditional_select(&p.wrapping_sub(q), &q.wrapping_sub(p), q.ct_gt(p));
+    distance.ct_gt(&Uint::ONE.shl_vartime(HALF * 32 - 100)).into()
+}
+
+/// Least common multiple of `a` and `b`, their product has to fit into `LIMBS` words.
+/// The gcd and the division take a fixed number of steps, so the running time does not depend on the values.
+pub fn lcm<const LIMBS: usize>(a: &Uint<LIMBS>, b: &Uint<LIMBS>) -> Uint<LIMBS> {
+    div(&a.mul_wide(b).0, &gcd(a, b))
+}
+
+/// Greatest common divisor of the nonzero `a` and `b` with the binary gcd algorithm.
+/// Every step halves at least one operand, so the loop always runs for the combined bit width.
+pub fn gcd<const LIMBS: usize>(a: &Uint<LIMBS>, b: &Uint<LIMBS>) -> Uint<LIMBS> {
+    let (mut a, mut b) = (*a, *b);
+    let mut shift = 0usize;
+    for _ in 0..2 * Uint::<LIMBS>::BITS {
+        // If both are odd, subtract the smaller from the larger
+        let both_odd = a.is_odd() & b.is_odd();
+        let a_less = a.ct_lt(&b);
+        let a_minus_b = a.wrapping_sub(&b);
+        let b_minus_a = b.wrapping_sub(&a);
+        a.conditional_assign(&a_minus_b, both_odd & !a_less);
+        b.conditional_assign(&b_minus_a, both_odd & a_less);
+
+        // Now at least one of them is even, common factors of two are added back at the end
+        let a_even = !a.is_odd();
+        let b_even = !b.is_odd();
+        shift += usize::from(bool::from(a_even & b_even));
+        a.conditional_assign(&a.shr_vartime(1), a_even);
+        b.conditional_assign(&b.shr_vartime(1), b_even);
+    }
+
+    // One of them is zero now
+    a.bitor(&b).shl(shift)
+}
+
+/// Quotient of `dividend` and the nonzero `divisor` by long division over all bits of the dividend,
+/// unlike `wrapping_div` the running time does not depend on the size of the divisor.
+fn div<const LIMBS: usize>(dividend: &Uint<LIMBS>, divisor: &Uint<LIMBS>) -> Uint<LIMBS> {
+    let mut quotient = Uint::<LIMBS>::ZERO;
+    let mut remainder = Uint::<LIMBS>::ZERO;
+    for i in (0..Uint::<LIMBS>::BITS).rev() {
+        // The bit shifted out of the remainder is part of it, the remainder is then larger than the divisor
+        let carry = Choice::from(remainder.bit(Uint::<LIMBS>::BITS - 1));
+        remainder = remainder.shl_vartime(1).bitor(&Uint::conditional_select(
+            &Uint::ZERO,
+            &Uint::ONE,
+            dividend.bit(i).into()
+        ));
+
+        let (difference, borrow) = remainder.sbb(divisor, Limb::ZERO);
+        let subtract = carry | borrow.ct_eq(&Limb::ZERO);
+        remainder.conditional_assign(&difference, subtract);
+        quotient = quotient.shl_vartime(1).bitor(&Uint::conditional_select(&Uint::ZERO, &Uint::ONE, subtract));
+    }
+
+    quotient
+}
+
+/// Remainder of the little endian integer `words` divided by `divisor`.
+fn rem_u32<const N: usize>(words: &[u32; N], divisor: u32) -> u32 {
+    words.iter().rev().fold(0u64, |rem, &word| ((rem << 32) | word as u64) % divisor as u64) as u32
+}
+
+/// Number of miller rabin rounds for a candidate of `limbs` words. This is at least the number of rounds
+/// FIPS 186-4 table C.3 requires for an error probability below 2^-100.
+const fn miller_rabin_rounds(limbs: usize) -> usize {
+    match limbs * 32 {
+        0..=512 => 7,
+        513..=1024 => 5,
+        _ => 4,
+    }
+}
+
+/// Miller rabin probabilistic primality test of the odd `candidate`,
+/// the witness exponentiations run on the rsa peripheral.
+fn is_probable_prime<const HALF: usize>(
+    rsa: &mut Rsa<Blocking>,
+    rng: &mut Rng,
+    candidate: &Uint<HALF>,
+    run_expo: RunExpo<HALF>
+) -> bool
+where [(); HALF * 2 + 1]: Sized
+{
+    let modulus = candidate.to_words();
+    let m_prime = compute_mprime(candidate);
+    let r = compute_r(candidate).to_words();
+
+    // candidate - 1 = 2^s * d with d odd
+    let candidate_minus_one = candidate.wrapping_sub(&Uint::ONE);
+    let s = candidate_minus_one.trailing_zeros();
+    let d = candidate_minus_one.shr_vartime(s).to_words();
+    let one = Uint::<HALF>::ONE.to_words();
+    let minus_one = candidate_minus_one.to_words();
+
+    let mut two = [0u32; HALF];
+    two[0] = 2;
+
+    let mut witness = [0u32; HALF];
+    let mut x = [0u32; HALF];
+    for _ in 0..miller_rabin_rounds(HALF) {
+        // Random witness in [2, candidate - 2]
+        loop {
+            for word in witness.iter_mut() {
+                *word = rng.random();
+            }
+            let reduced = Uint::from_words(witness).const_rem(candidate).0;
+            if reduced > Uint::ONE && reduced < candidate_minus_one {
+                witness = reduced.to_words();
+                break;
+            }
+        }
+
+        // x = witness^d mod candidate
+        run_expo(rsa, &d, &modulus, m_prime, &witness, &r, &mut x);
+        if x == one || x == minus_one {
+            continue;
+        }
+
+        let mut maybe_prime = false;
+        for _ in 1..s {
+            let base = x;
+            run_expo(rsa, &two, &modulus, m_prime, &base, &r, &mut x);
+            if x == minus_one {
+                maybe_prime = true;
+                break;
+            }
+        }
+
+        if !maybe_prime {
+            return false;
+        }
+    }
+
+    true
+}
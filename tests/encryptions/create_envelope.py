@@ -0,0 +1,58 @@
+#!/usr/bin/env python3
+# Seals ../test_file.txt to public_key_2048 in the envelope format of esp_32c3_crypto::rsa::envelope,
+# the key is wrapped with RSA-OAEP (SHA-256, MGF1-SHA-256). Also writes two envelopes whose PKCS#1 v1.5
+# wrapped key does not unwrap to an AES-256 key, which have to fail like any other bad envelope.
+# Needs the `cryptography` package.
+
+import os
+import struct
+
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import padding
+from cryptography.hazmat.primitives.ciphers.aead import AESGCM
+
+ENVELOPE_VERSION = 1
+
+with open("../keys/public_key_2048.der", "rb") as f:
+    public_key = serialization.load_der_public_key(f.read())
+with open("../test_file.txt", "rb") as f:
+    payload = f.read()
+
+key = AESGCM.generate_key(bit_length=256)
+nonce = os.urandom(12)
+wrapped_key = public_key.encrypt(
+    key,
+    padding.OAEP(mgf=padding.MGF1(algorithm=hashes.SHA256()), algorithm=hashes.SHA256(), label=None),
+)
+
+header = struct.pack(">BH", ENVELOPE_VERSION, len(wrapped_key)) + wrapped_key + nonce + struct.pack(">I", len(payload))
+envelope = header + AESGCM(key).encrypt(nonce, payload, header)
+
+with open("test_file.envelope_oaep_2048_sha256", "wb") as f:
+    f.write(envelope)
+
+
+def seal_raw_pkcs1v15(em, name):
+    """Seals the payload with the RSA block `em` encrypted without padding as wrapped key."""
+    numbers = public_key.public_numbers()
+    k = (numbers.n.bit_length() + 7) // 8
+    wrapped_key = pow(int.from_bytes(em, "big"), numbers.e, numbers.n).to_bytes(k, "big")
+
+    key = AESGCM.generate_key(bit_length=256)
+    nonce = os.urandom(12)
+    header = struct.pack(">BH", ENVELOPE_VERSION, k) + wrapped_key + nonce + struct.pack(">I", len(payload))
+    with open(name, "wb") as f:
+        f.write(header + AESGCM(key).encrypt(nonce, payload, header))
+
+
+def nonzero_bytes(length):
+    return bytes(b % 255 + 1 for b in os.urandom(length))
+
+
+k = (public_key.key_size + 7) // 8
+
+# PKCS#1 v1.5 block without the zero byte that ends the padding string
+seal_raw_pkcs1v15(b"\x00\x02" + nonzero_bytes(k - 2), "test_file.envelope_pkcs1v15_2048_bad_padding")
+
+# Valid PKCS#1 v1.5 padding around a 31 byte key
+seal_raw_pkcs1v15(b"\x00\x02" + nonzero_bytes(k - 3 - 31) + b"\x00" + os.urandom(31), "test_file.envelope_pkcs1v15_2048_short_key")
@@ -1,8 +1,27 @@
 
-for key_size in 1024 2048 4096
+for key_size in 1024 2048 3072 4096
 do
     echo "Generating keys with size: $key_size"
     openssl genrsa -out private_key_$key_size.pem -traditional $key_size
     openssl rsa -in private_key_$key_size.pem -out private_key_$key_size.der -outform DER
     openssl rsa -in private_key_$key_size.pem -pubout -out public_key_$key_size.der -outform DER
-done
\ No newline at end of file
+    openssl pkcs8 -topk8 -nocrypt -in private_key_$key_size.pem -out private_key_${key_size}_pkcs8.pem
+    openssl rsa -in private_key_$key_size.pem -pubout -out public_key_$key_size.pem
+    openssl rsa -in private_key_$key_size.pem -RSAPublicKey_out -out public_key_${key_size}_pkcs1.pem
+    openssl rsa -in private_key_$key_size.pem -traditional -out private_key_${key_size}_pkcs1.der -outform DER
+    openssl rsa -in private_key_$key_size.pem -RSAPublicKey_out -out public_key_${key_size}_pkcs1.der -outform DER
+done
+
+echo "Generating encrypted pkcs8 keys with password: esp32c3"
+for key_size in 1024 2048
+do
+    openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf hmacWithSHA256 -in private_key_$key_size.pem -out private_key_${key_size}_encrypted.der -outform DER -passout pass:esp32c3
+done
+openssl pkcs8 -topk8 -v2 aes-128-cbc -v2prf hmacWithSHA256 -in private_key_1024.pem -out private_key_1024_encrypted_aes128.der -outform DER -passout pass:esp32c3
+openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf hmacWithSHA1 -in private_key_1024.pem -out private_key_1024_encrypted_sha1.der -outform DER -passout pass:esp32c3
+
+echo "Generating ec keys for the algorithm check"
+openssl ecparam -name prime256v1 -genkey -noout -out private_key_ec.pem
+openssl pkcs8 -topk8 -nocrypt -in private_key_ec.pem -out private_key_ec.der -outform DER
+openssl ec -in private_key_ec.pem -pubout -out public_key_ec.der -outform DER
+rm private_key_ec.pem
\ No newline at end of file
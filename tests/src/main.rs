@@ -12,6 +12,24 @@ mod test_key_parsing;
 mod test_signature;
 mod test_encryption;
 mod test_b64_key_parsing;
+mod test_oaep_encryption;
+mod test_pss_signature;
+mod test_key_generation;
+mod test_key_export;
+mod test_pem_key_parsing;
+mod test_pkcs1_key_parsing;
+mod test_encrypted_key_parsing;
+mod test_hmac;
+mod test_hkdf;
+mod test_pbkdf2;
+mod test_digest;
+mod test_verifier;
+mod test_aes;
+mod test_gcm;
+mod test_ccm;
+mod test_chacha20poly1305;
+mod test_envelope;
+mod test_kw;
 
 
 #[entry]
@@ -32,8 +50,44 @@ fn main() -> ! {
 
     // test_signature::test_rsa_signature_pkcs1v15();
 
+    // test_pss_signature::test_rsa_signature_pss();
+
     // test_encryption::test_encryption();
 
+    // test_oaep_encryption::test_oaep_encryption();
+
+    // test_key_generation::test_key_generation();
+
+    // test_key_export::test_key_export();
+
+    // test_pem_key_parsing::test_pem_key_parsing();
+
+    // test_pkcs1_key_parsing::test_pkcs1_key_parsing();
+
+    // test_encrypted_key_parsing::test_encrypted_key_parsing();
+
+    // test_hmac::test_hmac();
+
+    // test_hkdf::test_hkdf();
+
+    // test_pbkdf2::test_pbkdf2();
+
+    // test_digest::test_digest();
+
+    // test_verifier::test_verifier();
+
+    // test_aes::test_aes();
+
+    // test_gcm::test_gcm();
+
+    // test_ccm::test_ccm();
+
+    // test_chacha20poly1305::test_chacha20poly1305();
+
+    // test_envelope::test_envelope();
+
+    // test_kw::test_kw();
+
     loop {
         log::info!("Tests done!");
         delay.delay(30.secs());
@@ -0,0 +1,208 @@
+use esp_32c3_crypto::{
+    aes::{cbc, ctr, ecb, BlockCipher, Esp32C3Aes, SoftAes},
+    error::Error
+};
+use esp_hal::{aes::Aes, peripherals::{Peripherals, AES}};
+
+
+// NIST SP 800-38A F.1 - F.5, all modes encrypt the same four blocks
+const plaintext: &[u8] = &[0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a, 0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51, 0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11, 0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef, 0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17, 0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10];
+
+const key_128: &[u8] = &[0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+const key_256: &[u8] = &[0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4];
+
+const cbc_iv: [u8; 16] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+const ctr_initial_counter: [u8; 16] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff];
+
+const ecb_128: &[u8] = &[0x3a, 0xd7, 0x7b, 0xb4, 0x0d, 0x7a, 0x36, 0x60, 0xa8, 0x9e, 0xca, 0xf3, 0x24, 0x66, 0xef, 0x97, 0xf5, 0xd3, 0xd5, 0x85, 0x03, 0xb9, 0x69, 0x9d, 0xe7, 0x85, 0x89, 0x5a, 0x96, 0xfd, 0xba, 0xaf, 0x43, 0xb1, 0xcd, 0x7f, 0x59, 0x8e, 0xce, 0x23, 0x88, 0x1b, 0x00, 0xe3, 0xed, 0x03, 0x06, 0x88, 0x7b, 0x0c, 0x78, 0x5e, 0x27, 0xe8, 0xad, 0x3f, 0x82, 0x23, 0x20, 0x71, 0x04, 0x72, 0x5d, 0xd4];
+const ecb_256: &[u8] = &[0xf3, 0xee, 0xd1, 0xbd, 0xb5, 0xd2, 0xa0, 0x3c, 0x06, 0x4b, 0x5a, 0x7e, 0x3d, 0xb1, 0x81, 0xf8, 0x59, 0x1c, 0xcb, 0x10, 0xd4, 0x10, 0xed, 0x26, 0xdc, 0x5b, 0xa7, 0x4a, 0x31, 0x36, 0x28, 0x70, 0xb6, 0xed, 0x21, 0xb9, 0x9c, 0xa6, 0xf4, 0xf9, 0xf1, 0x53, 0xe7, 0xb1, 0xbe, 0xaf, 0xed, 0x1d, 0x23, 0x30, 0x4b, 0x7a, 0x39, 0xf9, 0xf3, 0xff, 0x06, 0x7d, 0x8d, 0x8f, 0x9e, 0x24, 0xec, 0xc7];
+const cbc_128: &[u8] = &[0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, 0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9, 0x19, 0x7d, 0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee, 0x95, 0xdb, 0x11, 0x3a, 0x91, 0x76, 0x78, 0xb2, 0x73, 0xbe, 0xd6, 0xb8, 0xe3, 0xc1, 0x74, 0x3b, 0x71, 0x16, 0xe6, 0x9e, 0x22, 0x22, 0x95, 0x16, 0x3f, 0xf1, 0xca, 0xa1, 0x68, 0x1f, 0xac, 0x09, 0x12, 0x0e, 0xca, 0x30, 0x75, 0x86, 0xe1, 0xa7];
+const cbc_256: &[u8] = &[0xf5, 0x8c, 0x4c, 0x04, 0xd6, 0xe5, 0xf1, 0xba, 0x77, 0x9e, 0xab, 0xfb, 0x5f, 0x7b, 0xfb, 0xd6, 0x9c, 0xfc, 0x4e, 0x96, 0x7e, 0xdb, 0x80, 0x8d, 0x67, 0x9f, 0x77, 0x7b, 0xc6, 0x70, 0x2c, 0x7d, 0x39, 0xf2, 0x33, 0x69, 0xa9, 0xd9, 0xba, 0xcf, 0xa5, 0x30, 0xe2, 0x63, 0x04, 0x23, 0x14, 0x61, 0xb2, 0xeb, 0x05, 0xe2, 0xc3, 0x9b, 0xe9, 0xfc, 0xda, 0x6c, 0x19, 0x07, 0x8c, 0x6a, 0x9d, 0x1b];
+const ctr_128: &[u8] = &[0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce, 0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff, 0x5a, 0xe4, 0xdf, 0x3e, 0xdb, 0xd5, 0xd3, 0x5e, 0x5b, 0x4f, 0x09, 0x02, 0x0d, 0xb0, 0x3e, 0xab, 0x1e, 0x03, 0x1d, 0xda, 0x2f, 0xbe, 0x03, 0xd1, 0x79, 0x21, 0x70, 0xa0, 0xf3, 0x00, 0x9c, 0xee];
+const ctr_256: &[u8] = &[0x60, 0x1e, 0xc3, 0x13, 0x77, 0x57, 0x89, 0xa5, 0xb7, 0xa7, 0xf5, 0x04, 0xbb, 0xf3, 0xd2, 0x28, 0xf4, 0x43, 0xe3, 0xca, 0x4d, 0x62, 0xb5, 0x9a, 0xca, 0x84, 0xe9, 0x90, 0xca, 0xca, 0xf5, 0xc5, 0x2b, 0x09, 0x30, 0xda, 0xa2, 0x3d, 0xe9, 0x4c, 0xe8, 0x70, 0x17, 0xba, 0x2d, 0x84, 0x98, 0x8d, 0xdf, 0xc9, 0xc5, 0x8d, 0xb6, 0x7a, 0xad, 0xa6, 0x13, 0xc2, 0xdd, 0x08, 0x45, 0x79, 0x41, 0xa6];
+
+
+pub fn test_aes() {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut aes = Aes::new(peripherals.AES);
+
+    for (key, ecb_ciphertext, cbc_ciphertext, ctr_ciphertext, name) in [
+        (key_128, ecb_128, cbc_128, ctr_128, "aes128"),
+        (key_256, ecb_256, cbc_256, ctr_256, "aes256"),
+    ] {
+        let mut cipher = Esp32C3Aes::new(&mut aes, key).unwrap();
+        if ! test_modes(&mut cipher, ecb_ciphertext, cbc_ciphertext, ctr_ciphertext) {
+            log::error!("{} test on the aes peripheral failed", name);
+        } else {
+            log::info!("{} test on the aes peripheral succeded", name);
+        }
+
+        let mut cipher = SoftAes::new(key).unwrap();
+        if ! test_modes(&mut cipher, ecb_ciphertext, cbc_ciphertext, ctr_ciphertext) {
+            log::error!("{} test in software failed", name);
+        } else {
+            log::info!("{} test in software succeded", name);
+        }
+    }
+
+    let mut cipher = Esp32C3Aes::new(&mut aes, key_128).unwrap();
+    if ! test_cbc_padding(&mut cipher) {
+        log::error!("cbc padding test failed");
+    } else {
+        log::info!("cbc padding test succeded");
+    }
+    drop(cipher);
+
+    if ! test_key_cleared(&mut aes) {
+        log::error!("Clearing the key registers on drop failed");
+    } else {
+        log::info!("Clearing the key registers on drop succeded");
+    }
+
+    if ! test_invalid_lengths(&mut aes) {
+        log::error!("Error checks for invalid lengths failed!");
+    } else {
+        log::info!("Error checks for invalid lengths succeeded!");
+    }
+}
+
+/// Encrypts and decrypts the plaintext in place in every mode.
+fn test_modes<C: BlockCipher>(cipher: &mut C, ecb_ciphertext: &[u8], cbc_ciphertext: &[u8], ctr_ciphertext: &[u8]) -> bool {
+    let mut buffer = [0u8; 64];
+
+    buffer.copy_from_slice(plaintext);
+    ecb::encrypt(cipher, &mut buffer).unwrap();
+    if buffer != ecb_ciphertext {
+        log::error!("ECB ciphertext does not match");
+        return false;
+    }
+    ecb::decrypt(cipher, &mut buffer).unwrap();
+    if buffer != plaintext {
+        log::error!("ECB decryption does not match");
+        return false;
+    }
+
+    cbc::encrypt_blocks(cipher, &cbc_iv, &mut buffer).unwrap();
+    if buffer != cbc_ciphertext {
+        log::error!("CBC ciphertext does not match");
+        return false;
+    }
+    cbc::decrypt_blocks(cipher, &cbc_iv, &mut buffer).unwrap();
+    if buffer != plaintext {
+        log::error!("CBC decryption does not match");
+        return false;
+    }
+
+    // The counter continues across calls
+    let mut counter = ctr_initial_counter;
+    ctr::apply_keystream(cipher, &mut counter, &mut buffer[..32]);
+    ctr::apply_keystream(cipher, &mut counter, &mut buffer[32..]);
+    if buffer != ctr_ciphertext {
+        log::error!("CTR ciphertext does not match");
+        return false;
+    }
+    let mut counter = ctr_initial_counter;
+    ctr::apply_keystream(cipher, &mut counter, &mut buffer[..61]);
+    if buffer[..61] != plaintext[..61] {
+        log::error!("CTR decryption of a partial block does not match");
+        return false;
+    }
+
+    true
+}
+
+fn test_cbc_padding<C: BlockCipher>(cipher: &mut C) -> bool {
+    let mut buffer = [0u8; 80];
+
+    // The padding is a full block if the message fills the last block
+    for message_len in [0, 1, 15, 16, 17, 64] {
+        buffer[..message_len].copy_from_slice(&plaintext[..message_len]);
+        let ciphertext_len = match cbc::encrypt(cipher, &cbc_iv, &mut buffer, message_len) {
+            Ok(c) => c.len(),
+            Err(e) => {
+                log::error!("Failed to encrypt {} bytes with error: {:?}", message_len, e);
+                return false;
+            }
+        };
+        if ciphertext_len != (message_len / 16 + 1) * 16 {
+            log::error!("Wrong ciphertext length {} for {} bytes", ciphertext_len, message_len);
+            return false;
+        }
+
+        match cbc::decrypt(cipher, &cbc_iv, &mut buffer[..ciphertext_len]) {
+            Ok(m) if m == &plaintext[..message_len] => {},
+            r => {
+                log::error!("Decryption of {} bytes returned: {:?}", message_len, r);
+                return false;
+            }
+        }
+    }
+
+    // A modified last block breaks the padding
+    let ciphertext_len = cbc::encrypt(cipher, &cbc_iv, &mut buffer, 20).unwrap().len();
+    buffer[ciphertext_len - 1] ^= 1;
+    match cbc::decrypt(cipher, &cbc_iv, &mut buffer[..ciphertext_len]) {
+        Err(Error::Decryption) => {},
+        r => {
+            log::error!("Decryption with invalid padding should fail with Error::Decryption, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    // No room for the padding
+    match cbc::encrypt(cipher, &cbc_iv, &mut buffer[..64], 64) {
+        Err(Error::BufferTooSmall) => {},
+        r => {
+            log::error!("Encryption without room for padding should fail with Error::BufferTooSmall, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}
+
+/// The key stays in the peripheral after the last block until `Esp32C3Aes` is dropped.
+fn test_key_cleared(aes: &mut Aes<'_>) -> bool {
+    let mut cipher = Esp32C3Aes::new(aes, key_256).unwrap();
+    cipher.encrypt_block(&mut [0u8; 16]);
+    drop(cipher);
+
+    let registers = unsafe { &*AES::ptr() };
+    for i in 0..8 {
+        let word = registers.key(i).read().bits();
+        if word != 0 {
+            log::error!("Key register {} still holds {:#010x} after drop", i, word);
+            return false;
+        }
+    }
+
+    true
+}
+
+fn test_invalid_lengths(aes: &mut Aes<'_>) -> bool {
+    if ! matches!(Esp32C3Aes::new(aes, &key_256[..24]), Err(Error::InvalidLength)) {
+        log::error!("A 24 byte key should fail with Error::InvalidLength");
+        return false;
+    }
+    if ! matches!(SoftAes::new(&key_128[..15]), Err(Error::InvalidLength)) {
+        log::error!("A 15 byte key should fail with Error::InvalidLength");
+        return false;
+    }
+
+    let mut cipher = Esp32C3Aes::new(aes, key_128).unwrap();
+    let mut buffer = [0u8; 20];
+    if ! matches!(ecb::encrypt(&mut cipher, &mut buffer), Err(Error::InvalidBlockSize)) {
+        log::error!("ECB on a partial block should fail with Error::InvalidBlockSize");
+        return false;
+    }
+    if ! matches!(cbc::decrypt(&mut cipher, &cbc_iv, &mut buffer), Err(Error::InvalidBlockSize)) {
+        log::error!("CBC decryption of a partial block should fail with Error::InvalidBlockSize");
+        return false;
+    }
+    if ! matches!(cbc::decrypt(&mut cipher, &cbc_iv, &mut []), Err(Error::InvalidBlockSize)) {
+        log::error!("CBC decryption of an empty ciphertext should fail with Error::InvalidBlockSize");
+        return false;
+    }
+
+    true
+}
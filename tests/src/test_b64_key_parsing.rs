@@ -43,7 +43,7 @@ fn test_1024() {
 
     let mut plaintext_buffer = [0u8; RsaKeySize1024::BLOCKSIZE];
     let plaintext = rsa_private_key
-        .decrypt(&mut rsa, &padding, ciphertext, &mut plaintext_buffer)
+        .decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer)
         .expect("Should be able to decrypt with private key");
 
     for (i, &b) in plaintext.iter().enumerate() {
@@ -88,7 +88,7 @@ fn test_2048() {
 
     let mut plaintext_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
     let plaintext = rsa_private_key
-        .decrypt(&mut rsa, &padding, ciphertext, &mut plaintext_buffer)
+        .decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer)
         .expect("Should be able to decrypt with private key");
 
     for (i, &b) in plaintext.iter().enumerate() {
@@ -0,0 +1,142 @@
+use esp_32c3_crypto::{
+    aes::{AesCcm, Esp32C3Aes},
+    error::Error
+};
+use esp_hal::{aes::Aes, peripherals::Peripherals};
+
+
+// NIST SP 800-38C C.1 - C.3
+const sp800_38c_key: &[u8] = &[0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e, 0x4f];
+const example_1_nonce: &[u8] = &[0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16];
+const example_1_aad: &[u8] = &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+const example_1_plaintext: &[u8] = &[0x20, 0x21, 0x22, 0x23];
+const example_1_ciphertext: &[u8] = &[0x71, 0x62, 0x01, 0x5b, 0x4d, 0xac, 0x25, 0x5d];
+const example_2_nonce: &[u8] = &[0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17];
+const example_2_aad: &[u8] = &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+const example_2_plaintext: &[u8] = &[0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f];
+const example_2_ciphertext: &[u8] = &[0xd2, 0xa1, 0xf0, 0xe0, 0x51, 0xea, 0x5f, 0x62, 0x08, 0x1a, 0x77, 0x92, 0x07, 0x3d, 0x59, 0x3d, 0x1f, 0xc6, 0x4f, 0xbf, 0xac, 0xcd];
+const example_3_nonce: &[u8] = &[0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b];
+const example_3_aad: &[u8] = &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13];
+const example_3_plaintext: &[u8] = &[0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37];
+const example_3_ciphertext: &[u8] = &[0xe3, 0xb2, 0x01, 0xa9, 0xf5, 0xb7, 0x1a, 0x7a, 0x9b, 0x1c, 0xea, 0xec, 0xcd, 0x97, 0xe7, 0x0b, 0x61, 0x76, 0xaa, 0xd9, 0xa4, 0x42, 0x8a, 0xa5, 0x48, 0x43, 0x92, 0xfb, 0xc1, 0xb0, 0x99, 0x51];
+
+// RFC 3610 packet vector #1 with 13 byte nonce and 2 byte length field, the 4 and 16 byte tags of CCM* computed with pyca/cryptography
+const rfc3610_key: &[u8] = &[0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7, 0xc8, 0xc9, 0xca, 0xcb, 0xcc, 0xcd, 0xce, 0xcf];
+const rfc3610_nonce: &[u8] = &[0x00, 0x00, 0x00, 0x03, 0x02, 0x01, 0x00, 0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5];
+const rfc3610_aad: &[u8] = &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+const rfc3610_plaintext: &[u8] = &[0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e];
+const rfc3610_ciphertext_tag_4: &[u8] = &[0x58, 0x8c, 0x97, 0x9a, 0x61, 0xc6, 0x63, 0xd2, 0xf0, 0x66, 0xd0, 0xc2, 0xc0, 0xf9, 0x89, 0x80, 0x6d, 0x5f, 0x6b, 0x61, 0xda, 0xc3, 0x84, 0x50, 0x19, 0x8b, 0xbc];
+const rfc3610_ciphertext_tag_8: &[u8] = &[0x58, 0x8c, 0x97, 0x9a, 0x61, 0xc6, 0x63, 0xd2, 0xf0, 0x66, 0xd0, 0xc2, 0xc0, 0xf9, 0x89, 0x80, 0x6d, 0x5f, 0x6b, 0x61, 0xda, 0xc3, 0x84, 0x17, 0xe8, 0xd1, 0x2c, 0xfd, 0xf9, 0x26, 0xe0];
+const rfc3610_ciphertext_tag_16: &[u8] = &[0x58, 0x8c, 0x97, 0x9a, 0x61, 0xc6, 0x63, 0xd2, 0xf0, 0x66, 0xd0, 0xc2, 0xc0, 0xf9, 0x89, 0x80, 0x6d, 0x5f, 0x6b, 0x61, 0xda, 0xc3, 0x84, 0x50, 0x9d, 0xa6, 0x54, 0xe3, 0x2d, 0xea, 0xc3, 0x69, 0xc2, 0xda, 0xe7, 0x13, 0x3c, 0xb0, 0x8d];
+
+
+pub fn test_ccm() {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut aes = Aes::new(peripherals.AES);
+
+    for (tag_len, nonce, aad, plaintext, ciphertext, name) in [
+        (4, example_1_nonce, example_1_aad, example_1_plaintext, example_1_ciphertext, "SP 800-38C example 1"),
+        (6, example_2_nonce, example_2_aad, example_2_plaintext, example_2_ciphertext, "SP 800-38C example 2"),
+        (8, example_3_nonce, example_3_aad, example_3_plaintext, example_3_ciphertext, "SP 800-38C example 3"),
+    ] {
+        let cipher = Esp32C3Aes::new(&mut aes, sp800_38c_key).unwrap();
+        let mut ccm = AesCcm::new(cipher, tag_len, 15 - nonce.len()).unwrap();
+        if ! test_ccm_vector(&mut ccm, nonce, aad, plaintext, ciphertext) {
+            log::error!("ccm {} failed", name);
+        } else {
+            log::info!("ccm {} succeded", name);
+        }
+    }
+
+    for (tag_len, ciphertext) in [(4, rfc3610_ciphertext_tag_4), (8, rfc3610_ciphertext_tag_8), (16, rfc3610_ciphertext_tag_16)] {
+        let cipher = Esp32C3Aes::new(&mut aes, rfc3610_key).unwrap();
+        let mut ccm = AesCcm::new(cipher, tag_len, 2).unwrap();
+        if ! test_ccm_vector(&mut ccm, rfc3610_nonce, rfc3610_aad, rfc3610_plaintext, ciphertext) {
+            log::error!("ccm with {} byte tag failed", tag_len);
+        } else {
+            log::info!("ccm with {} byte tag succeded", tag_len);
+        }
+    }
+
+    if ! test_ccm_errors(&mut aes) {
+        log::error!("Error checks for ccm failed!");
+    } else {
+        log::info!("Error checks for ccm succeeded!");
+    }
+}
+
+fn test_ccm_vector(ccm: &mut AesCcm<Esp32C3Aes>, nonce: &[u8], aad: &[u8], plaintext: &[u8], expected: &[u8]) -> bool {
+    let mut ciphertext_buffer = [0u8; 64];
+    let mut plaintext_buffer = [0u8; 64];
+
+    let ciphertext = match ccm.encrypt(nonce, aad, plaintext, &mut ciphertext_buffer) {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Encryption failed with error: {:?}", e);
+            return false;
+        }
+    };
+    if ciphertext != expected {
+        log::error!("Ciphertext does not match");
+        return false;
+    }
+
+    match ccm.decrypt(nonce, aad, ciphertext, &mut plaintext_buffer) {
+        Ok(p) if p == plaintext => {},
+        r => {
+            log::error!("Decryption returned: {:?}", r);
+            return false;
+        }
+    }
+
+    // A modified ciphertext or tag is detected and the plaintext buffer is cleared
+    for position in [0, ciphertext.len() - 1] {
+        let mut modified = [0u8; 64];
+        modified[..ciphertext.len()].copy_from_slice(ciphertext);
+        modified[position] ^= 1;
+        if ! matches!(ccm.decrypt(nonce, aad, &modified[..ciphertext.len()], &mut plaintext_buffer), Err(Error::Decryption)) {
+            log::error!("Decryption with a modified byte at {} should fail with Error::Decryption", position);
+            return false;
+        }
+        if plaintext_buffer.iter().any(|b| *b != 0) {
+            log::error!("Failed decryption left plaintext in the buffer");
+            return false;
+        }
+    }
+
+    if ! matches!(ccm.decrypt(nonce, &aad[1..], ciphertext, &mut plaintext_buffer), Err(Error::Decryption)) {
+        log::error!("Decryption with modified associated data should fail with Error::Decryption");
+        return false;
+    }
+
+    true
+}
+
+fn test_ccm_errors(aes: &mut Aes<'_>) -> bool {
+    for (tag_len, length_len) in [(0, 2), (5, 2), (18, 2), (8, 1), (8, 9)] {
+        let cipher = Esp32C3Aes::new(aes, rfc3610_key).unwrap();
+        if ! matches!(AesCcm::new(cipher, tag_len, length_len), Err(Error::InvalidLength)) {
+            log::error!("Tag length {} with length field {} should fail with Error::InvalidLength", tag_len, length_len);
+            return false;
+        }
+    }
+
+    let cipher = Esp32C3Aes::new(aes, rfc3610_key).unwrap();
+    let mut ccm = AesCcm::new(cipher, 8, 2).unwrap();
+    let mut buffer = [0u8; 64];
+
+    if ! matches!(ccm.encrypt(&rfc3610_nonce[1..], rfc3610_aad, rfc3610_plaintext, &mut buffer), Err(Error::InvalidLength)) {
+        log::error!("A 12 byte nonce with a 2 byte length field should fail with Error::InvalidLength");
+        return false;
+    }
+    if ! matches!(ccm.encrypt(rfc3610_nonce, rfc3610_aad, rfc3610_plaintext, &mut buffer[..rfc3610_plaintext.len() + 7]), Err(Error::BufferTooSmall)) {
+        log::error!("Encryption without room for the tag should fail with Error::BufferTooSmall");
+        return false;
+    }
+    if ! matches!(ccm.decrypt(rfc3610_nonce, rfc3610_aad, &rfc3610_ciphertext_tag_8[..7], &mut buffer), Err(Error::Decryption)) {
+        log::error!("Decryption of input shorter than the tag should fail with Error::Decryption");
+        return false;
+    }
+
+    true
+}
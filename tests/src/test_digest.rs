@@ -0,0 +1,65 @@
+use digest::{FixedOutput, FixedOutputReset, HashMarker, Update};
+use esp_32c3_crypto::hash::sha::{Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, Hash};
+use esp_hal::peripherals::Peripherals;
+
+
+// FIPS 180-4 examples for the message "abc"
+const digest_sha1: &[u8] = &[0xa9, 0x99, 0x3e, 0x36, 0x47, 0x06, 0x81, 0x6a, 0xba, 0x3e, 0x25, 0x71, 0x78, 0x50, 0xc2, 0x6c, 0x9c, 0xd0, 0xd8, 0x9d];
+const digest_sha224: &[u8] = &[0x23, 0x09, 0x7d, 0x22, 0x34, 0x05, 0xd8, 0x22, 0x86, 0x42, 0xa4, 0x77, 0xbd, 0xa2, 0x55, 0xb3, 0x2a, 0xad, 0xbc, 0xe4, 0xbd, 0xa0, 0xb3, 0xf7, 0xe3, 0x6c, 0x9d, 0xa7];
+const digest_sha256: &[u8] = &[0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad];
+
+
+pub fn test_digest() {
+    let sha = unsafe { Peripherals::steal().SHA };
+    if ! test_digest_traits(Hash::<Esp32C3Sha1>::new(sha), digest_sha1) {
+        log::error!("digest traits test for sha1 failed");
+    } else {
+        log::info!("digest traits test for sha1 succeded");
+    }
+
+    let sha = unsafe { Peripherals::steal().SHA };
+    if ! test_digest_traits(Hash::<Esp32C3Sha224>::new(sha), digest_sha224) {
+        log::error!("digest traits test for sha224 failed");
+    } else {
+        log::info!("digest traits test for sha224 succeded");
+    }
+
+    let sha = unsafe { Peripherals::steal().SHA };
+    if ! test_digest_traits(Hash::<Esp32C3Sha256>::new(sha), digest_sha256) {
+        log::error!("digest traits test for sha256 failed");
+    } else {
+        log::info!("digest traits test for sha256 succeded");
+    }
+}
+
+/// Only uses the `digest` traits, like a crate generic over the hasher would.
+fn test_digest_traits<D: Update + FixedOutputReset + HashMarker>(mut hasher: D, expected: &[u8]) -> bool {
+    if D::output_size() != expected.len() {
+        log::error!("Wrong output size {}", D::output_size());
+        return false;
+    }
+
+    Update::update(&mut hasher, b"abc");
+    if hasher.finalize_fixed_reset().as_slice() != expected {
+        log::error!("Digest does not match");
+        return false;
+    }
+
+    // finalize_fixed_reset starts a new computation, the message is fed in chunks this time
+    let mut hasher = hasher.chain(b"a").chain(b"bc");
+    if hasher.finalize_fixed_reset().as_slice() != expected {
+        log::error!("Digest after reset does not match");
+        return false;
+    }
+
+    // Data passed before reset is discarded
+    Update::update(&mut hasher, b"discarded");
+    digest::Reset::reset(&mut hasher);
+    Update::update(&mut hasher, b"abc");
+    if FixedOutput::finalize_fixed(hasher).as_slice() != expected {
+        log::error!("Digest after explicit reset does not match");
+        return false;
+    }
+
+    true
+}
@@ -0,0 +1,161 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::context::ShaAccelerator,
+    padding::pkcs1v15::Pkcs1v15Encrypt,
+    rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey}
+};
+use esp_hal::{aes::Aes, peripherals::Peripherals, rng::Rng, rsa::Rsa};
+
+
+const password: &[u8] = b"esp32c3";
+
+const public_key_1024: &[u8] = include_bytes!("../keys/public_key_1024.der");
+const private_key_1024_encrypted: &[u8] = include_bytes!("../keys/private_key_1024_encrypted.der");
+const private_key_1024_encrypted_aes128: &[u8] = include_bytes!("../keys/private_key_1024_encrypted_aes128.der");
+const private_key_1024_encrypted_sha1: &[u8] = include_bytes!("../keys/private_key_1024_encrypted_sha1.der");
+
+const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
+const private_key_2048_encrypted: &[u8] = include_bytes!("../keys/private_key_2048_encrypted.der");
+
+
+pub fn test_encrypted_key_parsing() {
+    if ! test_encrypted_1024(public_key_1024, private_key_1024_encrypted) {
+        log::error!("Encrypted key parsing test for 1024 bit rsa key with AES-256-CBC failed");
+    } else {
+        log::info!("Encrypted key parsing test for 1024 bit rsa key with AES-256-CBC succeded");
+    }
+
+    if ! test_encrypted_1024(public_key_1024, private_key_1024_encrypted_aes128) {
+        log::error!("Encrypted key parsing test for 1024 bit rsa key with AES-128-CBC failed");
+    } else {
+        log::info!("Encrypted key parsing test for 1024 bit rsa key with AES-128-CBC succeded");
+    }
+
+    if ! test_encrypted_1024(public_key_1024, private_key_1024_encrypted_sha1) {
+        log::error!("Encrypted key parsing test for 1024 bit rsa key with PBKDF2-HMAC-SHA1 failed");
+    } else {
+        log::info!("Encrypted key parsing test for 1024 bit rsa key with PBKDF2-HMAC-SHA1 succeded");
+    }
+
+    if ! test_encrypted_2048(public_key_2048, private_key_2048_encrypted) {
+        log::error!("Encrypted key parsing test for 2048 bit rsa key with AES-256-CBC failed");
+    } else {
+        log::info!("Encrypted key parsing test for 2048 bit rsa key with AES-256-CBC succeded");
+    }
+
+    if ! test_wrong_password() {
+        log::error!("Error check for Error::Decryption with a wrong password failed!");
+    } else {
+        log::info!("Error check for Error::Decryption with a wrong password succeeded!");
+    }
+
+    if ! test_unsupported_prf() {
+        log::error!("Error check for Error::UnsupportedAlgorithm with HMAC-SHA512 failed!");
+    } else {
+        log::info!("Error check for Error::UnsupportedAlgorithm with HMAC-SHA512 succeeded!");
+    }
+}
+
+
+macro_rules! implement_encrypted_key_test {
+    ($name: ident, $key_size: ty) => {
+        fn $name(public_key_der: &[u8], encrypted_private_key_der: &[u8]) -> bool {
+            let peripherals = unsafe { Peripherals::steal() };
+            let mut rsa = Rsa::new(peripherals.RSA, None);
+            let mut rng = Rng::new(peripherals.RNG);
+            let mut sha = ShaAccelerator::new(peripherals.SHA);
+            let mut aes = Aes::new(peripherals.AES);
+
+            let rsa_public_key = match RsaPublicKey::<$key_size>::new_from_der(public_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Public Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let rsa_private_key = match RsaPrivateKey::<$key_size>::new_from_encrypted_der(
+                encrypted_private_key_der, password, &mut sha, &mut aes
+            ) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse encrypted Private Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let padding = Pkcs1v15Encrypt;
+
+            let initialtext = "hello".as_bytes();
+
+            let mut ciphertext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let ciphertext = rsa_public_key
+                .encrypt(&mut rsa, &mut rng, &padding, initialtext, &mut ciphertext_buffer)
+                .expect("Should be able to encrpyt with public key");
+
+            let mut plaintext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let plaintext = rsa_private_key
+                .decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer)
+                .expect("Should be able to decrypt with private key");
+
+            if plaintext != initialtext {
+                log::error!("initialtext and plaintext do not match: \ninitialtext: \t{:?}\nplaintext: \t{:?}", initialtext, plaintext);
+                return false;
+            }
+
+            true
+        }
+    };
+}
+
+implement_encrypted_key_test!(test_encrypted_1024, RsaKeySize1024);
+implement_encrypted_key_test!(test_encrypted_2048, RsaKeySize2048);
+
+
+fn test_wrong_password() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut sha = ShaAccelerator::new(peripherals.SHA);
+    let mut aes = Aes::new(peripherals.AES);
+
+    match RsaPrivateKey::<RsaKeySize1024>::new_from_encrypted_der(
+        private_key_1024_encrypted, b"wrong password", &mut sha, &mut aes
+    ) {
+        Err(Error::Decryption) => true,
+        r => {
+            log::error!("Parsing with a wrong password should fail with Error::Decryption, but returned: {:?}", r.err());
+            false
+        }
+    }
+}
+
+fn test_unsupported_prf() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut sha = ShaAccelerator::new(peripherals.SHA);
+    let mut aes = Aes::new(peripherals.AES);
+
+    // Turn the hmacWithSHA256 PRF (1.2.840.113549.2.9) of the key into hmacWithSHA512 (1.2.840.113549.2.11)
+    let hmac_with_sha256 = [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x09];
+    let mut encrypted_der = [0u8; 1024];
+    let encrypted_der = &mut encrypted_der[..private_key_1024_encrypted.len()];
+    encrypted_der.copy_from_slice(private_key_1024_encrypted);
+    let prf_index = match encrypted_der.windows(hmac_with_sha256.len()).position(|w| w == hmac_with_sha256) {
+        Some(i) => i,
+        None => {
+            log::error!("Encrypted key does not use hmacWithSHA256");
+            return false;
+        }
+    };
+    encrypted_der[prf_index + hmac_with_sha256.len() - 1] = 0x0b;
+
+    let sha512_oid = spki::ObjectIdentifier::new_unwrap("1.2.840.113549.2.11");
+
+    match RsaPrivateKey::<RsaKeySize1024>::new_from_encrypted_der(
+        encrypted_der, password, &mut sha, &mut aes
+    ) {
+        Err(Error::UnsupportedAlgorithm(oid)) if oid == sha512_oid => true,
+        r => {
+            log::error!("Parsing a key encrypted with HMAC-SHA512 should fail with Error::UnsupportedAlgorithm, but returned: {:?}", r.err());
+            false
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use core::str;
 
-use esp_32c3_crypto::{error::Error, hash::sha::{Esp32C3Sha256, Hash}, padding::pkcs1v15::Pkcs1v15Encrypt, rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey}};
+use esp_32c3_crypto::{error::Error, hash::sha::{Esp32C3Sha256, Hash}, padding::pkcs1v15::Pkcs1v15Encrypt, rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaKeySize3072, RsaKeySize4096, RsaPrivateKey, RsaPublicKey}};
 use esp_hal::{peripherals::Peripherals, rng::Rng, rsa::Rsa};
 
 
@@ -22,6 +22,18 @@ pub fn test_encryption() {
         log::info!("Encryption test for 2048 bit rsa key succeded");
     };
 
+    if ! test_3072() {
+        log::error!("Encryption test for 3072 bit rsa key failed");
+    } else {
+        log::info!("Encryption test for 3072 bit rsa key succeded");
+    };
+
+    if ! test_4096() {
+        log::error!("Encryption test for 4096 bit rsa key failed");
+    } else {
+        log::info!("Encryption test for 4096 bit rsa key succeded");
+    };
+
     if ! test_1024_enc_buffer_to_small() {
         log::error!("Error check for Error::BufferToSmall on encrytpion failed!");
     } else {
@@ -78,7 +90,7 @@ fn test_1024() -> bool {
 
     // Decrypt the encrypted file
     let mut plaintext_buffer = [0u8; RsaKeySize1024::BLOCKSIZE];
-    let plaintext = rsa_private_key.decrypt(&mut rsa, &padding, ciphertext, &mut plaintext_buffer);
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer);
     let plaintext = match plaintext {
         Ok(c) => c,
         Err(e) => {
@@ -97,7 +109,7 @@ fn test_1024() -> bool {
 
     // Decrypt the openssl encrypted file
     let mut plaintext_buffer = [0u8; RsaKeySize1024::BLOCKSIZE];
-    let plaintext = rsa_private_key.decrypt(&mut rsa, &padding, enc_1024_test_file, &mut plaintext_buffer);
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, enc_1024_test_file, &mut plaintext_buffer);
     let plaintext = match plaintext {
         Ok(c) => c,
         Err(e) => {
@@ -161,7 +173,7 @@ fn test_2048() -> bool {
 
     // Decrypt the plain text
     let mut plaintext_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
-    let plaintext = rsa_private_key.decrypt(&mut rsa, &padding, ciphertext, &mut plaintext_buffer);
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer);
     let plaintext = match plaintext {
         Ok(c) => c,
         Err(e) => {
@@ -180,7 +192,175 @@ fn test_2048() -> bool {
 
     // Decrypt the openssl encrypted file
     let mut plaintext_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
-    let plaintext = rsa_private_key.decrypt(&mut rsa, &padding, enc_2048_test_file, &mut plaintext_buffer);
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, enc_2048_test_file, &mut plaintext_buffer);
+    let plaintext = match plaintext {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Decryption failed with error: {:?}", e);
+            return false;
+        }
+    };
+
+    // Compare test file and openssl decrypted plaintext
+    for (i, &b) in plaintext.iter().enumerate() {
+        if b != test_file[i] {
+            log::error!("Openssl decrypted plaintext does not equal plaintext at position {i}: \nplaintext:\t\t{:?}\ninitial_plaintext:\t\t{:?}", plaintext, test_file);
+            return false;
+        }
+    }
+
+    true
+
+}
+
+
+const public_key_3072: &[u8] = include_bytes!("../keys/public_key_3072.der");
+const private_key_3072: &[u8] = include_bytes!("../keys/private_key_3072.der");
+const enc_3072_test_file: &[u8] = include_bytes!("../encryptions/test_file.enc_3072");
+
+fn test_3072() -> bool {
+
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+
+    let mut rsa = peripherals.RSA;
+    let mut rsa = Rsa::new(rsa, None);
+
+    // Parse Pub key
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize3072>::new_from_der(public_key_3072);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 3072 Byte Public Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_public_key = rsa_public_key.unwrap();
+
+    // Parse Priv key
+    let rsa_private_key= RsaPrivateKey::<RsaKeySize3072>::new_from_der(private_key_3072);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to Parse 3072 Byte Private Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_private_key= rsa_private_key.unwrap();
+
+    let padding = Pkcs1v15Encrypt;
+
+    // Encrypt the test file
+    let mut ciphertext_buffer = [0u8; RsaKeySize3072::BLOCKSIZE];
+    let ciphertext = rsa_public_key.encrypt(&mut rsa, &mut rng, &padding, &test_file, &mut ciphertext_buffer);
+    let ciphertext = match ciphertext {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Encryption failed with error: {:?}", e);
+            return false;
+        }
+    };
+
+    // Decrypt the plain text
+    let mut plaintext_buffer = [0u8; RsaKeySize3072::BLOCKSIZE];
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer);
+    let plaintext = match plaintext {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Decryption failed with error: {:?}", e);
+            return false;
+        }
+    };
+
+    // Compare test file and decrypted plaintext
+    for (i, &b) in plaintext.iter().enumerate() {
+        if b != test_file[i] {
+            log::error!("Decrypted plaintext does not equal plaintext at position {i}: \nplaintext:\t\t{:?}\ninitial_plaintext:\t\t{:?}", plaintext, test_file);
+            return false;
+        }
+    }
+
+    // Decrypt the openssl encrypted file
+    let mut plaintext_buffer = [0u8; RsaKeySize3072::BLOCKSIZE];
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, enc_3072_test_file, &mut plaintext_buffer);
+    let plaintext = match plaintext {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Decryption failed with error: {:?}", e);
+            return false;
+        }
+    };
+
+    // Compare test file and openssl decrypted plaintext
+    for (i, &b) in plaintext.iter().enumerate() {
+        if b != test_file[i] {
+            log::error!("Openssl decrypted plaintext does not equal plaintext at position {i}: \nplaintext:\t\t{:?}\ninitial_plaintext:\t\t{:?}", plaintext, test_file);
+            return false;
+        }
+    }
+
+    true
+
+}
+
+
+const public_key_4096: &[u8] = include_bytes!("../keys/public_key_4096.der");
+const private_key_4096: &[u8] = include_bytes!("../keys/private_key_4096.der");
+const enc_4096_test_file: &[u8] = include_bytes!("../encryptions/test_file.enc_4096");
+
+fn test_4096() -> bool {
+
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+
+    let mut rsa = peripherals.RSA;
+    let mut rsa = Rsa::new(rsa, None);
+
+    // Parse Pub key
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize4096>::new_from_der(public_key_4096);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 4096 Byte Public Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_public_key = rsa_public_key.unwrap();
+
+    // Parse Priv key
+    let rsa_private_key= RsaPrivateKey::<RsaKeySize4096>::new_from_der(private_key_4096);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to Parse 4096 Byte Private Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_private_key= rsa_private_key.unwrap();
+
+    let padding = Pkcs1v15Encrypt;
+
+    // Encrypt the test file
+    let mut ciphertext_buffer = [0u8; RsaKeySize4096::BLOCKSIZE];
+    let ciphertext = rsa_public_key.encrypt(&mut rsa, &mut rng, &padding, &test_file, &mut ciphertext_buffer);
+    let ciphertext = match ciphertext {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Encryption failed with error: {:?}", e);
+            return false;
+        }
+    };
+
+    // Decrypt the plain text
+    let mut plaintext_buffer = [0u8; RsaKeySize4096::BLOCKSIZE];
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer);
+    let plaintext = match plaintext {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Decryption failed with error: {:?}", e);
+            return false;
+        }
+    };
+
+    // Compare test file and decrypted plaintext
+    for (i, &b) in plaintext.iter().enumerate() {
+        if b != test_file[i] {
+            log::error!("Decrypted plaintext does not equal plaintext at position {i}: \nplaintext:\t\t{:?}\ninitial_plaintext:\t\t{:?}", plaintext, test_file);
+            return false;
+        }
+    }
+
+    // Decrypt the openssl encrypted file
+    let mut plaintext_buffer = [0u8; RsaKeySize4096::BLOCKSIZE];
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, enc_4096_test_file, &mut plaintext_buffer);
     let plaintext = match plaintext {
         Ok(c) => c,
         Err(e) => {
@@ -279,7 +459,7 @@ fn test_1024_dec_buffer_to_small() -> bool {
 
     // Decrypt the ciphertext -> should fail with Error::BufferToSmall
     let mut plaintext_buffer = [0u8; 1];
-    let plaintext = rsa_private_key.decrypt(&mut rsa,  &padding, ciphertext, &mut plaintext_buffer);
+    let plaintext = rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer);
 
     // Check if the correct error was thrown
     match plaintext {
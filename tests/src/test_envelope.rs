@@ -0,0 +1,187 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::sha::{Esp32C3Sha256, Hash},
+    padding::{oaep::Oaep, pkcs1v15::Pkcs1v15Encrypt},
+    rsa::{envelope::ENVELOPE_OVERHEAD, RsaKey, RsaKeySize2048, RsaPrivateKey, RsaPublicKey},
+    traits::PaddingScheme
+};
+use esp_hal::{aes::Aes, peripherals::Peripherals, rng::Rng, rsa::Rsa};
+
+
+const test_file: &[u8] = include_bytes!("../test_file.txt");
+
+const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
+const private_key_2048: &[u8] = include_bytes!("../keys/private_key_2048.der");
+
+/// Created with encryptions/create_envelope.py
+const envelope_oaep_2048_sha256: &[u8] = include_bytes!("../encryptions/test_file.envelope_oaep_2048_sha256");
+const envelope_pkcs1v15_2048_bad_padding: &[u8] = include_bytes!("../encryptions/test_file.envelope_pkcs1v15_2048_bad_padding");
+const envelope_pkcs1v15_2048_short_key: &[u8] = include_bytes!("../encryptions/test_file.envelope_pkcs1v15_2048_short_key");
+
+/// More than fits into a single RSA block.
+const payload_len: usize = 1000;
+const envelope_len: usize = ENVELOPE_OVERHEAD + RsaKeySize2048::BLOCKSIZE + payload_len;
+
+
+pub fn test_envelope() {
+    let sha = unsafe { Peripherals::steal().SHA };
+    if ! test_envelope_roundtrip(&Oaep::new(Hash::<Esp32C3Sha256>::new(sha))) {
+        log::error!("Envelope test with oaep failed");
+    } else {
+        log::info!("Envelope test with oaep succeded");
+    }
+
+    if ! test_envelope_roundtrip(&Pkcs1v15Encrypt) {
+        log::error!("Envelope test with pkcs1v15 failed");
+    } else {
+        log::info!("Envelope test with pkcs1v15 succeded");
+    }
+
+    if ! test_open_python_envelope() {
+        log::error!("Opening an envelope sealed with pyca/cryptography failed");
+    } else {
+        log::info!("Opening an envelope sealed with pyca/cryptography succeded");
+    }
+
+    if ! test_implicit_rejection(envelope_pkcs1v15_2048_bad_padding) {
+        log::error!("Error check for Error::Decryption with broken pkcs1v15 padding of the wrapped key failed!");
+    } else {
+        log::info!("Error check for Error::Decryption with broken pkcs1v15 padding of the wrapped key succeeded!");
+    }
+
+    if ! test_implicit_rejection(envelope_pkcs1v15_2048_short_key) {
+        log::error!("Error check for Error::Decryption with a 31 byte wrapped key failed!");
+    } else {
+        log::info!("Error check for Error::Decryption with a 31 byte wrapped key succeeded!");
+    }
+
+    if ! test_envelope_errors() {
+        log::error!("Error checks for envelopes failed!");
+    } else {
+        log::info!("Error checks for envelopes succeeded!");
+    }
+}
+
+fn test_envelope_roundtrip<P: PaddingScheme<RsaKeySize2048>>(padding: &P) -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+    let mut aes = Aes::new(peripherals.AES);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048).unwrap();
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+
+    let mut payload = [0u8; payload_len];
+    for (b, t) in payload.iter_mut().zip(test_file.iter().cycle()) {
+        *b = *t;
+    }
+
+    let mut envelope = [0u8; envelope_len];
+    let sealed_len = match rsa_public_key.seal_envelope(&mut rsa, &mut rng, &mut aes, padding, &payload, &mut envelope) {
+        Ok(e) => e.len(),
+        Err(e) => {
+            log::error!("Failed to seal envelope with error: {:?}", e);
+            return false;
+        }
+    };
+    if sealed_len != envelope_len {
+        log::error!("Envelope has length {} instead of {}", sealed_len, envelope_len);
+        return false;
+    }
+
+    let mut plaintext = [0u8; payload_len];
+    match rsa_private_key.open_envelope(&mut rsa, &mut rng, &mut aes, padding, &envelope, &mut plaintext) {
+        Ok(p) if p == payload => {},
+        r => {
+            log::error!("Opening the envelope returned: {:?}", r);
+            return false;
+        }
+    }
+
+    // Modified wrapped key, nonce, ciphertext and tag
+    for position in [14, 3 + RsaKeySize2048::BLOCKSIZE + 1, envelope_len - 17, envelope_len - 1] {
+        envelope[position] ^= 1;
+        if ! matches!(rsa_private_key.open_envelope(&mut rsa, &mut rng, &mut aes, padding, &envelope, &mut plaintext), Err(Error::Decryption)) {
+            log::error!("Opening an envelope with a modified byte at {} should fail with Error::Decryption", position);
+            return false;
+        }
+        envelope[position] ^= 1;
+    }
+
+    true
+}
+
+fn test_open_python_envelope() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+    let mut aes = Aes::new(peripherals.AES);
+
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+    let padding = Oaep::new(Hash::<Esp32C3Sha256>::new(peripherals.SHA));
+
+    let mut plaintext = [0u8; 64];
+    match rsa_private_key.open_envelope(&mut rsa, &mut rng, &mut aes, &padding, envelope_oaep_2048_sha256, &mut plaintext) {
+        Ok(p) if p == test_file => true,
+        r => {
+            log::error!("Opening the envelope returned: {:?}", r);
+            false
+        }
+    }
+}
+
+/// A wrapped key that does not unwrap to an AES-256 key fails at the tag check like a modified envelope.
+fn test_implicit_rejection(envelope: &[u8]) -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+    let mut aes = Aes::new(peripherals.AES);
+
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+
+    let mut plaintext = [0u8; 64];
+    match rsa_private_key.open_envelope(&mut rsa, &mut rng, &mut aes, &Pkcs1v15Encrypt, envelope, &mut plaintext) {
+        Err(Error::Decryption) => true,
+        r => {
+            log::error!("Opening the envelope should fail with Error::Decryption, but returned: {:?}", r);
+            false
+        }
+    }
+}
+
+fn test_envelope_errors() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+    let mut aes = Aes::new(peripherals.AES);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048).unwrap();
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+    let padding = Pkcs1v15Encrypt;
+
+    let mut envelope = [0u8; envelope_len];
+    if ! matches!(rsa_public_key.seal_envelope(&mut rsa, &mut rng, &mut aes, &padding, &[0u8; payload_len], &mut envelope[..envelope_len - 1]), Err(Error::BufferTooSmall)) {
+        log::error!("Sealing to a too small buffer should fail with Error::BufferTooSmall");
+        return false;
+    }
+
+    let sealed_len = rsa_public_key.seal_envelope(&mut rsa, &mut rng, &mut aes, &padding, test_file, &mut envelope).unwrap().len();
+    let mut plaintext = [0u8; 64];
+
+    if ! matches!(rsa_private_key.open_envelope(&mut rsa, &mut rng, &mut aes, &padding, &envelope[..sealed_len - 1], &mut plaintext), Err(Error::InvalidEncoding)) {
+        log::error!("Opening a truncated envelope should fail with Error::InvalidEncoding");
+        return false;
+    }
+    if ! matches!(rsa_private_key.open_envelope(&mut rsa, &mut rng, &mut aes, &padding, &envelope[..sealed_len], &mut plaintext[..test_file.len() - 1]), Err(Error::BufferTooSmall)) {
+        log::error!("Opening to a too small buffer should fail with Error::BufferTooSmall");
+        return false;
+    }
+
+    envelope[0] = 2;
+    if ! matches!(rsa_private_key.open_envelope(&mut rsa, &mut rng, &mut aes, &padding, &envelope[..sealed_len], &mut plaintext), Err(Error::InvalidEncoding)) {
+        log::error!("Opening an envelope with an unknown version should fail with Error::InvalidEncoding");
+        return false;
+    }
+
+    true
+}
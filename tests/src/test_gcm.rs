@@ -0,0 +1,130 @@
+use esp_32c3_crypto::{
+    aes::{gcm::TAG_LEN, AesGcm, Esp32C3Aes},
+    error::Error
+};
+use esp_hal::{aes::Aes, peripherals::Peripherals};
+
+
+// Test cases 3, 4, 15 and 16 of the GCM specification (McGrew, Viega), also used in NIST CAVP
+const key_128: &[u8] = &[0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08];
+const key_256: &[u8] = &[0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08, 0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08];
+const nonce: [u8; 12] = [0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88];
+const plaintext: &[u8] = &[0xd9, 0x31, 0x32, 0x25, 0xf8, 0x84, 0x06, 0xe5, 0xa5, 0x59, 0x09, 0xc5, 0xaf, 0xf5, 0x26, 0x9a, 0x86, 0xa7, 0xa9, 0x53, 0x15, 0x34, 0xf7, 0xda, 0x2e, 0x4c, 0x30, 0x3d, 0x8a, 0x31, 0x8a, 0x72, 0x1c, 0x3c, 0x0c, 0x95, 0x95, 0x68, 0x09, 0x53, 0x2f, 0xcf, 0x0e, 0x24, 0x49, 0xa6, 0xb5, 0x25, 0xb1, 0x6a, 0xed, 0xf5, 0xaa, 0x0d, 0xe6, 0x57, 0xba, 0x63, 0x7b, 0x39, 0x1a, 0xaf, 0xd2, 0x55];
+const aad: &[u8] = &[0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef, 0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef, 0xab, 0xad, 0xda, 0xd2];
+
+const ciphertext_3: &[u8] = &[0x42, 0x83, 0x1e, 0xc2, 0x21, 0x77, 0x74, 0x24, 0x4b, 0x72, 0x21, 0xb7, 0x84, 0xd0, 0xd4, 0x9c, 0xe3, 0xaa, 0x21, 0x2f, 0x2c, 0x02, 0xa4, 0xe0, 0x35, 0xc1, 0x7e, 0x23, 0x29, 0xac, 0xa1, 0x2e, 0x21, 0xd5, 0x14, 0xb2, 0x54, 0x66, 0x93, 0x1c, 0x7d, 0x8f, 0x6a, 0x5a, 0xac, 0x84, 0xaa, 0x05, 0x1b, 0xa3, 0x0b, 0x39, 0x6a, 0x0a, 0xac, 0x97, 0x3d, 0x58, 0xe0, 0x91, 0x47, 0x3f, 0x59, 0x85];
+const tag_3: &[u8] = &[0x4d, 0x5c, 0x2a, 0xf3, 0x27, 0xcd, 0x64, 0xa6, 0x2c, 0xf3, 0x5a, 0xbd, 0x2b, 0xa6, 0xfa, 0xb4];
+const ciphertext_4: &[u8] = &[0x42, 0x83, 0x1e, 0xc2, 0x21, 0x77, 0x74, 0x24, 0x4b, 0x72, 0x21, 0xb7, 0x84, 0xd0, 0xd4, 0x9c, 0xe3, 0xaa, 0x21, 0x2f, 0x2c, 0x02, 0xa4, 0xe0, 0x35, 0xc1, 0x7e, 0x23, 0x29, 0xac, 0xa1, 0x2e, 0x21, 0xd5, 0x14, 0xb2, 0x54, 0x66, 0x93, 0x1c, 0x7d, 0x8f, 0x6a, 0x5a, 0xac, 0x84, 0xaa, 0x05, 0x1b, 0xa3, 0x0b, 0x39, 0x6a, 0x0a, 0xac, 0x97, 0x3d, 0x58, 0xe0, 0x91];
+const tag_4: &[u8] = &[0x5b, 0xc9, 0x4f, 0xbc, 0x32, 0x21, 0xa5, 0xdb, 0x94, 0xfa, 0xe9, 0x5a, 0xe7, 0x12, 0x1a, 0x47];
+const ciphertext_15: &[u8] = &[0x52, 0x2d, 0xc1, 0xf0, 0x99, 0x56, 0x7d, 0x07, 0xf4, 0x7f, 0x37, 0xa3, 0x2a, 0x84, 0x42, 0x7d, 0x64, 0x3a, 0x8c, 0xdc, 0xbf, 0xe5, 0xc0, 0xc9, 0x75, 0x98, 0xa2, 0xbd, 0x25, 0x55, 0xd1, 0xaa, 0x8c, 0xb0, 0x8e, 0x48, 0x59, 0x0d, 0xbb, 0x3d, 0xa7, 0xb0, 0x8b, 0x10, 0x56, 0x82, 0x88, 0x38, 0xc5, 0xf6, 0x1e, 0x63, 0x93, 0xba, 0x7a, 0x0a, 0xbc, 0xc9, 0xf6, 0x62, 0x89, 0x80, 0x15, 0xad];
+const tag_15: &[u8] = &[0xb0, 0x94, 0xda, 0xc5, 0xd9, 0x34, 0x71, 0xbd, 0xec, 0x1a, 0x50, 0x22, 0x70, 0xe3, 0xcc, 0x6c];
+const ciphertext_16: &[u8] = &[0x52, 0x2d, 0xc1, 0xf0, 0x99, 0x56, 0x7d, 0x07, 0xf4, 0x7f, 0x37, 0xa3, 0x2a, 0x84, 0x42, 0x7d, 0x64, 0x3a, 0x8c, 0xdc, 0xbf, 0xe5, 0xc0, 0xc9, 0x75, 0x98, 0xa2, 0xbd, 0x25, 0x55, 0xd1, 0xaa, 0x8c, 0xb0, 0x8e, 0x48, 0x59, 0x0d, 0xbb, 0x3d, 0xa7, 0xb0, 0x8b, 0x10, 0x56, 0x82, 0x88, 0x38, 0xc5, 0xf6, 0x1e, 0x63, 0x93, 0xba, 0x7a, 0x0a, 0xbc, 0xc9, 0xf6, 0x62];
+const tag_16: &[u8] = &[0x76, 0xfc, 0x6e, 0xce, 0x0f, 0x4e, 0x17, 0x68, 0xcd, 0xdf, 0x88, 0x53, 0xbb, 0x2d, 0x55, 0x1b];
+
+
+pub fn test_gcm() {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut aes = Aes::new(peripherals.AES);
+
+    // Test cases 4 and 16 have associated data and a message that ends in a partial block
+    for (key, message, aad_, ciphertext, tag, name) in [
+        (key_128, plaintext, &[][..], ciphertext_3, tag_3, "aes128 test case 3"),
+        (key_128, &plaintext[..60], aad, ciphertext_4, tag_4, "aes128 test case 4"),
+        (key_256, plaintext, &[][..], ciphertext_15, tag_15, "aes256 test case 15"),
+        (key_256, &plaintext[..60], aad, ciphertext_16, tag_16, "aes256 test case 16"),
+    ] {
+        let mut gcm = AesGcm::new(Esp32C3Aes::new(&mut aes, key).unwrap());
+        if ! test_gcm_vector(&mut gcm, message, aad_, ciphertext, tag) {
+            log::error!("gcm {} failed", name);
+        } else {
+            log::info!("gcm {} succeded", name);
+        }
+    }
+
+    let mut gcm = AesGcm::new(Esp32C3Aes::new(&mut aes, key_128).unwrap());
+    if ! test_gcm_authentication(&mut gcm) {
+        log::error!("gcm authentication test failed");
+    } else {
+        log::info!("gcm authentication test succeded");
+    }
+}
+
+fn test_gcm_vector(gcm: &mut AesGcm<Esp32C3Aes>, message: &[u8], aad_: &[u8], ciphertext: &[u8], tag: &[u8]) -> bool {
+    let mut buffer = [0u8; 64 + TAG_LEN];
+
+    buffer[..message.len()].copy_from_slice(message);
+    let detached_tag = gcm.encrypt_detached(&nonce, aad_, &mut buffer[..message.len()]).unwrap();
+    if &buffer[..message.len()] != ciphertext || detached_tag != tag {
+        log::error!("Detached ciphertext or tag does not match");
+        return false;
+    }
+    if let Err(e) = gcm.decrypt_detached(&nonce, aad_, &mut buffer[..message.len()], &detached_tag) {
+        log::error!("Detached decryption failed with error: {:?}", e);
+        return false;
+    }
+    if &buffer[..message.len()] != message {
+        log::error!("Detached decryption does not match");
+        return false;
+    }
+
+    // The tag is appended to the ciphertext
+    let sealed_len = gcm.encrypt(&nonce, aad_, &mut buffer, message.len()).unwrap().len();
+    if buffer[..message.len()] != *ciphertext || buffer[message.len()..sealed_len] != *tag {
+        log::error!("Attached ciphertext or tag does not match");
+        return false;
+    }
+    match gcm.decrypt(&nonce, aad_, &mut buffer[..sealed_len]) {
+        Ok(m) if m == message => {},
+        r => {
+            log::error!("Attached decryption returned: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Any change to the ciphertext, tag, associated data or nonce is detected and leaves the buffer unchanged.
+fn test_gcm_authentication(gcm: &mut AesGcm<Esp32C3Aes>) -> bool {
+    let mut buffer = [0u8; 60 + TAG_LEN];
+    buffer[..60].copy_from_slice(&plaintext[..60]);
+    let sealed_len = gcm.encrypt(&nonce, aad, &mut buffer, 60).unwrap().len();
+    let sealed = buffer;
+
+    let mut other_nonce = nonce;
+    other_nonce[11] ^= 1;
+    for (i, (n, a)) in [(nonce, &aad[1..]), (other_nonce, aad)].into_iter().enumerate() {
+        match gcm.decrypt(&n, a, &mut buffer[..sealed_len]) {
+            Err(Error::Decryption) => {},
+            r => {
+                log::error!("Decryption with modified input {} should fail with Error::Decryption, but returned: {:?}", i, r);
+                return false;
+            }
+        }
+    }
+
+    for position in [0, 59, 60, sealed_len - 1] {
+        buffer[position] ^= 0x10;
+        let modified = buffer;
+        if ! matches!(gcm.decrypt(&nonce, aad, &mut buffer[..sealed_len]), Err(Error::Decryption)) {
+            log::error!("Decryption with a modified byte at {} should fail with Error::Decryption", position);
+            return false;
+        }
+        if buffer != modified {
+            log::error!("Failed decryption changed the buffer");
+            return false;
+        }
+        buffer = sealed;
+    }
+
+    if ! matches!(gcm.decrypt(&nonce, aad, &mut buffer[..TAG_LEN - 1]), Err(Error::Decryption)) {
+        log::error!("Decryption of input shorter than the tag should fail with Error::Decryption");
+        return false;
+    }
+    if ! matches!(gcm.encrypt(&nonce, aad, &mut buffer[..60 + TAG_LEN - 1], 60), Err(Error::BufferTooSmall)) {
+        log::error!("Encryption without room for the tag should fail with Error::BufferTooSmall");
+        return false;
+    }
+
+    true
+}
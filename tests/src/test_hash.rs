@@ -1,4 +1,8 @@
-use esp_32c3_crypto::hash::sha::{Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, Hash, HashAlgorithm};
+use esp_32c3_crypto::error::Error;
+use esp_32c3_crypto::hash::{
+    context::{HashContext, ShaAccelerator},
+    sha::{Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, Hash, HashFunction, HashMode, Sha384, Sha512}
+};
 use esp_hal::{peripherals::Peripherals, sha::ShaMode};
 use log::{error, info};
 
@@ -29,10 +33,42 @@ pub fn test_hash() {
         },
     }
 
-    log::warn!("{error_count} of 3 hash algortihm tests failed.");
+    match test_sha256_streaming() {
+        Ok(_) => info!("SHA256 streaming tests passed"),
+        Err(e) => {
+            error!("SHA256 streaming test failed with: {e}");
+            error_count += 1;
+        },
+    }
+
+    match test_interleaved_contexts() {
+        Ok(_) => info!("Interleaved hash context tests passed"),
+        Err(e) => {
+            error!("Interleaved hash context test failed with: {e}");
+            error_count += 1;
+        },
+    }
+
+    match test_sha384() {
+        Ok(_) => info!("SHA384 tests passed"),
+        Err(e) => {
+            error!("SHA384 test failed with: {e}");
+            error_count += 1;
+        },
+    }
+
+    match test_sha512() {
+        Ok(_) => info!("SHA512 tests passed"),
+        Err(e) => {
+            error!("SHA512 test failed with: {e}");
+            error_count += 1;
+        },
+    }
+
+    log::warn!("{error_count} of 7 hash algortihm tests failed.");
 }
 
-fn test_hash_function<T: HashAlgorithm>(data: &[u8], out: &mut [u8], expected: &[u8], hash: &mut Hash<T>) -> Result<(), &'static str> {
+fn test_hash_function<T: HashFunction>(data: &[u8], out: &mut [u8], expected: &[u8], hash: &mut Hash<T>) -> Result<(), &'static str> {
     if let Ok(result) = hash.hash(data, out) {
         if result != expected {
             error!("The result ({:?}) does not equal the expected_result ({:?})", result, expected);
@@ -172,4 +208,248 @@ fn test_sha256() -> Result<(), &'static str>{
     )?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+
+fn test_sha256_streaming() -> Result<(), &'static str>{
+    let sha = unsafe { Peripherals::steal().SHA };
+
+    let mut hash = Hash::<Esp32C3Sha256>::new(sha);
+
+    let expected = [0x42, 0x49, 0x2d, 0xa0, 0x62, 0x34, 0xad, 0x0a, 0xc7, 0x6f, 0x5d, 0x5d, 0xeb, 0xdb, 0x6d, 0x1a, 0xe0, 0x27, 0xcf, 0xfb, 0xe7, 0x46, 0xa1, 0xc1, 0x3b, 0x89, 0xbb, 0x8b, 0xc0, 0x13, 0x91, 0x37];
+
+    if ! matches!(hash.finalize_into(&mut [0; 31]), Err(Error::BufferTooSmall)) {
+        return Err("Should error with Error::BufferTooSmall for an undersized out");
+    }
+
+    // The message split into chunks hashes to the same digest
+    let mut out = [0; 32];
+    hash.update("aaaaaaa".as_bytes());
+    hash.update("".as_bytes());
+    hash.update("aaaaaaaaaaaaa".as_bytes());
+    if hash.finalize_into(&mut out).ok() != Some(&expected[..]) {
+        return Err("Digest of chunked message does not match");
+    }
+
+    // Chunks of different sizes crossing the 64 byte block boundaries
+    let mut message = [0u8; 300];
+    for (i, b) in message.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+
+    let mut one_shot = [0; 32];
+    hash.hash(&message, &mut one_shot).map_err(|_| "Error while hashing")?;
+
+    for chunk_size in [1, 3, 63, 64, 65, 128] {
+        for chunk in message.chunks(chunk_size) {
+            hash.update(chunk);
+        }
+
+        if hash.finalize_into(&mut out).ok() != Some(&one_shot[..]) {
+            error!("Digest with chunk size {} does not match the one shot digest", chunk_size);
+            return Err("Digest of chunked message does not match");
+        }
+    }
+
+    // reset discards the data passed so far
+    hash.update(&message[..100]);
+    hash.reset();
+    hash.update(&message);
+    if hash.finalize_into(&mut out).ok() != Some(&one_shot[..]) {
+        return Err("Digest after reset does not match");
+    }
+
+    // One shot hashing is not affected by a previous update
+    hash.update(&message[..100]);
+    test_hash_function(
+        "aaaaaaaaaaaaaaaaaaaa".as_bytes(),
+        &mut [0; 32],
+        &expected,
+        &mut hash
+    )?;
+
+    Ok(())
+}
+
+
+fn test_interleaved_contexts() -> Result<(), &'static str>{
+    let sha = unsafe { Peripherals::steal().SHA };
+
+    let mut accelerator = ShaAccelerator::new(sha);
+
+    let expected_sha1 = [0x38, 0x66, 0x6b, 0x8b, 0xa5, 0x00, 0xfa, 0xa5, 0xc2, 0x40, 0x6f, 0x45, 0x75, 0xd4, 0x2a, 0x92, 0x37, 0x98, 0x44, 0xc2];
+    let expected_sha224 = [0x43, 0x58, 0x6e, 0xff, 0x52, 0xcb, 0xaf, 0x9f, 0x22, 0x48, 0x2f, 0x34, 0xa9, 0x43, 0x7f, 0xf4, 0x5b, 0xd2, 0xe7, 0x31, 0x2a, 0xd5, 0x86, 0xb3, 0xdd, 0x82, 0x80, 0x2f];
+    let expected_sha256 = [0x42, 0x49, 0x2d, 0xa0, 0x62, 0x34, 0xad, 0x0a, 0xc7, 0x6f, 0x5d, 0x5d, 0xeb, 0xdb, 0x6d, 0x1a, 0xe0, 0x27, 0xcf, 0xfb, 0xe7, 0x46, 0xa1, 0xc1, 0x3b, 0x89, 0xbb, 0x8b, 0xc0, 0x13, 0x91, 0x37];
+
+    // Three digests of different algorithms fed byte by byte in turns
+    let mut sha1 = HashContext::<Esp32C3Sha1>::new();
+    let mut sha224 = HashContext::<Esp32C3Sha224>::new();
+    let mut sha256 = HashContext::<Esp32C3Sha256>::new();
+    for b in "aaaaaaaaaaaaaaaaaaaa".as_bytes().chunks(1) {
+        sha1.update(&mut accelerator, b);
+        sha224.update(&mut accelerator, b);
+        sha256.update(&mut accelerator, b);
+    }
+
+    let mut out = [0; 32];
+    if sha1.finalize_into(&mut accelerator, &mut out).ok() != Some(&expected_sha1[..]) {
+        return Err("SHA1 digest of interleaved context does not match");
+    }
+    if sha224.finalize_into(&mut accelerator, &mut out).ok() != Some(&expected_sha224[..]) {
+        return Err("SHA224 digest of interleaved context does not match");
+    }
+    if sha256.finalize_into(&mut accelerator, &mut out).ok() != Some(&expected_sha256[..]) {
+        return Err("SHA256 digest of interleaved context does not match");
+    }
+
+    // Long messages where the saved state has to be restored between blocks
+    let mut message = [0u8; 300];
+    for (i, b) in message.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+
+    let mut hash = Hash::<Esp32C3Sha256>::from_accelerator(accelerator);
+    let mut one_shot = [0; 32];
+    hash.hash(&message, &mut one_shot).map_err(|_| "Error while hashing")?;
+
+    // A forked context continues independently of the original one
+    let mut first = HashContext::<Esp32C3Sha256>::new();
+    first.update(hash.accelerator(), &message[..100]);
+    let mut second = first.clone();
+    for chunk in message[100..].chunks(50) {
+        first.update(hash.accelerator(), chunk);
+        hash.update(&message[..10]);
+        second.update(hash.accelerator(), chunk);
+    }
+
+    if first.finalize_into(hash.accelerator(), &mut out).ok() != Some(&one_shot[..]) {
+        return Err("Digest of forked context does not match");
+    }
+    if second.finalize_into(hash.accelerator(), &mut out).ok() != Some(&one_shot[..]) {
+        return Err("Digest of cloned context does not match");
+    }
+
+    // The Hash sharing the accelerator was not disturbed: 4 times the first 10 bytes
+    let mut expected = [0; 32];
+    let mut repeated = [0u8; 40];
+    for chunk in repeated.chunks_mut(10) {
+        chunk.copy_from_slice(&message[..10]);
+    }
+    let mut reference = HashContext::<Esp32C3Sha256>::new();
+    reference.update(hash.accelerator(), &repeated);
+    reference.finalize_into(hash.accelerator(), &mut expected).map_err(|_| "Error while hashing")?;
+
+    if hash.finalize_into(&mut out).ok() != Some(&expected[..]) {
+        return Err("Digest of Hash sharing the accelerator does not match");
+    }
+
+    Ok(())
+}
+
+
+// FIPS 180-4 example message of 112 bytes, its padding needs a second 128 byte block
+const two_block_message: &[u8] = "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu".as_bytes();
+
+fn test_sha384() -> Result<(), &'static str>{
+    let sha = unsafe { Peripherals::steal().SHA };
+
+    let mut hash = Hash::<Sha384>::new(sha);
+
+    if hash.output_len() != 48 {
+        return Err("Wrong output length")
+    }
+
+    if hash.mode() != HashMode::SHA384 {
+        return Err("Wrong hash algorithm");
+    }
+
+    if let Ok(_) = hash.hash(&[0; 1], &mut [0; 47]) {
+        return Err("Should error with undersized out");
+    }
+
+    // Test output with exact size
+    test_hash_function(
+        "abc".as_bytes(),
+        &mut [0; 48],
+        &[0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b, 0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6, 0x50, 0x07, 0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63, 0x1a, 0x8b, 0x60, 0x5a, 0x43, 0xff, 0x5b, 0xed, 0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc, 0x23, 0x58, 0xba, 0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7],
+        &mut hash
+    )?;
+
+    // Test output with bigger size
+    test_hash_function(
+        two_block_message,
+        &mut [0; 64],
+        &[0x09, 0x33, 0x0c, 0x33, 0xf7, 0x11, 0x47, 0xe8, 0x3d, 0x19, 0x2f, 0xc7, 0x82, 0xcd, 0x1b, 0x47, 0x53, 0x11, 0x1b, 0x17, 0x3b, 0x3b, 0x05, 0xd2, 0x2f, 0xa0, 0x80, 0x86, 0xe3, 0xb0, 0xf7, 0x12, 0xfc, 0xc7, 0xc7, 0x1a, 0x55, 0x7e, 0x2d, 0xb9, 0x66, 0xc3, 0xe9, 0xfa, 0x91, 0x74, 0x60, 0x39],
+        &mut hash
+    )?;
+
+    Ok(())
+}
+
+
+fn test_sha512() -> Result<(), &'static str>{
+    let sha = unsafe { Peripherals::steal().SHA };
+
+    let mut hash = Hash::<Sha512>::new(sha);
+
+    if hash.output_len() != 64 {
+        return Err("Wrong output length")
+    }
+
+    if hash.mode() != HashMode::SHA512 {
+        return Err("Wrong hash algorithm");
+    }
+
+    if let Ok(_) = hash.hash(&[0; 1], &mut [0; 63]) {
+        return Err("Should error with undersized out");
+    }
+
+    // Test output with exact size
+    test_hash_function(
+        "abc".as_bytes(),
+        &mut [0; 64],
+        &[0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae, 0x20, 0x41, 0x31, 0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, 0x0a, 0x9e, 0xee, 0xe6, 0x4b, 0x55, 0xd3, 0x9a, 0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1, 0xa8, 0x36, 0xba, 0x3c, 0x23, 0xa3, 0xfe, 0xeb, 0xbd, 0x45, 0x4d, 0x44, 0x23, 0x64, 0x3c, 0xe8, 0x0e, 0x2a, 0x9a, 0xc9, 0x4f, 0xa5, 0x4c, 0xa4, 0x9f],
+        &mut hash
+    )?;
+
+    let expected = [0x8e, 0x95, 0x9b, 0x75, 0xda, 0xe3, 0x13, 0xda, 0x8c, 0xf4, 0xf7, 0x28, 0x14, 0xfc, 0x14, 0x3f, 0x8f, 0x77, 0x79, 0xc6, 0xeb, 0x9f, 0x7f, 0xa1, 0x72, 0x99, 0xae, 0xad, 0xb6, 0x88, 0x90, 0x18, 0x50, 0x1d, 0x28, 0x9e, 0x49, 0x00, 0xf7, 0xe4, 0x33, 0x1b, 0x99, 0xde, 0xc4, 0xb5, 0x43, 0x3a, 0xc7, 0xd3, 0x29, 0xee, 0xb6, 0xdd, 0x26, 0x54, 0x5e, 0x96, 0xe5, 0x5b, 0x87, 0x4b, 0xe9, 0x09];
+
+    // Test output with bigger size
+    test_hash_function(
+        two_block_message,
+        &mut [0; 80],
+        &expected,
+        &mut hash
+    )?;
+
+    // Streaming in chunks of different sizes
+    let mut out = [0; 64];
+    for chunk_size in [1, 7, 64, 111] {
+        for chunk in two_block_message.chunks(chunk_size) {
+            hash.update(chunk);
+        }
+
+        if hash.finalize_into(&mut out).ok() != Some(&expected[..]) {
+            error!("Digest with chunk size {} does not match", chunk_size);
+            return Err("Digest of chunked message does not match");
+        }
+    }
+
+    // A software context interleaved with a context on the accelerator
+    let expected_sha256 = [0x42, 0x49, 0x2d, 0xa0, 0x62, 0x34, 0xad, 0x0a, 0xc7, 0x6f, 0x5d, 0x5d, 0xeb, 0xdb, 0x6d, 0x1a, 0xe0, 0x27, 0xcf, 0xfb, 0xe7, 0x46, 0xa1, 0xc1, 0x3b, 0x89, 0xbb, 0x8b, 0xc0, 0x13, 0x91, 0x37];
+    let mut sha256 = HashContext::<Esp32C3Sha256>::new();
+    for (chunk, a) in two_block_message.chunks(11).zip("aaaaaaaaaaaaaaaaaaaa".as_bytes().chunks(2)) {
+        hash.update(chunk);
+        sha256.update(hash.accelerator(), a);
+    }
+    hash.update(&two_block_message[110..]);
+
+    if sha256.finalize_into(hash.accelerator(), &mut out).ok() != Some(&expected_sha256[..]) {
+        return Err("SHA256 digest interleaved with SHA512 does not match");
+    }
+    if hash.finalize_into(&mut out).ok() != Some(&expected[..]) {
+        return Err("SHA512 digest interleaved with SHA256 does not match");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::sha::{Esp32C3Sha1, Esp32C3Sha256, Hash, HashAlgorithm},
+    kdf::hkdf
+};
+use esp_hal::peripherals::Peripherals;
+
+
+// RFC 5869 test cases 1, 3 and 4
+const ikm_1: &[u8] = &[0x0b; 22];
+const salt_1: &[u8] = &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c];
+const info_1: &[u8] = &[0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+const prk_1: &[u8] = &[0x07, 0x77, 0x09, 0x36, 0x2c, 0x2e, 0x32, 0xdf, 0x0d, 0xdc, 0x3f, 0x0d, 0xc4, 0x7b, 0xba, 0x63, 0x90, 0xb6, 0xc7, 0x3b, 0xb5, 0x0f, 0x9c, 0x31, 0x22, 0xec, 0x84, 0x4a, 0xd7, 0xc2, 0xb3, 0xe5];
+const okm_1: &[u8] = &[0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f, 0x64, 0xd0, 0x36, 0x2f, 0x2a, 0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a, 0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56, 0xec, 0xc4, 0xc5, 0xbf, 0x34, 0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65];
+
+const prk_3: &[u8] = &[0x19, 0xef, 0x24, 0xa3, 0x2c, 0x71, 0x7b, 0x16, 0x7f, 0x33, 0xa9, 0x1d, 0x6f, 0x64, 0x8b, 0xdf, 0x96, 0x59, 0x67, 0x76, 0xaf, 0xdb, 0x63, 0x77, 0xac, 0x43, 0x4c, 0x1c, 0x29, 0x3c, 0xcb, 0x04];
+const okm_3: &[u8] = &[0x8d, 0xa4, 0xe7, 0x75, 0xa5, 0x63, 0xc1, 0x8f, 0x71, 0x5f, 0x80, 0x2a, 0x06, 0x3c, 0x5a, 0x31, 0xb8, 0xa1, 0x1f, 0x5c, 0x5e, 0xe1, 0x87, 0x9e, 0xc3, 0x45, 0x4e, 0x5f, 0x3c, 0x73, 0x8d, 0x2d, 0x9d, 0x20, 0x13, 0x95, 0xfa, 0xa4, 0xb6, 0x1a, 0x96, 0xc8];
+
+const ikm_4: &[u8] = &[0x0b; 11];
+const prk_4: &[u8] = &[0x9b, 0x6c, 0x18, 0xc4, 0x32, 0xa7, 0xbf, 0x8f, 0x0e, 0x71, 0xc8, 0xeb, 0x88, 0xf4, 0xb3, 0x0b, 0xaa, 0x2b, 0xa2, 0x43];
+const okm_4: &[u8] = &[0x08, 0x5a, 0x01, 0xea, 0x1b, 0x10, 0xf3, 0x69, 0x33, 0x06, 0x8b, 0x56, 0xef, 0xa5, 0xad, 0x81, 0xa4, 0xf1, 0x4b, 0x82, 0x2f, 0x5b, 0x09, 0x15, 0x68, 0xa9, 0xcd, 0xd4, 0xf1, 0x55, 0xfd, 0xa2, 0xc2, 0x2e, 0x42, 0x24, 0x78, 0xd3, 0x05, 0xf3, 0xf8, 0x96];
+
+
+pub fn test_hkdf() {
+    if ! test_hkdf_vector::<Esp32C3Sha256>(salt_1, ikm_1, info_1, prk_1, okm_1) {
+        log::error!("HKDF-SHA256 test case 1 failed");
+    } else {
+        log::info!("HKDF-SHA256 test case 1 succeded");
+    }
+
+    if ! test_hkdf_vector::<Esp32C3Sha256>(&[], ikm_1, &[], prk_3, okm_3) {
+        log::error!("HKDF-SHA256 test case 3 with empty salt and info failed");
+    } else {
+        log::info!("HKDF-SHA256 test case 3 with empty salt and info succeded");
+    }
+
+    if ! test_hkdf_vector::<Esp32C3Sha1>(salt_1, ikm_4, info_1, prk_4, okm_4) {
+        log::error!("HKDF-SHA1 test case 4 failed");
+    } else {
+        log::info!("HKDF-SHA1 test case 4 succeded");
+    }
+
+    if ! test_hkdf_invalid_length() {
+        log::error!("Error check for Error::InvalidLength on HKDF expand failed!");
+    } else {
+        log::info!("Error check for Error::InvalidLength on HKDF expand succeeded!");
+    }
+}
+
+
+fn test_hkdf_vector<HA: HashAlgorithm>(salt: &[u8], ikm: &[u8], info: &[u8], expected_prk: &[u8], expected_okm: &[u8]) -> bool {
+    let mut hash = Hash::<HA>::new(unsafe { Peripherals::steal().SHA });
+
+    let mut prk_buffer = [0u8; 32];
+    let prk = match hkdf::extract(&mut hash, salt, ikm, &mut prk_buffer) {
+        Ok(prk) if prk == expected_prk => prk,
+        r => {
+            log::error!("PRK does not match the expected PRK {:?}: {:?}", expected_prk, r);
+            return false;
+        }
+    };
+
+    let mut okm = [0u8; 42];
+    if let Err(e) = hkdf::expand(&mut hash, prk, info, &mut okm) {
+        log::error!("Expand failed with error: {:?}", e);
+        return false;
+    }
+
+    if okm != expected_okm {
+        log::error!("OKM does not match the expected OKM: \nokm: \t{:?}\nexpected: \t{:?}", okm, expected_okm);
+        return false;
+    }
+
+    true
+}
+
+fn test_hkdf_invalid_length() -> bool {
+    let mut hash = Hash::<Esp32C3Sha1>::new(unsafe { Peripherals::steal().SHA });
+
+    // 255 * 20 bytes is the longest output of HKDF-SHA1
+    let mut okm = [0u8; 255 * 20 + 1];
+    match hkdf::expand(&mut hash, prk_4, info_1, &mut okm) {
+        Err(Error::InvalidLength) => {},
+        r => {
+            log::error!("Expanding more than 255 * HashLen bytes should fail with Error::InvalidLength, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    if let Err(e) = hkdf::expand(&mut hash, prk_4, info_1, &mut okm[..255 * 20]) {
+        log::error!("Expanding 255 * HashLen bytes failed with error: {:?}", e);
+        return false;
+    }
+
+    match hkdf::expand(&mut hash, &prk_4[..19], info_1, &mut okm[..20]) {
+        Err(Error::InvalidLength) => true,
+        r => {
+            log::error!("Expanding a PRK shorter than HashLen should fail with Error::InvalidLength, but returned: {:?}", r);
+            false
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::{
+        hmac::Hmac,
+        sha::{Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, Hash, HashAlgorithm}
+    }
+};
+use esp_hal::peripherals::Peripherals;
+
+
+// RFC 2202 and RFC 4231 test cases 1, 2 and the larger than block size key case
+const key_1: &[u8] = &[0x0b; 20];
+const data_1: &[u8] = b"Hi There";
+const key_2: &[u8] = b"Jefe";
+const data_2: &[u8] = b"what do ya want for nothing?";
+const key_3_sha1: &[u8] = &[0xaa; 80];
+const key_3: &[u8] = &[0xaa; 131];
+const data_3: &[u8] = b"Test Using Larger Than Block-Size Key - Hash Key First";
+
+const hmac_sha1_1: &[u8] = &[0xb6, 0x17, 0x31, 0x86, 0x55, 0x05, 0x72, 0x64, 0xe2, 0x8b, 0xc0, 0xb6, 0xfb, 0x37, 0x8c, 0x8e, 0xf1, 0x46, 0xbe, 0x00];
+const hmac_sha1_2: &[u8] = &[0xef, 0xfc, 0xdf, 0x6a, 0xe5, 0xeb, 0x2f, 0xa2, 0xd2, 0x74, 0x16, 0xd5, 0xf1, 0x84, 0xdf, 0x9c, 0x25, 0x9a, 0x7c, 0x79];
+const hmac_sha1_3: &[u8] = &[0xaa, 0x4a, 0xe5, 0xe1, 0x52, 0x72, 0xd0, 0x0e, 0x95, 0x70, 0x56, 0x37, 0xce, 0x8a, 0x3b, 0x55, 0xed, 0x40, 0x21, 0x12];
+const hmac_sha224_1: &[u8] = &[0x89, 0x6f, 0xb1, 0x12, 0x8a, 0xbb, 0xdf, 0x19, 0x68, 0x32, 0x10, 0x7c, 0xd4, 0x9d, 0xf3, 0x3f, 0x47, 0xb4, 0xb1, 0x16, 0x99, 0x12, 0xba, 0x4f, 0x53, 0x68, 0x4b, 0x22];
+const hmac_sha224_2: &[u8] = &[0xa3, 0x0e, 0x01, 0x09, 0x8b, 0xc6, 0xdb, 0xbf, 0x45, 0x69, 0x0f, 0x3a, 0x7e, 0x9e, 0x6d, 0x0f, 0x8b, 0xbe, 0xa2, 0xa3, 0x9e, 0x61, 0x48, 0x00, 0x8f, 0xd0, 0x5e, 0x44];
+const hmac_sha224_3: &[u8] = &[0x95, 0xe9, 0xa0, 0xdb, 0x96, 0x20, 0x95, 0xad, 0xae, 0xbe, 0x9b, 0x2d, 0x6f, 0x0d, 0xbc, 0xe2, 0xd4, 0x99, 0xf1, 0x12, 0xf2, 0xd2, 0xb7, 0x27, 0x3f, 0xa6, 0x87, 0x0e];
+const hmac_sha256_1: &[u8] = &[0xb0, 0x34, 0x4c, 0x61, 0xd8, 0xdb, 0x38, 0x53, 0x5c, 0xa8, 0xaf, 0xce, 0xaf, 0x0b, 0xf1, 0x2b, 0x88, 0x1d, 0xc2, 0x00, 0xc9, 0x83, 0x3d, 0xa7, 0x26, 0xe9, 0x37, 0x6c, 0x2e, 0x32, 0xcf, 0xf7];
+const hmac_sha256_2: &[u8] = &[0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95, 0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec, 0x38, 0x43];
+const hmac_sha256_3: &[u8] = &[0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, 0x0d, 0x8a, 0x26, 0xaa, 0xcb, 0xf5, 0xb7, 0x7f, 0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, 0x05, 0x46, 0x04, 0x0f, 0x0e, 0xe3, 0x7f, 0x54];
+
+
+pub fn test_hmac() {
+    if ! test_hmac_vectors::<Esp32C3Sha1>([(key_1, data_1, hmac_sha1_1), (key_2, data_2, hmac_sha1_2), (key_3_sha1, data_3, hmac_sha1_3)]) {
+        log::error!("HMAC-SHA1 test failed");
+    } else {
+        log::info!("HMAC-SHA1 test succeded");
+    }
+
+    if ! test_hmac_vectors::<Esp32C3Sha224>([(key_1, data_1, hmac_sha224_1), (key_2, data_2, hmac_sha224_2), (key_3, data_3, hmac_sha224_3)]) {
+        log::error!("HMAC-SHA224 test failed");
+    } else {
+        log::info!("HMAC-SHA224 test succeded");
+    }
+
+    if ! test_hmac_vectors::<Esp32C3Sha256>([(key_1, data_1, hmac_sha256_1), (key_2, data_2, hmac_sha256_2), (key_3, data_3, hmac_sha256_3)]) {
+        log::error!("HMAC-SHA256 test failed");
+    } else {
+        log::info!("HMAC-SHA256 test succeded");
+    }
+
+    if ! test_hmac_streaming() {
+        log::error!("Streaming HMAC test failed");
+    } else {
+        log::info!("Streaming HMAC test succeded");
+    }
+
+    if ! test_hmac_verify() {
+        log::error!("Error check for Error::Verification on HMAC verify failed!");
+    } else {
+        log::info!("Error check for Error::Verification on HMAC verify succeeded!");
+    }
+}
+
+
+fn test_hmac_vectors<HA: HashAlgorithm>(vectors: [(&[u8], &[u8], &[u8]); 3]) -> bool {
+    let mut out = [0u8; 32];
+    for (key, data, expected) in vectors {
+        let hash = Hash::<HA>::new(unsafe { Peripherals::steal().SHA });
+        let mut hmac = Hmac::new(hash, key);
+
+        match hmac.mac(data, &mut out) {
+            Ok(mac) if mac == expected => {},
+            r => {
+                log::error!("MAC does not match the expected MAC {:?}: {:?}", expected, r);
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn test_hmac_streaming() -> bool {
+    let hash = Hash::<Esp32C3Sha256>::new(unsafe { Peripherals::steal().SHA });
+    let mut hmac = Hmac::new(hash, key_2);
+
+    let mut out = [0u8; 32];
+    if let Err(Error::BufferTooSmall) = hmac.finalize_into(&mut out[..31]) {} else {
+        log::error!("finalize_into should fail with Error::BufferTooSmall for an undersized out");
+        return false;
+    }
+
+    // The message fed byte by byte, the key is reused for every MAC
+    for _ in 0..2 {
+        for b in data_2.chunks(1) {
+            hmac.update(b);
+        }
+
+        match hmac.finalize_into(&mut out) {
+            Ok(mac) if mac == hmac_sha256_2 => {},
+            r => {
+                log::error!("Streamed MAC does not match the expected MAC: {:?}", r);
+                return false;
+            }
+        }
+    }
+
+    // reset discards the data passed so far
+    hmac.update(data_1);
+    hmac.reset();
+    hmac.update(data_2);
+    match hmac.finalize_into(&mut out) {
+        Ok(mac) if mac == hmac_sha256_2 => {},
+        r => {
+            log::error!("MAC after reset does not match the expected MAC: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}
+
+fn test_hmac_verify() -> bool {
+    let hash = Hash::<Esp32C3Sha256>::new(unsafe { Peripherals::steal().SHA });
+    let mut hmac = Hmac::new(hash, key_1);
+
+    if let Err(e) = hmac.verify(data_1, hmac_sha256_1) {
+        log::error!("Verification of a valid MAC failed with error: {:?}", e);
+        return false;
+    }
+
+    hmac.update(data_1);
+    if let Err(e) = hmac.finalize_verify(hmac_sha256_1) {
+        log::error!("Verification of a valid streamed MAC failed with error: {:?}", e);
+        return false;
+    }
+
+    let mut tampered = [0u8; 32];
+    tampered.copy_from_slice(hmac_sha256_1);
+    tampered[31] ^= 1;
+
+    for tag in [&tampered[..], &hmac_sha256_1[..31], hmac_sha256_2] {
+        match hmac.verify(data_1, tag) {
+            Err(Error::Verification) => {},
+            r => {
+                log::error!("Verification of an invalid MAC should fail with Error::Verification, but returned: {:?}", r);
+                return false;
+            }
+        }
+    }
+
+    true
+}
@@ -0,0 +1,197 @@
+use esp_32c3_crypto::{
+    error::Error,
+    rsa::{RsaKeySize1024, RsaKeySize2048, RsaKeySize3072, RsaKeySize4096, RsaPrivateKey, RsaPublicKey}
+};
+
+
+const public_key_1024: &[u8] = include_bytes!("../keys/public_key_1024.der");
+const private_key_1024: &[u8] = include_bytes!("../keys/private_key_1024.der");
+const public_key_1024_pem: &str = include_str!("../keys/public_key_1024.pem");
+const public_key_1024_pkcs1_pem: &str = include_str!("../keys/public_key_1024_pkcs1.pem");
+const private_key_1024_pem: &str = include_str!("../keys/private_key_1024.pem");
+const private_key_1024_pkcs8_pem: &str = include_str!("../keys/private_key_1024_pkcs8.pem");
+
+const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
+const private_key_2048: &[u8] = include_bytes!("../keys/private_key_2048.der");
+const public_key_2048_pem: &str = include_str!("../keys/public_key_2048.pem");
+const public_key_2048_pkcs1_pem: &str = include_str!("../keys/public_key_2048_pkcs1.pem");
+const private_key_2048_pem: &str = include_str!("../keys/private_key_2048.pem");
+const private_key_2048_pkcs8_pem: &str = include_str!("../keys/private_key_2048_pkcs8.pem");
+
+const public_key_3072: &[u8] = include_bytes!("../keys/public_key_3072.der");
+const private_key_3072: &[u8] = include_bytes!("../keys/private_key_3072.der");
+const public_key_3072_pem: &str = include_str!("../keys/public_key_3072.pem");
+const public_key_3072_pkcs1_pem: &str = include_str!("../keys/public_key_3072_pkcs1.pem");
+const private_key_3072_pem: &str = include_str!("../keys/private_key_3072.pem");
+const private_key_3072_pkcs8_pem: &str = include_str!("../keys/private_key_3072_pkcs8.pem");
+
+const public_key_4096: &[u8] = include_bytes!("../keys/public_key_4096.der");
+const private_key_4096: &[u8] = include_bytes!("../keys/private_key_4096.der");
+const public_key_4096_pem: &str = include_str!("../keys/public_key_4096.pem");
+const public_key_4096_pkcs1_pem: &str = include_str!("../keys/public_key_4096_pkcs1.pem");
+const private_key_4096_pem: &str = include_str!("../keys/private_key_4096.pem");
+const private_key_4096_pkcs8_pem: &str = include_str!("../keys/private_key_4096_pkcs8.pem");
+
+
+pub fn test_key_export() {
+    if ! test_key_export_1024(
+        public_key_1024, private_key_1024, public_key_1024_pem, public_key_1024_pkcs1_pem, private_key_1024_pem, private_key_1024_pkcs8_pem
+    ) {
+        log::error!("Key export test for 1024 bit rsa key failed");
+    } else {
+        log::info!("Key export test for 1024 bit rsa key succeded");
+    }
+
+    if ! test_key_export_2048(
+        public_key_2048, private_key_2048, public_key_2048_pem, public_key_2048_pkcs1_pem, private_key_2048_pem, private_key_2048_pkcs8_pem
+    ) {
+        log::error!("Key export test for 2048 bit rsa key failed");
+    } else {
+        log::info!("Key export test for 2048 bit rsa key succeded");
+    }
+
+    if ! test_key_export_3072(
+        public_key_3072, private_key_3072, public_key_3072_pem, public_key_3072_pkcs1_pem, private_key_3072_pem, private_key_3072_pkcs8_pem
+    ) {
+        log::error!("Key export test for 3072 bit rsa key failed");
+    } else {
+        log::info!("Key export test for 3072 bit rsa key succeded");
+    }
+
+    if ! test_key_export_4096(
+        public_key_4096, private_key_4096, public_key_4096_pem, public_key_4096_pkcs1_pem, private_key_4096_pem, private_key_4096_pkcs8_pem
+    ) {
+        log::error!("Key export test for 4096 bit rsa key failed");
+    } else {
+        log::info!("Key export test for 4096 bit rsa key succeded");
+    }
+
+    if ! test_key_export_buffer_too_small() {
+        log::error!("Error check for Error::BufferTooSmall on key export failed!");
+    } else {
+        log::info!("Error check for Error::BufferTooSmall on key export succeeded!");
+    }
+}
+
+
+macro_rules! implement_key_export_test {
+    ($name: ident, $key_size: ty) => {
+        fn $name(
+            public_key_der: &[u8],
+            private_key_der: &[u8],
+            public_key_pem: &str,
+            public_key_pkcs1_pem: &str,
+            private_key_pkcs1_pem: &str,
+            private_key_pkcs8_pem: &str
+        ) -> bool {
+            let rsa_public_key = match RsaPublicKey::<$key_size>::new_from_der(public_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Public Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let rsa_private_key = match RsaPrivateKey::<$key_size>::new_from_der(private_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Private Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let mut buffer = [0u8; 4096];
+
+            // Public key der and pem have to match the openssl encodings
+            match rsa_public_key.to_public_key_der(&mut buffer) {
+                Ok(der) if der == public_key_der => {},
+                r => {
+                    log::error!("Exported public key der does not match the openssl der: {:?}", r);
+                    return false;
+                }
+            }
+
+            match rsa_public_key.to_public_key_pem(&mut buffer) {
+                Ok(pem) if pem == public_key_pem => {},
+                r => {
+                    log::error!("Exported public key pem does not match the openssl pem: {:?}", r);
+                    return false;
+                }
+            }
+
+            match rsa_public_key.to_pkcs1_pem(&mut buffer) {
+                Ok(pem) if pem == public_key_pkcs1_pem => {},
+                r => {
+                    log::error!("Exported pkcs1 public key pem does not match the openssl pem: {:?}", r);
+                    return false;
+                }
+            }
+
+            // The derived public key equals the parsed public key
+            match rsa_private_key.to_public_key().to_public_key_der(&mut buffer) {
+                Ok(der) if der == public_key_der => {},
+                r => {
+                    log::error!("Derived public key does not match the openssl public key: {:?}", r);
+                    return false;
+                }
+            }
+
+            // Private key der and pem have to match the openssl encodings
+            match rsa_private_key.to_pkcs8_der(&mut buffer) {
+                Ok(der) if der == private_key_der => {},
+                r => {
+                    log::error!("Exported private key der does not match the openssl der: {:?}", r);
+                    return false;
+                }
+            }
+
+            match rsa_private_key.to_pkcs8_pem(&mut buffer) {
+                Ok(pem) if pem == private_key_pkcs8_pem => {},
+                r => {
+                    log::error!("Exported pkcs8 private key pem does not match the openssl pem: {:?}", r);
+                    return false;
+                }
+            }
+
+            match rsa_private_key.to_pkcs1_pem(&mut buffer) {
+                Ok(pem) if pem == private_key_pkcs1_pem => {},
+                r => {
+                    log::error!("Exported pkcs1 private key pem does not match the openssl pem: {:?}", r);
+                    return false;
+                }
+            }
+
+            // The exported pkcs8 der can be parsed again
+            let der = rsa_private_key.to_pkcs8_der(&mut buffer).unwrap();
+            if let Err(e) = RsaPrivateKey::<$key_size>::new_from_der(der) {
+                log::error!("Failed to parse exported Private Key with error: {:?}", e);
+                return false;
+            }
+
+            true
+        }
+    };
+}
+
+implement_key_export_test!(test_key_export_1024, RsaKeySize1024);
+implement_key_export_test!(test_key_export_2048, RsaKeySize2048);
+implement_key_export_test!(test_key_export_3072, RsaKeySize3072);
+implement_key_export_test!(test_key_export_4096, RsaKeySize4096);
+
+
+fn test_key_export_buffer_too_small() -> bool {
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize1024>::new_from_der(private_key_1024).unwrap();
+
+    let mut buffer = [0u8; 128];
+    match rsa_private_key.to_pkcs8_der(&mut buffer) {
+        Ok(_) => {
+            log::error!("Export succeded but should have failed!");
+            false
+        },
+        Err(Error::BufferTooSmall) => true,
+        Err(e) => {
+            log::error!("Export failed with error: {:?}", e);
+            false
+        }
+    }
+}
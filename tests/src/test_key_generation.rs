@@ -0,0 +1,126 @@
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    Encoding, Uint
+};
+use esp_32c3_crypto::{
+    hash::sha::{Esp32C3Sha256, Hash, HashAlgorithm},
+    padding::pkcs1v15::Pkcs1v15Sign,
+    rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey},
+    traits::{PrivateKeyParts, PublicKeyParts, SignatureScheme}
+};
+use esp_hal::{peripherals::Peripherals, rng::Rng, rsa::Rsa};
+
+
+const test_file: &[u8] = include_bytes!("../test_file.txt");
+
+
+pub fn test_key_generation() {
+    if ! test_key_generation_1024() {
+        log::error!("Key generation test for 1024 bit rsa key failed");
+    } else {
+        log::info!("Key generation test for 1024 bit rsa key succeded");
+    }
+
+    if ! test_key_generation_2048() {
+        log::error!("Key generation test for 2048 bit rsa key failed");
+    } else {
+        log::info!("Key generation test for 2048 bit rsa key succeded");
+    }
+}
+
+
+macro_rules! implement_key_generation_test {
+    ($name: ident, $key_size: ty) => {
+        fn $name() -> bool {
+            const LIMBS: usize = <$key_size>::OperandWords;
+
+            let peripherals = unsafe { Peripherals::steal() };
+            let mut rng = Rng::new(peripherals.RNG);
+            let mut rsa = Rsa::new(peripherals.RSA, None);
+
+            let mut hash = Hash::<Esp32C3Sha256>::new(peripherals.SHA);
+
+            let rsa_private_key = match RsaPrivateKey::<$key_size>::generate(&mut rng, &mut rsa) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to generate key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let n = Uint::<LIMBS>::from_words(*rsa_private_key.n());
+            let e = Uint::<LIMBS>::from_words(*rsa_private_key.e());
+            let p = Uint::<LIMBS>::from_words(*rsa_private_key.p());
+            let q = Uint::<LIMBS>::from_words(*rsa_private_key.q());
+
+            // The modulus has the full key size and is the product of the primes
+            if n.bits() != <$key_size>::KEYSIZE || p.mul_wide(&q).0 != n {
+                log::error!("Generated modulus is not the product of two half size primes");
+                return false;
+            }
+
+            // e * dP = 1 mod (p - 1), e * dQ = 1 mod (q - 1) and q * qInv = 1 mod p
+            let p_minus_one = p.wrapping_sub(&Uint::ONE);
+            let q_minus_one = q.wrapping_sub(&Uint::ONE);
+            let dp = Uint::<LIMBS>::from_words(*rsa_private_key.dp());
+            let dq = Uint::<LIMBS>::from_words(*rsa_private_key.dq());
+            let qinv = Uint::<LIMBS>::from_words(*rsa_private_key.qinv());
+            if Uint::const_rem_wide(e.mul_wide(&dp), &p_minus_one).0 != Uint::ONE
+                || Uint::const_rem_wide(e.mul_wide(&dq), &q_minus_one).0 != Uint::ONE
+                || Uint::const_rem_wide(q.mul_wide(&qinv), &p).0 != Uint::ONE
+            {
+                log::error!("Generated crt parameters are inconsistent");
+                return false;
+            }
+
+            // e * d = 1 mod lcm(p - 1, q - 1) with d < lcm(p - 1, q - 1) (FIPS 186-4 B.3.1)
+            let (mut a, mut b) = (p_minus_one, q_minus_one);
+            while b != Uint::ZERO {
+                (a, b) = (b, a.const_rem(&b).0);
+            }
+            let lambda = p_minus_one.mul_wide(&q_minus_one).0.wrapping_div(&a);
+            let d = Uint::<LIMBS>::from_words(*rsa_private_key.d());
+            if d >= lambda || Uint::const_rem_wide(e.mul_wide(&d), &lambda).0 != Uint::ONE {
+                log::error!("Generated private exponent is not the inverse of e mod lcm(p - 1, q - 1)");
+                return false;
+            }
+
+            // |p - q| > 2^(nlen/2 - 100) (FIPS 186-4 B.3.3)
+            let distance = if p > q { p.wrapping_sub(&q) } else { q.wrapping_sub(&p) };
+            if distance <= Uint::ONE.shl_vartime(<$key_size>::KEYSIZE / 2 - 100) {
+                log::error!("Generated primes are too close to each other");
+                return false;
+            }
+
+            // Sign with the generated key and check the signature in software: s^e mod n = EM
+            let mut digest_buffer = [0u8; Esp32C3Sha256::output_len];
+            let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+            let scheme = Pkcs1v15Sign::new::<Esp32C3Sha256>();
+            let mut signature_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let signature = match scheme.sign(&rsa_private_key, rng, &mut rsa, digest, &mut signature_buffer) {
+                Ok(sig) => sig,
+                Err(e) => {
+                    log::error!("Failed to sign with generated key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let params = DynResidueParams::new(&n);
+            let em: [u8; <$key_size>::BLOCKSIZE] = DynResidue::new(&Uint::<LIMBS>::from_be_slice(signature), params)
+                .pow(&e)
+                .retrieve()
+                .to_be_bytes();
+
+            if em[0] != 0 || em[1] != 1 || &em[<$key_size>::BLOCKSIZE - digest.len()..] != digest {
+                log::error!("Signature of the generated key does not decrypt to the encoded digest: {:?}", em);
+                return false;
+            }
+
+            true
+        }
+    };
+}
+
+implement_key_generation_test!(test_key_generation_1024, RsaKeySize1024);
+implement_key_generation_test!(test_key_generation_2048, RsaKeySize2048);
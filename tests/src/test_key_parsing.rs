@@ -1,4 +1,4 @@
-use esp_32c3_crypto::rsa::{RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey};
+use esp_32c3_crypto::rsa::{RsaKeySize1024, RsaKeySize2048, RsaKeySize3072, RsaKeySize4096, RsaPrivateKey, RsaPublicKey};
 
 
 const public_key_1024: &[u8] = include_bytes!("../keys/public_key_1024.der");
@@ -7,6 +7,12 @@ const private_key_1024: &[u8] = include_bytes!("../keys/private_key_1024.der");
 const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
 const private_key_2048: &[u8] = include_bytes!("../keys/private_key_2048.der");
 
+const public_key_3072: &[u8] = include_bytes!("../keys/public_key_3072.der");
+const private_key_3072: &[u8] = include_bytes!("../keys/private_key_3072.der");
+
+const public_key_4096: &[u8] = include_bytes!("../keys/public_key_4096.der");
+const private_key_4096: &[u8] = include_bytes!("../keys/private_key_4096.der");
+
 
 pub fn test_rsa_key_parsing() {
     let mut error_count = 0;
@@ -41,5 +47,35 @@ pub fn test_rsa_key_parsing() {
     }
     log::info!("2048 Private Key parsing successfull");
 
-    log::warn!("{error_count} of 4 key parsing tests failed.");
+    // 3072
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize3072>::new_from_der(public_key_3072);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 3072 Byte Public Key with error: {:?}", e);
+            error_count += 1;
+    }
+    log::info!("3072 Public Key parsing successfull");
+
+    let rsa_private_key  = RsaPrivateKey::<RsaKeySize3072>::new_from_der(private_key_3072);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to parse 3072 Byte Private Key with error: {:?}", e);
+            error_count += 1;
+    }
+    log::info!("3072 Private Key parsing successfull");
+
+    // 4096
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize4096>::new_from_der(public_key_4096);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 4096 Byte Public Key with error: {:?}", e);
+            error_count += 1;
+    }
+    log::info!("4096 Public Key parsing successfull");
+
+    let rsa_private_key  = RsaPrivateKey::<RsaKeySize4096>::new_from_der(private_key_4096);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to parse 4096 Byte Private Key with error: {:?}", e);
+            error_count += 1;
+    }
+    log::info!("4096 Private Key parsing successfull");
+
+    log::warn!("{error_count} of 8 key parsing tests failed.");
 }
\ No newline at end of file
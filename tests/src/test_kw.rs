@@ -0,0 +1,146 @@
+use esp_32c3_crypto::{
+    aes::{kw, Esp32C3Aes},
+    error::Error
+};
+use esp_hal::{aes::Aes, peripherals::Peripherals};
+
+
+// Key wrap test vectors 4.1 and 4.6 of RFC 3394
+const kek_128: &[u8] = &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+const kek_256: &[u8] = &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f];
+const key_128: &[u8] = &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+const key_256: &[u8] = &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+const wrapped_4_1: &[u8] = &[0x1f, 0xa6, 0x8b, 0x0a, 0x81, 0x12, 0xb4, 0x47, 0xae, 0xf3, 0x4b, 0xd8, 0xfb, 0x5a, 0x7b, 0x82, 0x9d, 0x3e, 0x86, 0x23, 0x71, 0xd2, 0xcf, 0xe5];
+const wrapped_4_6: &[u8] = &[0x28, 0xc9, 0xf4, 0x04, 0xc4, 0xb8, 0x10, 0xf4, 0xcb, 0xcc, 0xb3, 0x5c, 0xfb, 0x87, 0xf8, 0x26, 0x3f, 0x57, 0x86, 0xe2, 0xd8, 0x0e, 0xd3, 0x26, 0xcb, 0xc7, 0xf0, 0xe7, 0x1a, 0x99, 0xf4, 0x3b, 0xfb, 0x98, 0x8b, 0x9b, 0x7a, 0x02, 0xdd, 0x21];
+
+// Keys of RFC 5649 6, wrapped with padding under the keys above with pyca/cryptography
+// (the key-encryption key of RFC 5649 is 192 bits, which the accelerator does not support)
+const key_20: &[u8] = &[0xc3, 0x7b, 0x7e, 0x64, 0x92, 0x58, 0x43, 0x40, 0xbe, 0xd1, 0x22, 0x07, 0x80, 0x89, 0x41, 0x15, 0x50, 0x68, 0xf7, 0x38];
+const key_7: &[u8] = &[0x46, 0x6f, 0x72, 0x50, 0x61, 0x73, 0x69];
+const wrapped_20_128: &[u8] = &[0xe1, 0xf7, 0x17, 0x6e, 0xcb, 0xd7, 0x5d, 0x42, 0xe8, 0x2b, 0x24, 0xf9, 0x89, 0xa2, 0x81, 0x6c, 0x20, 0x9c, 0x6e, 0xf2, 0xd1, 0xaa, 0x94, 0xd2, 0xa3, 0xe6, 0x02, 0x84, 0x90, 0x0d, 0x03, 0xa2];
+const wrapped_7_128: &[u8] = &[0xbe, 0x80, 0x53, 0x5e, 0x12, 0xe9, 0x39, 0x4c, 0x8f, 0x8d, 0xf2, 0x6b, 0xd9, 0x52, 0x8a, 0x35];
+const wrapped_20_256: &[u8] = &[0x29, 0xb7, 0xfa, 0x19, 0x1c, 0x21, 0x65, 0x68, 0x43, 0x74, 0xee, 0xe9, 0xf7, 0x45, 0x95, 0xe2, 0xa4, 0x2b, 0xac, 0xe7, 0x5c, 0x42, 0x5b, 0x30, 0x53, 0xef, 0xa2, 0x6f, 0xfe, 0x1b, 0xb3, 0x2f];
+const wrapped_7_256: &[u8] = &[0x44, 0x3b, 0x17, 0x83, 0x7b, 0xb3, 0x93, 0x48, 0x61, 0x0d, 0x19, 0x20, 0x2d, 0xf8, 0xa1, 0xf9];
+
+
+pub fn test_kw() {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut aes = Aes::new(peripherals.AES);
+
+    for (kek, key, wrapped, padding, name) in [
+        (kek_128, key_128, wrapped_4_1, false, "kw rfc 3394 4.1"),
+        (kek_256, key_256, wrapped_4_6, false, "kw rfc 3394 4.6"),
+        (kek_128, key_20, wrapped_20_128, true, "kwp aes128 20 byte key"),
+        (kek_128, key_7, wrapped_7_128, true, "kwp aes128 7 byte key"),
+        (kek_256, key_20, wrapped_20_256, true, "kwp aes256 20 byte key"),
+        (kek_256, key_7, wrapped_7_256, true, "kwp aes256 7 byte key"),
+    ] {
+        let mut cipher = Esp32C3Aes::new(&mut aes, kek).unwrap();
+        if ! test_kw_vector(&mut cipher, key, wrapped, padding) {
+            log::error!("{} failed", name);
+        } else {
+            log::info!("{} succeded", name);
+        }
+    }
+
+    if ! test_kw_errors(&mut Esp32C3Aes::new(&mut aes, kek_128).unwrap()) {
+        log::error!("Error checks for key wrap failed!");
+    } else {
+        log::info!("Error checks for key wrap succeeded!");
+    }
+}
+
+fn wrap<'a>(cipher: &mut Esp32C3Aes, key: &[u8], out: &'a mut [u8], padding: bool) -> Result<&'a [u8], Error> {
+    if padding {
+        kw::wrap_with_padding(cipher, key, out)
+    } else {
+        kw::wrap(cipher, key, out)
+    }
+}
+
+fn unwrap<'a>(cipher: &mut Esp32C3Aes, wrapped: &[u8], out: &'a mut [u8], padding: bool) -> Result<&'a [u8], Error> {
+    if padding {
+        kw::unwrap_with_padding(cipher, wrapped, out)
+    } else {
+        kw::unwrap(cipher, wrapped, out)
+    }
+}
+
+fn test_kw_vector(cipher: &mut Esp32C3Aes, key: &[u8], wrapped: &[u8], padding: bool) -> bool {
+    let mut out = [0u8; 40];
+    match wrap(cipher, key, &mut out, padding) {
+        Ok(w) if w == wrapped => {},
+        r => {
+            log::error!("Wrapping returned: {:?}", r);
+            return false;
+        }
+    }
+
+    let mut out = [0u8; 32];
+    match unwrap(cipher, wrapped, &mut out, padding) {
+        Ok(k) if k == key => {},
+        r => {
+            log::error!("Unwrapping returned: {:?}", r);
+            return false;
+        }
+    }
+
+    // Every modified byte has to fail the integrity check and must not leave key material in out
+    let mut modified = [0u8; 40];
+    modified[..wrapped.len()].copy_from_slice(wrapped);
+    for position in 0..wrapped.len() {
+        modified[position] ^= 1;
+        if ! matches!(unwrap(cipher, &modified[..wrapped.len()], &mut out, padding), Err(Error::KeyUnwrap)) {
+            log::error!("Unwrapping with a modified byte at {} should fail with Error::KeyUnwrap", position);
+            return false;
+        }
+        if out.iter().any(|b| *b != 0) {
+            log::error!("Failed unwrapping left data in the output buffer");
+            return false;
+        }
+        modified[position] ^= 1;
+    }
+
+    true
+}
+
+fn test_kw_errors(cipher: &mut Esp32C3Aes) -> bool {
+    let mut out = [0u8; 40];
+
+    if ! matches!(kw::wrap(cipher, &key_128[..8], &mut out), Err(Error::InvalidLength)) {
+        log::error!("Wrapping a single semiblock without padding should fail with Error::InvalidLength");
+        return false;
+    }
+    if ! matches!(kw::wrap(cipher, key_20, &mut out), Err(Error::InvalidLength)) {
+        log::error!("Wrapping a key that is not a multiple of 8 bytes without padding should fail with Error::InvalidLength");
+        return false;
+    }
+    if ! matches!(kw::wrap_with_padding(cipher, &[], &mut out), Err(Error::InvalidLength)) {
+        log::error!("Wrapping an empty key should fail with Error::InvalidLength");
+        return false;
+    }
+    if ! matches!(kw::wrap(cipher, key_128, &mut out[..key_128.len() + kw::OVERHEAD - 1]), Err(Error::BufferTooSmall)) {
+        log::error!("Wrapping to a too small buffer should fail with Error::BufferTooSmall");
+        return false;
+    }
+    if ! matches!(kw::unwrap(cipher, wrapped_4_1, &mut out[..key_128.len() - 1]), Err(Error::BufferTooSmall)) {
+        log::error!("Unwrapping to a too small buffer should fail with Error::BufferTooSmall");
+        return false;
+    }
+    if ! matches!(kw::unwrap(cipher, &wrapped_4_1[..wrapped_4_1.len() - 1], &mut out), Err(Error::InvalidLength)) {
+        log::error!("Unwrapping a truncated key should fail with Error::InvalidLength");
+        return false;
+    }
+
+    // A key wrapped with padding is no valid key wrap without padding and the other way round
+    if ! matches!(kw::unwrap(cipher, wrapped_20_128, &mut out), Err(Error::KeyUnwrap)) {
+        log::error!("Unwrapping a key wrapped with padding as kw should fail with Error::KeyUnwrap");
+        return false;
+    }
+    if ! matches!(kw::unwrap_with_padding(cipher, wrapped_4_1, &mut out), Err(Error::KeyUnwrap)) {
+        log::error!("Unwrapping a key wrapped without padding as kwp should fail with Error::KeyUnwrap");
+        return false;
+    }
+
+    true
+}
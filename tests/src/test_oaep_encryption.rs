@@ -0,0 +1,196 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::sha::{Esp32C3Sha1, Esp32C3Sha256, Hash, HashAlgorithm},
+    padding::oaep::Oaep,
+    rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey},
+    traits::PaddingScheme
+};
+use esp_hal::{peripherals::Peripherals, rng::Rng, rsa::Rsa};
+
+
+const test_file: &[u8] = include_bytes!("../test_file.txt");
+
+const public_key_1024: &[u8] = include_bytes!("../keys/public_key_1024.der");
+const private_key_1024: &[u8] = include_bytes!("../keys/private_key_1024.der");
+const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
+const private_key_2048: &[u8] = include_bytes!("../keys/private_key_2048.der");
+
+const enc_oaep_1024_sha1: &[u8] = include_bytes!("../encryptions/test_file.enc_oaep_1024_sha1");
+const enc_oaep_1024_sha256: &[u8] = include_bytes!("../encryptions/test_file.enc_oaep_1024_sha256");
+const enc_oaep_2048_sha1: &[u8] = include_bytes!("../encryptions/test_file.enc_oaep_2048_sha1");
+const enc_oaep_2048_sha256: &[u8] = include_bytes!("../encryptions/test_file.enc_oaep_2048_sha256");
+
+
+pub fn test_oaep_encryption() {
+    if ! test_oaep_1024_sha1(public_key_1024, private_key_1024, enc_oaep_1024_sha1) {
+        log::error!("OAEP encryption test for 1024 bit rsa key with sha1 failed");
+    } else {
+        log::info!("OAEP encryption test for 1024 bit rsa key with sha1 succeded");
+    }
+
+    if ! test_oaep_1024_sha256(public_key_1024, private_key_1024, enc_oaep_1024_sha256) {
+        log::error!("OAEP encryption test for 1024 bit rsa key with sha256 failed");
+    } else {
+        log::info!("OAEP encryption test for 1024 bit rsa key with sha256 succeded");
+    }
+
+    if ! test_oaep_2048_sha1(public_key_2048, private_key_2048, enc_oaep_2048_sha1) {
+        log::error!("OAEP encryption test for 2048 bit rsa key with sha1 failed");
+    } else {
+        log::info!("OAEP encryption test for 2048 bit rsa key with sha1 succeded");
+    }
+
+    if ! test_oaep_2048_sha256(public_key_2048, private_key_2048, enc_oaep_2048_sha256) {
+        log::error!("OAEP encryption test for 2048 bit rsa key with sha256 failed");
+    } else {
+        log::info!("OAEP encryption test for 2048 bit rsa key with sha256 succeded");
+    }
+
+    if ! test_oaep_label_mismatch() {
+        log::error!("Error check for Error::Decryption on OAEP label mismatch failed!");
+    } else {
+        log::info!("Error check for Error::Decryption on OAEP label mismatch succeeded!");
+    }
+
+    if ! test_oaep_message_too_long() {
+        log::error!("Error check for Error::MessageTooLong on OAEP encryption failed!");
+    } else {
+        log::info!("Error check for Error::MessageTooLong on OAEP encryption succeeded!");
+    }
+}
+
+
+macro_rules! implement_oaep_test {
+    ($name: ident, $key_size: ty, $hash: ty) => {
+        fn $name(public_key_der: &[u8], private_key_der: &[u8], openssl_ciphertext: &[u8]) -> bool {
+            let peripherals = unsafe { Peripherals::steal() };
+            let mut rng = Rng::new(peripherals.RNG);
+            let mut rsa = Rsa::new(peripherals.RSA, None);
+
+            let padding = Oaep::new(Hash::<$hash>::new(peripherals.SHA));
+
+            // Parse Pub key
+            let rsa_public_key = match RsaPublicKey::<$key_size>::new_from_der(public_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Public Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            // Parse Priv key
+            let rsa_private_key = match RsaPrivateKey::<$key_size>::new_from_der(private_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Private Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            // Encrypt the test file
+            let mut ciphertext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let ciphertext = match rsa_public_key.encrypt(&mut rsa, &mut rng, &padding, test_file, &mut ciphertext_buffer) {
+                Ok(c) => c,
+                Err(e) => {
+                    log::error!("Encryption failed with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            // Decrypt the encrypted file
+            let mut plaintext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let plaintext = match rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer) {
+                Ok(p) => p,
+                Err(e) => {
+                    log::error!("Decryption failed with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            if plaintext != test_file {
+                log::error!("Decrypted plaintext does not equal plaintext: \nplaintext:\t\t{:?}\ninitial_plaintext:\t\t{:?}", plaintext, test_file);
+                return false;
+            }
+
+            // Decrypt the openssl encrypted file
+            let mut plaintext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let plaintext = match rsa_private_key.decrypt(&mut rsa, &mut rng, &padding, openssl_ciphertext, &mut plaintext_buffer) {
+                Ok(p) => p,
+                Err(e) => {
+                    log::error!("Decryption of openssl ciphertext failed with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            if plaintext != test_file {
+                log::error!("Openssl decrypted plaintext does not equal plaintext: \nplaintext:\t\t{:?}\ninitial_plaintext:\t\t{:?}", plaintext, test_file);
+                return false;
+            }
+
+            true
+        }
+    };
+}
+
+implement_oaep_test!(test_oaep_1024_sha1, RsaKeySize1024, Esp32C3Sha1);
+implement_oaep_test!(test_oaep_1024_sha256, RsaKeySize1024, Esp32C3Sha256);
+implement_oaep_test!(test_oaep_2048_sha1, RsaKeySize2048, Esp32C3Sha1);
+implement_oaep_test!(test_oaep_2048_sha256, RsaKeySize2048, Esp32C3Sha256);
+
+
+fn test_oaep_label_mismatch() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize1024>::new_from_der(public_key_1024).unwrap();
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize1024>::new_from_der(private_key_1024).unwrap();
+
+    let padding = Oaep::new_with_label(Hash::<Esp32C3Sha256>::new(peripherals.SHA), b"label");
+
+    let mut ciphertext_buffer = [0u8; RsaKeySize1024::BLOCKSIZE];
+    let ciphertext = padding
+        .encrypt(&mut rsa, &mut rng, &rsa_public_key, test_file, &mut ciphertext_buffer)
+        .expect("Should be able to encrypt with public key");
+
+    // Decrypt with a different label -> should fail with Error::Decryption
+    let padding = Oaep::new_with_label(padding.into_inner(), b"other label");
+    let mut plaintext_buffer = [0u8; RsaKeySize1024::BLOCKSIZE];
+    match padding.decrypt(&mut rsa, &mut rng, &rsa_private_key, ciphertext, &mut plaintext_buffer) {
+        Ok(p) => {
+            log::error!("Decryption succeded but should have failed, with result: {:?}!", p);
+            false
+        },
+        Err(Error::Decryption) => true,
+        Err(e) => {
+            log::error!("Decryption failed with error: {:?}", e);
+            false
+        }
+    }
+}
+
+
+fn test_oaep_message_too_long() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize1024>::new_from_der(public_key_1024).unwrap();
+
+    let padding = Oaep::new(Hash::<Esp32C3Sha256>::new(peripherals.SHA));
+
+    // k - 2 * hLen - 2 = 62 bytes is the longest message for a 1024 bit key with sha256
+    let message = [0x42u8; RsaKeySize1024::BLOCKSIZE - 2 * Esp32C3Sha256::output_len - 1];
+    let mut ciphertext_buffer = [0u8; RsaKeySize1024::BLOCKSIZE];
+    match padding.encrypt(&mut rsa, &mut rng, &rsa_public_key, &message, &mut ciphertext_buffer) {
+        Ok(_) => {
+            log::error!("Encryption succeded but should have failed!");
+            false
+        },
+        Err(Error::MessageTooLong) => true,
+        Err(e) => {
+            log::error!("Encryption failed with error: {:?}", e);
+            false
+        }
+    }
+}
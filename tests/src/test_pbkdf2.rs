@@ -0,0 +1,77 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::sha::{Esp32C3Sha1, Esp32C3Sha256, Hash, HashAlgorithm},
+    kdf::pbkdf2::pbkdf2
+};
+use esp_hal::peripherals::Peripherals;
+
+
+// RFC 6070 test cases 1, 2, 3 and 5
+const pbkdf2_sha1_1: &[u8] = &[0x0c, 0x60, 0xc8, 0x0f, 0x96, 0x1f, 0x0e, 0x71, 0xf3, 0xa9, 0xb5, 0x24, 0xaf, 0x60, 0x12, 0x06, 0x2f, 0xe0, 0x37, 0xa6];
+const pbkdf2_sha1_2: &[u8] = &[0xea, 0x6c, 0x01, 0x4d, 0xc7, 0x2d, 0x6f, 0x8c, 0xcd, 0x1e, 0xd9, 0x2a, 0xce, 0x1d, 0x41, 0xf0, 0xd8, 0xde, 0x89, 0x57];
+const pbkdf2_sha1_4096: &[u8] = &[0x4b, 0x00, 0x79, 0x01, 0xb7, 0x65, 0x48, 0x9a, 0xbe, 0xad, 0x49, 0xd9, 0x26, 0xf7, 0x21, 0xd0, 0x65, 0xa4, 0x29, 0xc1];
+const pbkdf2_sha1_4096_long: &[u8] = &[0x3d, 0x2e, 0xec, 0x4f, 0xe4, 0x1c, 0x84, 0x9b, 0x80, 0xc8, 0xd8, 0x36, 0x62, 0xc0, 0xe4, 0x4a, 0x8b, 0x29, 0x1a, 0x96, 0x4c, 0xf2, 0xf0, 0x70, 0x38];
+
+const pbkdf2_sha256_1: &[u8] = &[0x12, 0x0f, 0xb6, 0xcf, 0xfc, 0xf8, 0xb3, 0x2c, 0x43, 0xe7, 0x22, 0x52, 0x56, 0xc4, 0xf8, 0x37, 0xa8, 0x65, 0x48, 0xc9, 0x2c, 0xcc, 0x35, 0x48, 0x08, 0x05, 0x98, 0x7c, 0xb7, 0x0b, 0xe1, 0x7b];
+const pbkdf2_sha256_4096: &[u8] = &[0xc5, 0xe4, 0x78, 0xd5, 0x92, 0x88, 0xc8, 0x41, 0xaa, 0x53, 0x0d, 0xb6, 0x84, 0x5c, 0x4c, 0x8d, 0x96, 0x28, 0x93, 0xa0, 0x01, 0xce, 0x4e, 0x11, 0xa4, 0x96, 0x38, 0x73, 0xaa, 0x98, 0x13, 0x4a];
+
+// IEEE 802.11i WPA2 PSK of passphrase "password" and ssid "IEEE"
+const wpa2_psk: &[u8] = &[0xf4, 0x2c, 0x6f, 0xc5, 0x2d, 0xf0, 0xeb, 0xef, 0x9e, 0xbb, 0x4b, 0x90, 0xb3, 0x8a, 0x5f, 0x90, 0x2e, 0x83, 0xfe, 0x1b, 0x13, 0x5a, 0x70, 0xe2, 0x3a, 0xed, 0x76, 0x2e, 0x97, 0x10, 0xa1, 0x2e];
+
+
+pub fn test_pbkdf2() {
+    let vectors: [(fn(&[u8], &[u8], u32, &[u8]) -> bool, &[u8], &[u8], u32, &[u8]); 7] = [
+        (test_pbkdf2_vector::<Esp32C3Sha1>, b"password", b"salt", 1, pbkdf2_sha1_1),
+        (test_pbkdf2_vector::<Esp32C3Sha1>, b"password", b"salt", 2, pbkdf2_sha1_2),
+        (test_pbkdf2_vector::<Esp32C3Sha1>, b"password", b"salt", 4096, pbkdf2_sha1_4096),
+        (test_pbkdf2_vector::<Esp32C3Sha1>, b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, pbkdf2_sha1_4096_long),
+        (test_pbkdf2_vector::<Esp32C3Sha256>, b"password", b"salt", 1, pbkdf2_sha256_1),
+        (test_pbkdf2_vector::<Esp32C3Sha256>, b"password", b"salt", 4096, pbkdf2_sha256_4096),
+        (test_pbkdf2_vector::<Esp32C3Sha1>, b"password", b"IEEE", 4096, wpa2_psk),
+    ];
+
+    for (test, password, salt, iterations, expected) in vectors {
+        if ! test(password, salt, iterations, expected) {
+            log::error!("PBKDF2 test with {} iterations failed", iterations);
+        } else {
+            log::info!("PBKDF2 test with {} iterations succeded", iterations);
+        }
+    }
+
+    if ! test_pbkdf2_zero_iterations() {
+        log::error!("Error check for Error::InvalidLength with zero iterations failed!");
+    } else {
+        log::info!("Error check for Error::InvalidLength with zero iterations succeeded!");
+    }
+}
+
+
+fn test_pbkdf2_vector<HA: HashAlgorithm>(password: &[u8], salt: &[u8], iterations: u32, expected: &[u8]) -> bool {
+    let mut hash = Hash::<HA>::new(unsafe { Peripherals::steal().SHA });
+
+    let mut out = [0u8; 32];
+    let out = &mut out[..expected.len()];
+    if let Err(e) = pbkdf2(&mut hash, password, salt, iterations, out) {
+        log::error!("PBKDF2 failed with error: {:?}", e);
+        return false;
+    }
+
+    if out != expected {
+        log::error!("Derived key does not match: \nderived: \t{:?}\nexpected: \t{:?}", out, expected);
+        return false;
+    }
+
+    true
+}
+
+fn test_pbkdf2_zero_iterations() -> bool {
+    let mut hash = Hash::<Esp32C3Sha256>::new(unsafe { Peripherals::steal().SHA });
+
+    match pbkdf2(&mut hash, b"password", b"salt", 0, &mut [0u8; 32]) {
+        Err(Error::InvalidLength) => true,
+        r => {
+            log::error!("PBKDF2 with zero iterations should fail with Error::InvalidLength, but returned: {:?}", r);
+            false
+        }
+    }
+}
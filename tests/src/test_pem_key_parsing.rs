@@ -0,0 +1,136 @@
+use esp_32c3_crypto::{
+    error::Error,
+    padding::pkcs1v15::Pkcs1v15Encrypt,
+    rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey}
+};
+use esp_hal::{peripherals::Peripherals, rng::Rng, rsa::Rsa};
+
+
+const public_key_1024_pem: &str = include_str!("../keys/public_key_1024.pem");
+const public_key_1024_pkcs1_pem: &str = include_str!("../keys/public_key_1024_pkcs1.pem");
+const private_key_1024_pem: &str = include_str!("../keys/private_key_1024.pem");
+const private_key_1024_pkcs8_pem: &str = include_str!("../keys/private_key_1024_pkcs8.pem");
+
+const public_key_2048_pem: &str = include_str!("../keys/public_key_2048.pem");
+const public_key_2048_pkcs1_pem: &str = include_str!("../keys/public_key_2048_pkcs1.pem");
+const private_key_2048_pem: &str = include_str!("../keys/private_key_2048.pem");
+const private_key_2048_pkcs8_pem: &str = include_str!("../keys/private_key_2048_pkcs8.pem");
+
+const public_key_1024_pem_crlf: &str = "-----BEGIN PUBLIC KEY-----\r\n\
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQCr0WsLSj5hWByaWb0AtD4AW5MM\r\n\
+FxN7hDXvDVMViuTFi8xlYM2cSnJlMk+5leSBMde+J4jxov7N9GWtQy9yvh4HxxHH\r\n\
+qJRMmL3nZj4MZM4W4dZNVfrOCZI+WwjKWIobCvuQO9T4TZ9PvaE3WrQmivCuIISW\r\n\
+TVG234Z9s2prlk5VuwIDAQAB\r\n\
+-----END PUBLIC KEY-----\r\n";
+
+
+pub fn test_pem_key_parsing() {
+    if ! test_pem_1024(public_key_1024_pem, private_key_1024_pem) {
+        log::error!("PEM parsing test for 1024 bit SubjectPublicKeyInfo and pkcs1 private key failed");
+    } else {
+        log::info!("PEM parsing test for 1024 bit SubjectPublicKeyInfo and pkcs1 private key succeded");
+    }
+
+    if ! test_pem_1024(public_key_1024_pkcs1_pem, private_key_1024_pkcs8_pem) {
+        log::error!("PEM parsing test for 1024 bit pkcs1 public key and pkcs8 private key failed");
+    } else {
+        log::info!("PEM parsing test for 1024 bit pkcs1 public key and pkcs8 private key succeded");
+    }
+
+    if ! test_pem_1024(public_key_1024_pem_crlf, private_key_1024_pem) {
+        log::error!("PEM parsing test for 1024 bit public key with CRLF line breaks failed");
+    } else {
+        log::info!("PEM parsing test for 1024 bit public key with CRLF line breaks succeded");
+    }
+
+    if ! test_pem_2048(public_key_2048_pem, private_key_2048_pem) {
+        log::error!("PEM parsing test for 2048 bit SubjectPublicKeyInfo and pkcs1 private key failed");
+    } else {
+        log::info!("PEM parsing test for 2048 bit SubjectPublicKeyInfo and pkcs1 private key succeded");
+    }
+
+    if ! test_pem_2048(public_key_2048_pkcs1_pem, private_key_2048_pkcs8_pem) {
+        log::error!("PEM parsing test for 2048 bit pkcs1 public key and pkcs8 private key failed");
+    } else {
+        log::info!("PEM parsing test for 2048 bit pkcs1 public key and pkcs8 private key succeded");
+    }
+
+    if ! test_pem_label_mismatch() {
+        log::error!("Error check for Error::UnexpectedPemLabel failed!");
+    } else {
+        log::info!("Error check for Error::UnexpectedPemLabel succeeded!");
+    }
+}
+
+
+macro_rules! implement_pem_test {
+    ($name: ident, $key_size: ty) => {
+        fn $name(public_key_pem: &str, private_key_pem: &str) -> bool {
+            let peripherals = unsafe { Peripherals::steal() };
+            let mut rsa = Rsa::new(peripherals.RSA, None);
+            let mut rng = Rng::new(peripherals.RNG);
+
+            let rsa_public_key = match RsaPublicKey::<$key_size>::new_from_pem(public_key_pem) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Public Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let rsa_private_key = match RsaPrivateKey::<$key_size>::new_from_pem(private_key_pem) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Private Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let padding = Pkcs1v15Encrypt;
+
+            let initialtext = "hello".as_bytes();
+
+            let mut ciphertext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let ciphertext = rsa_public_key
+                .encrypt(&mut rsa, &mut rng, &padding, initialtext, &mut ciphertext_buffer)
+                .expect("Should be able to encrpyt with public key");
+
+            let mut plaintext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let plaintext = rsa_private_key
+                .decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer)
+                .expect("Should be able to decrypt with private key");
+
+            if plaintext != initialtext {
+                log::error!("initialtext and plaintext do not match: \ninitialtext: \t{:?}\nplaintext: \t{:?}", initialtext, plaintext);
+                return false;
+            }
+
+            true
+        }
+    };
+}
+
+implement_pem_test!(test_pem_1024, RsaKeySize1024);
+implement_pem_test!(test_pem_2048, RsaKeySize2048);
+
+
+fn test_pem_label_mismatch() -> bool {
+    // A private key pem passed as public key reports the label of the private key
+    match RsaPublicKey::<RsaKeySize1024>::new_from_pem(private_key_1024_pem) {
+        Err(Error::UnexpectedPemLabel(label)) if label == "RSA PRIVATE KEY" => {},
+        r => {
+            log::error!("Parsing a private key pem as public key should fail with Error::UnexpectedPemLabel, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    match RsaPrivateKey::<RsaKeySize1024>::new_from_pem(public_key_1024_pem) {
+        Err(Error::UnexpectedPemLabel(label)) if label == "PUBLIC KEY" => {},
+        r => {
+            log::error!("Parsing a public key pem as private key should fail with Error::UnexpectedPemLabel, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}
@@ -0,0 +1,217 @@
+use esp_32c3_crypto::{
+    error::Error,
+    padding::pkcs1v15::Pkcs1v15Encrypt,
+    rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey}
+};
+use esp_hal::{peripherals::Peripherals, rng::Rng, rsa::Rsa};
+
+
+const public_key_1024: &[u8] = include_bytes!("../keys/public_key_1024.der");
+const public_key_1024_pkcs1: &[u8] = include_bytes!("../keys/public_key_1024_pkcs1.der");
+const private_key_1024: &[u8] = include_bytes!("../keys/private_key_1024.der");
+const private_key_1024_pkcs1: &[u8] = include_bytes!("../keys/private_key_1024_pkcs1.der");
+
+const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
+const public_key_2048_pkcs1: &[u8] = include_bytes!("../keys/public_key_2048_pkcs1.der");
+const private_key_2048: &[u8] = include_bytes!("../keys/private_key_2048.der");
+const private_key_2048_pkcs1: &[u8] = include_bytes!("../keys/private_key_2048_pkcs1.der");
+
+const public_key_ec: &[u8] = include_bytes!("../keys/public_key_ec.der");
+const private_key_ec: &[u8] = include_bytes!("../keys/private_key_ec.der");
+
+
+pub fn test_pkcs1_key_parsing() {
+    if ! test_pkcs1_1024(public_key_1024_pkcs1, private_key_1024_pkcs1) {
+        log::error!("pkcs1 der parsing test for 1024 bit rsa key failed");
+    } else {
+        log::info!("pkcs1 der parsing test for 1024 bit rsa key succeded");
+    }
+
+    if ! test_pkcs1_2048(public_key_2048_pkcs1, private_key_2048_pkcs1) {
+        log::error!("pkcs1 der parsing test for 2048 bit rsa key failed");
+    } else {
+        log::info!("pkcs1 der parsing test for 2048 bit rsa key succeded");
+    }
+
+    if ! test_der_any_1024(public_key_1024, public_key_1024_pkcs1, private_key_1024, private_key_1024_pkcs1) {
+        log::error!("Format detection test for 1024 bit rsa key failed");
+    } else {
+        log::info!("Format detection test for 1024 bit rsa key succeded");
+    }
+
+    if ! test_der_any_2048(public_key_2048, public_key_2048_pkcs1, private_key_2048, private_key_2048_pkcs1) {
+        log::error!("Format detection test for 2048 bit rsa key failed");
+    } else {
+        log::info!("Format detection test for 2048 bit rsa key succeded");
+    }
+
+    if ! test_unsupported_algorithm() {
+        log::error!("Error check for Error::UnsupportedAlgorithm failed!");
+    } else {
+        log::info!("Error check for Error::UnsupportedAlgorithm succeeded!");
+    }
+
+    if ! test_oversized_exponent() {
+        log::error!("Error check for an oversized public exponent failed!");
+    } else {
+        log::info!("Error check for an oversized public exponent succeeded!");
+    }
+
+    if ! test_inconsistent_crt_parts(private_key_1024_pkcs1) {
+        log::error!("Error check for inconsistent crt components failed!");
+    } else {
+        log::info!("Error check for inconsistent crt components succeeded!");
+    }
+}
+
+
+macro_rules! implement_pkcs1_test {
+    ($name: ident, $key_size: ty) => {
+        fn $name(public_key_der: &[u8], private_key_der: &[u8]) -> bool {
+            let peripherals = unsafe { Peripherals::steal() };
+            let mut rsa = Rsa::new(peripherals.RSA, None);
+            let mut rng = Rng::new(peripherals.RNG);
+
+            let rsa_public_key = match RsaPublicKey::<$key_size>::from_pkcs1_der(public_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Public Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let rsa_private_key = match RsaPrivateKey::<$key_size>::from_pkcs1_der(private_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Private Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            let padding = Pkcs1v15Encrypt;
+
+            let initialtext = "hello".as_bytes();
+
+            let mut ciphertext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let ciphertext = rsa_public_key
+                .encrypt(&mut rsa, &mut rng, &padding, initialtext, &mut ciphertext_buffer)
+                .expect("Should be able to encrpyt with public key");
+
+            let mut plaintext_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let plaintext = rsa_private_key
+                .decrypt(&mut rsa, &mut rng, &padding, ciphertext, &mut plaintext_buffer)
+                .expect("Should be able to decrypt with private key");
+
+            if plaintext != initialtext {
+                log::error!("initialtext and plaintext do not match: \ninitialtext: \t{:?}\nplaintext: \t{:?}", initialtext, plaintext);
+                return false;
+            }
+
+            true
+        }
+    };
+}
+
+implement_pkcs1_test!(test_pkcs1_1024, RsaKeySize1024);
+implement_pkcs1_test!(test_pkcs1_2048, RsaKeySize2048);
+
+
+macro_rules! implement_der_any_test {
+    ($name: ident, $key_size: ty) => {
+        fn $name(public_key_spki: &[u8], public_key_pkcs1: &[u8], private_key_pkcs8: &[u8], private_key_pkcs1: &[u8]) -> bool {
+            let mut buffer = [0u8; 4096];
+
+            // Both public key formats result in the same key
+            for public_key_der in [public_key_spki, public_key_pkcs1] {
+                match RsaPublicKey::<$key_size>::from_der_any(public_key_der)
+                    .and_then(|k| k.to_public_key_der(&mut buffer).map(|der| der == public_key_spki))
+                {
+                    Ok(true) => {},
+                    r => {
+                        log::error!("Detected public key does not match the SubjectPublicKeyInfo: {:?}", r);
+                        return false;
+                    }
+                }
+            }
+
+            // Both private key formats result in the same key
+            for private_key_der in [private_key_pkcs8, private_key_pkcs1] {
+                match RsaPrivateKey::<$key_size>::from_der_any(private_key_der)
+                    .and_then(|k| k.to_pkcs8_der(&mut buffer).map(|der| der == private_key_pkcs8))
+                {
+                    Ok(true) => {},
+                    r => {
+                        log::error!("Detected private key does not match the PrivateKeyInfo: {:?}", r);
+                        return false;
+                    }
+                }
+            }
+
+            true
+        }
+    };
+}
+
+implement_der_any_test!(test_der_any_1024, RsaKeySize1024);
+implement_der_any_test!(test_der_any_2048, RsaKeySize2048);
+
+
+fn test_unsupported_algorithm() -> bool {
+    // id-ecPublicKey
+    let ec_oid = spki::ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+
+    match RsaPublicKey::<RsaKeySize1024>::from_der_any(public_key_ec) {
+        Err(Error::UnsupportedAlgorithm(oid)) if oid == ec_oid => {},
+        r => {
+            log::error!("Parsing an ec public key should fail with Error::UnsupportedAlgorithm, but returned: {:?}", r.err());
+            return false;
+        }
+    }
+
+    match RsaPrivateKey::<RsaKeySize1024>::from_der_any(private_key_ec) {
+        Err(Error::UnsupportedAlgorithm(oid)) if oid == ec_oid => {},
+        r => {
+            log::error!("Parsing an ec private key should fail with Error::UnsupportedAlgorithm, but returned: {:?}", r.err());
+            return false;
+        }
+    }
+
+    true
+}
+
+fn test_oversized_exponent() -> bool {
+    // RSAPublicKey with a 1024 bit modulus and a 130 byte public exponent, which does not fit into a block
+    let mut der = [0u8; 269];
+    der[..7].copy_from_slice(&[0x30, 0x82, 0x01, 0x09, 0x02, 0x81, 0x81]);
+    der[8] = 0xc0;
+    der[135] = 0x01;
+    der[136..140].copy_from_slice(&[0x02, 0x81, 0x82, 0x01]);
+    der[268] = 0x01;
+
+    match RsaPublicKey::<RsaKeySize1024>::from_der_any(&der) {
+        Err(Error::RsaKeySizeError) => true,
+        r => {
+            log::error!("Parsing a public key with an oversized exponent should fail with Error::RsaKeySizeError, but returned: {:?}", r.err());
+            false
+        }
+    }
+}
+
+fn test_inconsistent_crt_parts(private_key_pkcs1: &[u8]) -> bool {
+    // Offsets of the last byte of the modulus, exponent1 and coefficient in the 1024 bit RSAPrivateKey
+    for (index, name) in [(138, "modulus"), (475, "exponent1"), (608, "coefficient")] {
+        let mut der = [0u8; 609];
+        der.copy_from_slice(private_key_pkcs1);
+        der[index] ^= 0x02;
+
+        match RsaPrivateKey::<RsaKeySize1024>::from_pkcs1_der(&der) {
+            Err(Error::InvalidEncoding) => {},
+            r => {
+                log::error!("Parsing a private key with a modified {} should fail with Error::InvalidEncoding, but returned: {:?}", name, r.err());
+                return false;
+            }
+        }
+    }
+
+    true
+}
@@ -0,0 +1,275 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::sha::{Esp32C3Sha1, Esp32C3Sha256, Hash, HashAlgorithm},
+    padding::pss::{Pss, PssSaltLength},
+    rsa::{RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey},
+    traits::{MessageSignatureScheme, SignatureScheme}
+};
+use esp_hal::{peripherals::Peripherals, rng::Rng, rsa::Rsa};
+
+const test_file: &[u8] = include_bytes!("../test_file.txt");
+
+const public_key_1024: &[u8] = include_bytes!("../keys/public_key_1024.der");
+const private_key_1024: &[u8] = include_bytes!("../keys/private_key_1024.der");
+const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
+const private_key_2048: &[u8] = include_bytes!("../keys/private_key_2048.der");
+
+const test_file_sign_pss_1024_sha1: &[u8] = include_bytes!("../signatures/test_file.txt.sign_pss_1024_sha1");
+const test_file_sign_pss_1024_sha256: &[u8] = include_bytes!("../signatures/test_file.txt.sign_pss_1024_sha256");
+const test_file_sign_pss_2048_sha1: &[u8] = include_bytes!("../signatures/test_file.txt.sign_pss_2048_sha1");
+const test_file_sign_pss_2048_sha256: &[u8] = include_bytes!("../signatures/test_file.txt.sign_pss_2048_sha256");
+const test_file_sign_pss_max_2048_sha256: &[u8] = include_bytes!("../signatures/test_file.txt.sign_pss_max_2048_sha256");
+
+
+pub fn test_rsa_signature_pss() {
+    if ! test_rsa_signature_pss_1024_sha1(public_key_1024, private_key_1024, test_file_sign_pss_1024_sha1) {
+        log::error!("pss signature test for 1024 bit rsa key with sha1 failed");
+    } else {
+        log::info!("pss signature test for 1024 bit rsa key with sha1 succeded");
+    }
+
+    if ! test_rsa_signature_pss_1024_sha256(public_key_1024, private_key_1024, test_file_sign_pss_1024_sha256) {
+        log::error!("pss signature test for 1024 bit rsa key with sha256 failed");
+    } else {
+        log::info!("pss signature test for 1024 bit rsa key with sha256 succeded");
+    }
+
+    if ! test_rsa_signature_pss_2048_sha1(public_key_2048, private_key_2048, test_file_sign_pss_2048_sha1) {
+        log::error!("pss signature test for 2048 bit rsa key with sha1 failed");
+    } else {
+        log::info!("pss signature test for 2048 bit rsa key with sha1 succeded");
+    }
+
+    if ! test_rsa_signature_pss_2048_sha256(public_key_2048, private_key_2048, test_file_sign_pss_2048_sha256) {
+        log::error!("pss signature test for 2048 bit rsa key with sha256 failed");
+    } else {
+        log::info!("pss signature test for 2048 bit rsa key with sha256 succeded");
+    }
+
+    if ! test_rsa_signature_pss_auto_salt_len() {
+        log::error!("pss signature test with automatic salt length failed");
+    } else {
+        log::info!("pss signature test with automatic salt length succeded");
+    }
+
+    if ! test_rsa_signature_pss_sign_message() {
+        log::error!("pss sign_message and verify_message test failed");
+    } else {
+        log::info!("pss sign_message and verify_message test succeded");
+    }
+
+    if ! test_rsa_signature_pss_oversized_salt_len() {
+        log::error!("pss signature test with oversized salt length failed");
+    } else {
+        log::info!("pss signature test with oversized salt length succeded");
+    }
+}
+
+
+macro_rules! implement_pss_test {
+    ($name: ident, $key_size: ty, $hash: ty) => {
+        fn $name(public_key_der: &[u8], private_key_der: &[u8], openssl_signature: &[u8]) -> bool {
+            let peripherals = unsafe { Peripherals::steal() };
+            let rng = Rng::new(peripherals.RNG);
+            let mut rsa = Rsa::new(peripherals.RSA, None);
+
+            let mut hash = Hash::<$hash>::new(unsafe { Peripherals::steal() }.SHA);
+
+            // Parse Pub key
+            let rsa_public_key = match RsaPublicKey::<$key_size>::new_from_der(public_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Public Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            // Parse Priv key
+            let rsa_private_key = match RsaPrivateKey::<$key_size>::new_from_der(private_key_der) {
+                Ok(k) => k,
+                Err(e) => {
+                    log::error!("Failed to parse Private Key with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            // Hash the test file
+            let mut digest_buffer = [0u8; <$hash>::output_len];
+            let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+            // Create Signature scheme
+            let scheme = Pss::new(Hash::<$hash>::new(peripherals.SHA));
+
+            // Create the Signature
+            let mut signature_buffer = [0u8; <$key_size>::BLOCKSIZE];
+            let signature = match scheme.sign(&rsa_private_key, rng, &mut rsa, digest, &mut signature_buffer) {
+                Ok(sig) => sig,
+                Err(e) => {
+                    log::error!("Failed to create signature with error: {:?}", e);
+                    return false;
+                }
+            };
+
+            // Verify Openssl Signature
+            if let Err(e) = scheme.verify(&rsa_public_key, &mut rsa, digest, openssl_signature) {
+                log::error!("Failed to verify Openssl signature with error: {:?}", e);
+                return false;
+            }
+
+            // Verify Esp32c3 Crypto Signature
+            if let Err(e) = scheme.verify(&rsa_public_key, &mut rsa, digest, signature) {
+                log::error!("Failed to verify Esp32c3Crypto signature with error: {:?}", e);
+                return false;
+            }
+
+            // A modified digest must not verify
+            let mut modified_digest = [0u8; <$hash>::output_len];
+            modified_digest.copy_from_slice(digest);
+            modified_digest[0] ^= 1;
+            match scheme.verify(&rsa_public_key, &mut rsa, &modified_digest, signature) {
+                Err(Error::Verification) => {},
+                r => {
+                    log::error!("Verification of a modified digest should fail with Error::Verification, but returned: {:?}", r);
+                    return false;
+                }
+            }
+
+            true
+        }
+    };
+}
+
+implement_pss_test!(test_rsa_signature_pss_1024_sha1, RsaKeySize1024, Esp32C3Sha1);
+implement_pss_test!(test_rsa_signature_pss_1024_sha256, RsaKeySize1024, Esp32C3Sha256);
+implement_pss_test!(test_rsa_signature_pss_2048_sha1, RsaKeySize2048, Esp32C3Sha1);
+implement_pss_test!(test_rsa_signature_pss_2048_sha256, RsaKeySize2048, Esp32C3Sha256);
+
+
+fn test_rsa_signature_pss_auto_salt_len() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+
+    let mut hash = Hash::<Esp32C3Sha256>::new(unsafe { Peripherals::steal() }.SHA);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048).unwrap();
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+
+    let mut digest_buffer = [0u8; Esp32C3Sha256::output_len];
+    let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+    // The openssl signature uses the maximum salt length, which fails with the digest salt length
+    let scheme = Pss::new(Hash::<Esp32C3Sha256>::new(peripherals.SHA));
+    if let Ok(_) = scheme.verify(&rsa_public_key, &mut rsa, digest, test_file_sign_pss_max_2048_sha256) {
+        log::error!("Verification with a mismatched salt length succeded but should have failed!");
+        return false;
+    }
+
+    let scheme = Pss::new_with_salt_len(scheme.into_inner(), PssSaltLength::Auto);
+    if let Err(e) = scheme.verify(&rsa_public_key, &mut rsa, digest, test_file_sign_pss_max_2048_sha256) {
+        log::error!("Failed to verify Openssl signature with automatic salt length with error: {:?}", e);
+        return false;
+    }
+
+    // Signing with automatic salt length uses the maximum salt length
+    let mut signature_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
+    let signature = match scheme.sign(&rsa_private_key, rng, &mut rsa, digest, &mut signature_buffer) {
+        Ok(sig) => sig,
+        Err(e) => {
+            log::error!("Failed to create signature with error: {:?}", e);
+            return false;
+        }
+    };
+
+    let scheme = Pss::new_with_salt_len(
+        scheme.into_inner(),
+        PssSaltLength::Fixed(RsaKeySize2048::BLOCKSIZE - Esp32C3Sha256::output_len - 2)
+    );
+    if let Err(e) = scheme.verify(&rsa_public_key, &mut rsa, digest, signature) {
+        log::error!("Failed to verify Esp32c3Crypto signature with maximum salt length with error: {:?}", e);
+        return false;
+    }
+
+    true
+}
+
+
+fn test_rsa_signature_pss_sign_message() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048).unwrap();
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+
+    // The scheme hashes the message with its own hasher
+    let scheme = Pss::new(Hash::<Esp32C3Sha256>::new(peripherals.SHA));
+
+    // Verify Openssl Signature of the message
+    if let Err(e) = rsa_public_key.verify_message(&mut rsa, &scheme, test_file, test_file_sign_pss_2048_sha256) {
+        log::error!("Failed to verify Openssl signature of the message with error: {:?}", e);
+        return false;
+    }
+
+    let mut signature_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
+    let signature = match rsa_private_key.sign_message(rng, &mut rsa, &scheme, test_file, &mut signature_buffer) {
+        Ok(sig) => sig,
+        Err(e) => {
+            log::error!("Failed to sign the message with error: {:?}", e);
+            return false;
+        }
+    };
+
+    if let Err(e) = scheme.verify_message(&rsa_public_key, &mut rsa, test_file, signature) {
+        log::error!("Failed to verify Esp32c3Crypto signature of the message with error: {:?}", e);
+        return false;
+    }
+
+    // The signature does not verify for another message
+    match scheme.verify_message(&rsa_public_key, &mut rsa, &test_file[1..], signature) {
+        Err(Error::Verification) => {},
+        r => {
+            log::error!("Verification of a modified message should fail with Error::Verification, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}
+
+
+fn test_rsa_signature_pss_oversized_salt_len() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+
+    let mut hash = Hash::<Esp32C3Sha256>::new(unsafe { Peripherals::steal() }.SHA);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048).unwrap();
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+
+    let mut digest_buffer = [0u8; Esp32C3Sha256::output_len];
+    let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+    // The length checks must not overflow for a salt length close to usize::MAX
+    let scheme = Pss::new_with_salt_len(Hash::<Esp32C3Sha256>::new(peripherals.SHA), PssSaltLength::Fixed(usize::MAX));
+
+    let mut signature_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
+    match scheme.sign(&rsa_private_key, rng, &mut rsa, digest, &mut signature_buffer) {
+        Err(Error::MessageTooLong) => {},
+        r => {
+            log::error!("Signing with salt length usize::MAX should fail with Error::MessageTooLong, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    match scheme.verify(&rsa_public_key, &mut rsa, digest, test_file_sign_pss_2048_sha256) {
+        Err(Error::InvalidLength) => {},
+        r => {
+            log::error!("Verification with salt length usize::MAX should fail with Error::InvalidLength, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}
@@ -1,12 +1,13 @@
 use esp_32c3_crypto::{
+    error::Error,
     hash::sha::{
-        Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, Hash, HashAlgorithm
+        Esp32C3Sha1, Esp32C3Sha224, Esp32C3Sha256, Hash, HashAlgorithm, HashFunction, Sha384, Sha512
     },
-    padding::pkcs1v15::Pkcs1v15Sign,
+    padding::pkcs1v15::{Pkcs1v15Sign, Pkcs1v15SignWithHash},
     rsa::{
-        RsaKey, RsaKeySize1024, RsaKeySize2048, RsaPrivateKey, RsaPublicKey
+        RsaKey, RsaKeySize1024, RsaKeySize2048, RsaKeySize3072, RsaKeySize4096, RsaPrivateKey, RsaPublicKey
     },
-    traits::SignatureScheme
+    traits::{MessageSignatureScheme, SignatureScheme}
 };
 use esp_hal::{peripherals::Peripherals, rng::Rng, rsa::Rsa};
 
@@ -49,6 +50,36 @@ pub fn test_rsa_signature_pkcs1v15() {
     } else {
         log::info!("pkcs1v15 signature test for 2048 bit rsa key with sha256 succeded");
     };
+
+    if ! test_rsa_signature_pkcs1v15_2048_sha384() {
+        log::error!("pkcs1v15 signature test for 2048 bit rsa key with sha384 failed");
+    } else {
+        log::info!("pkcs1v15 signature test for 2048 bit rsa key with sha384 succeded");
+    };
+
+    if ! test_rsa_signature_pkcs1v15_2048_sha512() {
+        log::error!("pkcs1v15 signature test for 2048 bit rsa key with sha512 failed");
+    } else {
+        log::info!("pkcs1v15 signature test for 2048 bit rsa key with sha512 succeded");
+    };
+
+    if ! test_rsa_signature_pkcs1v15_sign_message() {
+        log::error!("pkcs1v15 sign_message and verify_message test failed");
+    } else {
+        log::info!("pkcs1v15 sign_message and verify_message test succeded");
+    };
+
+    if ! test_rsa_signature_pkcs1v15_3072_sha256() {
+        log::error!("pkcs1v15 signature test for 3072 bit rsa key with sha256 failed");
+    } else {
+        log::info!("pkcs1v15 signature test for 3072 bit rsa key with sha256 succeded");
+    };
+
+    if ! test_rsa_signature_pkcs1v15_4096_sha256() {
+        log::error!("pkcs1v15 signature test for 4096 bit rsa key with sha256 failed");
+    } else {
+        log::info!("pkcs1v15 signature test for 4096 bit rsa key with sha256 succeded");
+    };
 }
 
 // ########
@@ -280,6 +311,359 @@ pub fn test_rsa_signature_pkcs1v15_2048_sha256() -> bool {
 
 }
 
+const test_file_sign_2048_sha384: &[u8] = include_bytes!("../signatures/test_file.txt.sign_2048_sha384");
+
+pub fn test_rsa_signature_pkcs1v15_2048_sha384() -> bool {
+
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+
+    let rsa = peripherals.RSA;
+    let mut rsa = Rsa::new(rsa, None);
+
+    let mut hash = Hash::<Sha384>::new(peripherals.SHA);
+
+    // Parse Pub key
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 1024 Byte Public Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_public_key = rsa_public_key.unwrap();
+
+    // Parse Priv key
+    let rsa_private_key= RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to Parse 1024 Byte Private Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_private_key= rsa_private_key.unwrap();
+
+    // Creat Signature scheme
+    let scheme = Pkcs1v15Sign::new::<Sha384>();
+
+    // Hash the test file
+    let mut digest_buffer = [0u8; Sha384::OUTPUT_LEN];
+    let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+    // Create the Signature
+    let mut signature_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
+    let signature = scheme
+        .sign(&rsa_private_key, rng, &mut rsa, &digest, &mut signature_buffer);
+
+    // Unpack the signature
+    let signature = match signature {
+        Err(e) => {
+            log::error!("Failed to create signature with error: {:?}", e);
+            return false;
+        },
+        Ok(sig) => sig,
+    };
+
+    // Compare Signature to openssl version
+    for (i, &b) in signature.iter().enumerate() {
+        if test_file_sign_2048_sha384[i] != b {
+            log::error!("Openssl Signature does not match Esp32c3Crypto Signature at position: {i}");
+            return false;
+        }
+    }
+
+    // Verify Openssl Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, test_file_sign_2048_sha384);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Openssl signature!");
+        return false;
+    }
+
+    // Verify Esp32c3 Crypto Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, signature);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Esp32c3Crypto signature!");
+        return false;
+    }
+
+    true
+
+}
+
+const test_file_sign_2048_sha512: &[u8] = include_bytes!("../signatures/test_file.txt.sign_2048_sha512");
+
+pub fn test_rsa_signature_pkcs1v15_2048_sha512() -> bool {
+
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+
+    let rsa = peripherals.RSA;
+    let mut rsa = Rsa::new(rsa, None);
+
+    let mut hash = Hash::<Sha512>::new(peripherals.SHA);
+
+    // Parse Pub key
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 1024 Byte Public Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_public_key = rsa_public_key.unwrap();
+
+    // Parse Priv key
+    let rsa_private_key= RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to Parse 1024 Byte Private Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_private_key= rsa_private_key.unwrap();
+
+    // Creat Signature scheme
+    let scheme = Pkcs1v15Sign::new::<Sha512>();
+
+    // Hash the test file
+    let mut digest_buffer = [0u8; Sha512::OUTPUT_LEN];
+    let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+    // Create the Signature
+    let mut signature_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
+    let signature = scheme
+        .sign(&rsa_private_key, rng, &mut rsa, &digest, &mut signature_buffer);
+
+    // Unpack the signature
+    let signature = match signature {
+        Err(e) => {
+            log::error!("Failed to create signature with error: {:?}", e);
+            return false;
+        },
+        Ok(sig) => sig,
+    };
+
+    // Compare Signature to openssl version
+    for (i, &b) in signature.iter().enumerate() {
+        if test_file_sign_2048_sha512[i] != b {
+            log::error!("Openssl Signature does not match Esp32c3Crypto Signature at position: {i}");
+            return false;
+        }
+    }
+
+    // Verify Openssl Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, test_file_sign_2048_sha512);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Openssl signature!");
+        return false;
+    }
+
+    // Verify Esp32c3 Crypto Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, signature);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Esp32c3Crypto signature!");
+        return false;
+    }
+
+    true
+
+}
+
+pub fn test_rsa_signature_pkcs1v15_sign_message() -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048).unwrap();
+    let rsa_private_key = RsaPrivateKey::<RsaKeySize2048>::new_from_der(private_key_2048).unwrap();
+
+    // The scheme hashes the message with the algorithm of its prefix
+    let scheme = Pkcs1v15SignWithHash::new(Hash::<Esp32C3Sha256>::new(peripherals.SHA));
+    let mut signature_buffer = [0u8; RsaKeySize2048::BLOCKSIZE];
+    let signature = match rsa_private_key.sign_message(rng, &mut rsa, &scheme, test_file, &mut signature_buffer) {
+        Ok(sig) => sig,
+        Err(e) => {
+            log::error!("Failed to sign the message with error: {:?}", e);
+            return false;
+        }
+    };
+
+    if signature != test_file_sign_2048_sha256 {
+        log::error!("Openssl Signature does not match Esp32c3Crypto Signature of the message");
+        return false;
+    }
+
+    if let Err(e) = rsa_public_key.verify_message(&mut rsa, &scheme, test_file, test_file_sign_2048_sha256) {
+        log::error!("Failed to verify Openssl signature of the message with error: {:?}", e);
+        return false;
+    }
+
+    // The signature does not verify for another message
+    match scheme.verify_message(&rsa_public_key, &mut rsa, &test_file[1..], test_file_sign_2048_sha256) {
+        Err(Error::Verification) => {},
+        r => {
+            log::error!("Verification of a modified message should fail with Error::Verification, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}
+
+// ########
+// # 3072 #
+// ########
+const public_key_3072: &[u8] = include_bytes!("../keys/public_key_3072.der");
+const private_key_3072: &[u8] = include_bytes!("../keys/private_key_3072.der");
+
+const test_file_sign_3072_sha256: &[u8] = include_bytes!("../signatures/test_file.txt.sign_3072_sha256");
+
+pub fn test_rsa_signature_pkcs1v15_3072_sha256() -> bool {
+
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+
+    let rsa = peripherals.RSA;
+    let mut rsa = Rsa::new(rsa, None);
+
+    let mut hash = Hash::<Esp32C3Sha256>::new(peripherals.SHA);
+
+    // Parse Pub key
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize3072>::new_from_der(public_key_3072);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 3072 Byte Public Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_public_key = rsa_public_key.unwrap();
+
+    // Parse Priv key
+    let rsa_private_key= RsaPrivateKey::<RsaKeySize3072>::new_from_der(private_key_3072);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to Parse 3072 Byte Private Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_private_key= rsa_private_key.unwrap();
+
+    // Creat Signature scheme
+    let scheme = Pkcs1v15Sign::new::<Esp32C3Sha256>();
+
+    // Hash the test file
+    let mut digest_buffer = [0u8; Esp32C3Sha256::output_len];
+    let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+    // Create the Signature
+    let mut signature_buffer = [0u8; RsaKeySize3072::BLOCKSIZE];
+    let signature = scheme
+        .sign(&rsa_private_key, rng, &mut rsa, &digest, &mut signature_buffer);
+
+    // Unpack the signature
+    let signature = match signature {
+        Err(e) => {
+            log::error!("Failed to create signature with error: {:?}", e);
+            return false;
+        },
+        Ok(sig) => sig,
+    };
+
+    // Compare Signature to openssl version
+    for (i, &b) in signature.iter().enumerate() {
+        if test_file_sign_3072_sha256[i] != b {
+            log::error!("Openssl Signature does not match Esp32c3Crypto Signature at position: {i}");
+            return false;
+        }
+    }
+
+    // Verify Openssl Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, test_file_sign_3072_sha256);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Openssl signature!");
+        return false;
+    }
+
+    // Verify Esp32c3 Crypto Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, signature);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Esp32c3Crypto signature!");
+        return false;
+    }
+
+    true
+
+}
+
+// ########
+// # 4096 #
+// ########
+const public_key_4096: &[u8] = include_bytes!("../keys/public_key_4096.der");
+const private_key_4096: &[u8] = include_bytes!("../keys/private_key_4096.der");
+
+const test_file_sign_4096_sha256: &[u8] = include_bytes!("../signatures/test_file.txt.sign_4096_sha256");
+
+pub fn test_rsa_signature_pkcs1v15_4096_sha256() -> bool {
+
+    let peripherals = unsafe { Peripherals::steal() };
+    let rng = Rng::new(peripherals.RNG);
+
+    let rsa = peripherals.RSA;
+    let mut rsa = Rsa::new(rsa, None);
+
+    let mut hash = Hash::<Esp32C3Sha256>::new(peripherals.SHA);
+
+    // Parse Pub key
+    let rsa_public_key  = RsaPublicKey::<RsaKeySize4096>::new_from_der(public_key_4096);
+    if let Err(e) = rsa_public_key {
+            log::error!("Failed to parse 4096 Byte Public Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_public_key = rsa_public_key.unwrap();
+
+    // Parse Priv key
+    let rsa_private_key= RsaPrivateKey::<RsaKeySize4096>::new_from_der(private_key_4096);
+    if let Err(e) = rsa_private_key {
+            log::error!("Failed to Parse 4096 Byte Private Key with error: {:?}", e);
+            return false;
+    }
+    let rsa_private_key= rsa_private_key.unwrap();
+
+    // Creat Signature scheme
+    let scheme = Pkcs1v15Sign::new::<Esp32C3Sha256>();
+
+    // Hash the test file
+    let mut digest_buffer = [0u8; Esp32C3Sha256::output_len];
+    let digest = hash.hash(test_file, &mut digest_buffer).unwrap();
+
+    // Create the Signature
+    let mut signature_buffer = [0u8; RsaKeySize4096::BLOCKSIZE];
+    let signature = scheme
+        .sign(&rsa_private_key, rng, &mut rsa, &digest, &mut signature_buffer);
+
+    // Unpack the signature
+    let signature = match signature {
+        Err(e) => {
+            log::error!("Failed to create signature with error: {:?}", e);
+            return false;
+        },
+        Ok(sig) => sig,
+    };
+
+    // Compare Signature to openssl version
+    for (i, &b) in signature.iter().enumerate() {
+        if test_file_sign_4096_sha256[i] != b {
+            log::error!("Openssl Signature does not match Esp32c3Crypto Signature at position: {i}");
+            return false;
+        }
+    }
+
+    // Verify Openssl Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, test_file_sign_4096_sha256);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Openssl signature!");
+        return false;
+    }
+
+    // Verify Esp32c3 Crypto Signature
+    let verification_result = scheme.verify(&rsa_public_key, &mut rsa, digest, signature);
+    if let Err(e) = verification_result {
+        log::error!("Failed to verify Esp32c3Crypto signature!");
+        return false;
+    }
+
+    true
+
+}
+
 // ########
 // # 1024 #
 // ########
@@ -0,0 +1,72 @@
+use esp_32c3_crypto::{
+    error::Error,
+    hash::sha::{Esp32C3Sha256, Hash},
+    padding::{pkcs1v15::Pkcs1v15SignWithHash, pss::Pss},
+    rsa::{RsaKeySize2048, RsaPublicKey},
+    traits::MessageSignatureScheme
+};
+use esp_hal::{peripherals::Peripherals, rsa::Rsa};
+
+const test_file: &[u8] = include_bytes!("../test_file.txt");
+
+const public_key_2048: &[u8] = include_bytes!("../keys/public_key_2048.der");
+
+const test_file_sign_2048_sha256: &[u8] = include_bytes!("../signatures/test_file.txt.sign_2048_sha256");
+const test_file_sign_pss_2048_sha256: &[u8] = include_bytes!("../signatures/test_file.txt.sign_pss_2048_sha256");
+
+
+pub fn test_verifier() {
+    let sha = unsafe { Peripherals::steal().SHA };
+    let scheme = Pkcs1v15SignWithHash::new(Hash::<Esp32C3Sha256>::new(sha));
+    if ! test_streaming_verification(&scheme, test_file_sign_2048_sha256) {
+        log::error!("Streaming pkcs1v15 signature verification failed");
+    } else {
+        log::info!("Streaming pkcs1v15 signature verification succeded");
+    }
+    drop(scheme);
+
+    let sha = unsafe { Peripherals::steal().SHA };
+    let scheme = Pss::new(Hash::<Esp32C3Sha256>::new(sha));
+    if ! test_streaming_verification(&scheme, test_file_sign_pss_2048_sha256) {
+        log::error!("Streaming pss signature verification failed");
+    } else {
+        log::info!("Streaming pss signature verification succeded");
+    }
+    drop(scheme);
+}
+
+/// Verifies the openssl signature of the test file passed in chunks of different sizes.
+fn test_streaming_verification<S: MessageSignatureScheme<RsaKeySize2048>>(scheme: &S, openssl_signature: &[u8]) -> bool {
+    let peripherals = unsafe { Peripherals::steal() };
+    let mut rsa = Rsa::new(peripherals.RSA, None);
+
+    let rsa_public_key = RsaPublicKey::<RsaKeySize2048>::new_from_der(public_key_2048).unwrap();
+
+    for chunk_size in [1, 5, 16, test_file.len()] {
+        let mut verifier = rsa_public_key.verifier(&mut rsa, scheme);
+
+        for chunk in test_file.chunks(chunk_size) {
+            verifier.update(chunk);
+        }
+
+        if let Err(e) = verifier.finalize(openssl_signature) {
+            log::error!("Failed to verify Openssl signature with chunk size {} with error: {:?}", chunk_size, e);
+            return false;
+        }
+    }
+
+    // A missing chunk must not verify
+    let mut verifier = rsa_public_key.verifier(&mut rsa, scheme);
+    for chunk in test_file.chunks(16).skip(1) {
+        verifier.update(chunk);
+    }
+    match verifier.finalize(openssl_signature) {
+        Err(Error::Verification) => {},
+        r => {
+            log::error!("Verification of a modified message should fail with Error::Verification, but returned: {:?}", r);
+            return false;
+        }
+    }
+
+    true
+}